@@ -38,6 +38,9 @@ async fn main() {
         .route("/api/binance/options/positions", post(get_options_positions))
         .route("/api/binance/futures/positions", post(get_futures_positions))
         .route("/api/binance/ticker/price", get(get_ticker_price))
+        .route("/api/binance/options/mark", get(get_option_mark))
+        .route("/api/binance/options/exchangeInfo", get(get_option_exchange_info))
+        .route("/api/binance/futures/premiumIndex", get(get_premium_index))
         .layer(cors);
 
     let listener = tokio::net::TcpListener::bind("127.0.0.1:3001")
@@ -127,6 +130,31 @@ async fn get_ticker_price(Query(params): Query<std::collections::HashMap<String,
     }
 }
 
+async fn get_option_mark(Query(params): Query<std::collections::HashMap<String, String>>) -> Result<Json<serde_json::Value>, StatusCode> {
+    let symbol = params.get("symbol").ok_or(StatusCode::BAD_REQUEST)?;
+
+    match fetch_option_mark(symbol).await {
+        Ok(data) => Ok(Json(data)),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+async fn get_option_exchange_info() -> Result<Json<serde_json::Value>, StatusCode> {
+    match fetch_option_exchange_info().await {
+        Ok(data) => Ok(Json(data)),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+async fn get_premium_index(Query(params): Query<std::collections::HashMap<String, String>>) -> Result<Json<serde_json::Value>, StatusCode> {
+    let symbol = params.get("symbol").ok_or(StatusCode::BAD_REQUEST)?;
+
+    match fetch_premium_index(symbol).await {
+        Ok(data) => Ok(Json(data)),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
 async fn fetch_binance_data(
     api_key: &str,
     secret_key: &str,
@@ -291,3 +319,77 @@ async fn fetch_ticker_price(symbol: &str) -> Result<serde_json::Value, String> {
 
     Ok(data)
 }
+
+// ฟังก์ชั่นสำหรับ Options Mark Price / IV (Public API - ไม่ต้องใช้ signature)
+async fn fetch_option_mark(symbol: &str) -> Result<serde_json::Value, String> {
+    let url = format!("https://eapi.binance.com/eapi/v1/mark?symbol={}", symbol);
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Network error: {}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        return Err(format!("Binance Options API Error ({}): {}", status, error_text));
+    }
+
+    let data: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse response: {}", e))?;
+
+    Ok(data)
+}
+
+// Options contract metadata (unit, quote asset) - public API
+async fn fetch_option_exchange_info() -> Result<serde_json::Value, String> {
+    let url = "https://eapi.binance.com/eapi/v1/exchangeInfo";
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Network error: {}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        return Err(format!("Binance Options API Error ({}): {}", status, error_text));
+    }
+
+    let data: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse response: {}", e))?;
+
+    Ok(data)
+}
+
+async fn fetch_premium_index(symbol: &str) -> Result<serde_json::Value, String> {
+    let url = format!("https://fapi.binance.com/fapi/v1/premiumIndex?symbol={}", symbol);
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Network error: {}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        return Err(format!("Binance Futures API Error ({}): {}", status, error_text));
+    }
+
+    let data: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse response: {}", e))?;
+
+    Ok(data)
+}
@@ -1,6 +1,7 @@
-use crate::components::{ChartControls, PayoffChart, PortfolioManager, PositionForm, PositionList, ApiTester, ApiKeyManager};
-use crate::models::{Portfolio, Position};
-use crate::utils::{AppSettings, LocalStorageManager};
+use crate::components::{ChartControls, HedgePanel, PayoffChart, PortfolioManager, PositionForm, PositionList, ApiTester, ApiKeyManager, ScenarioTable, StrikeOptimizer, TemplatePicker, UnderlyingSelector};
+use crate::engine::{BetaWeighting, PayoffEngine, PortfolioEngine, ScenarioEngine, SimulationConfig, SimulationEngine, StrategyEngine, TemplateEngine, ValuationContext};
+use crate::models::{ExportFormat, FeeSchedule, MarginMethod, Portfolio, Position, PositionSource, PriceDistribution, ScenarioGrid, UnderlyingQuote, VarSettings};
+use crate::utils::{AppSettings, BrowserFileManager, LocalStorageManager};
 use dioxus::prelude::*;
 
 pub fn App() -> Element {
@@ -14,6 +15,14 @@ pub fn App() -> Element {
     let mut price_start = use_signal(|| 0.0);
     let mut price_end = use_signal(|| 300.0);
 
+    // Underlying on the chart's price axis (None = primary) and whether the
+    // other underlyings are beta-weighted onto it
+    let mut selected_underlying = use_signal(|| None::<String>);
+    let mut combined_view = use_signal(|| false);
+
+    // User-defined strategy templates (the built-in library is added on display)
+    let mut user_templates = use_signal(|| LocalStorageManager::load_templates().unwrap_or_default());
+
     // Load app on startup
     use_effect(move || {
         // Load app settings
@@ -85,9 +94,119 @@ pub fn App() -> Element {
             .unwrap_or_default()
     });
 
-    // Auto-adjust price range based on positions
+    // Structures held by the active positions, recognized once per change
+    let strategy_report = use_memo(move || StrategyEngine::classify(&positions()));
+
+    // Positions net of trading costs: those without their own fees take the portfolio schedule
+    let costed_positions = use_memo(move || {
+        let positions = positions();
+        match current_portfolio() {
+            Some(p) => p.settings.calculation_settings.fee_schedule.apply(&positions),
+            None => positions,
+        }
+    });
+
+    // Positions split by underlying, in order of first appearance
+    let underlying_groups = use_memo(move || PayoffEngine::group_by_underlying(&costed_positions()));
+
+    // Selected underlying, falling back to the first one when it has no positions left
+    let view_underlying = use_memo(move || {
+        let groups = underlying_groups();
+        let selected = selected_underlying();
+        if groups.iter().any(|group| group.underlying == selected) {
+            selected
+        } else {
+            groups.first().and_then(|group| group.underlying.clone())
+        }
+    });
+
+    // Positions on the price-axis underlying
+    let view_positions = use_memo(move || {
+        underlying_groups()
+            .into_iter()
+            .find(|group| group.underlying == view_underlying())
+            .map(|group| group.positions)
+            .unwrap_or_default()
+    });
+
+    // The combined view only applies once the portfolio spans several underlyings
+    let is_combined = use_memo(move || combined_view() && underlying_groups().len() > 1);
+
+    let chart_positions = use_memo(move || {
+        if is_combined() {
+            costed_positions()
+        } else {
+            view_positions()
+        }
+    });
+
+    // Suggested hedge trades still awaiting the user's decision
+    let pending_hedges = use_memo(move || {
+        current_portfolio()
+            .map(|p| p.proposed_hedge_indices())
+            .unwrap_or_default()
+    });
+
+    // Chart positions with the pending hedges switched on, for the hedged preview curve
+    let hedge_preview_positions = use_memo(move || {
+        let pending = pending_hedges();
+        let portfolio = current_portfolio()?;
+        if pending.is_empty() {
+            return None;
+        }
+        let hedged: Vec<Position> = portfolio
+            .positions
+            .iter()
+            .enumerate()
+            .map(|(index, ep)| {
+                let mut position = ep.position.clone();
+                if pending.contains(&index) {
+                    position.set_active(true);
+                }
+                position
+            })
+            .collect();
+        let costed = portfolio.settings.calculation_settings.fee_schedule.apply(&hedged);
+        if is_combined() {
+            Some(costed)
+        } else {
+            Some(
+                PayoffEngine::group_by_underlying(&costed)
+                    .into_iter()
+                    .find(|group| group.underlying == view_underlying())
+                    .map(|group| group.positions)
+                    .unwrap_or_default(),
+            )
+        }
+    });
+
+    // Pre-expiry valuation assumptions from the portfolio's calculation settings
+    let valuation = use_memo(move || {
+        current_portfolio()
+            .map(|p| ValuationContext::from_settings(&p.settings.calculation_settings))
+            .unwrap_or_default()
+    });
+
+    // Price distribution used for probability of profit and expected value
+    let price_distribution = use_memo(move || {
+        current_portfolio()
+            .map(|p| p.settings.calculation_settings.price_distribution)
+            .unwrap_or(PriceDistribution::Lognormal)
+    });
+
+    // Margin methodology and the account equity it is checked against
+    let margin_settings = use_memo(move || {
+        current_portfolio()
+            .map(|p| {
+                let settings = &p.settings.calculation_settings;
+                (settings.margin_method, settings.account_equity)
+            })
+            .unwrap_or((MarginMethod::Standard, None))
+    });
+
+    // Auto-adjust price range based on the price-axis underlying's positions
     let auto_range = use_memo(move || {
-        let pos = positions();
+        let pos = view_positions();
         if pos.is_empty() {
             return (0.0, 300.0);
         }
@@ -103,6 +222,19 @@ pub fn App() -> Element {
                     min_relevant = min_relevant.min(option.strike_price - range_padding);
                     max_relevant = max_relevant.max(option.strike_price + range_padding);
                 }
+                Position::Digital(ref digital) => {
+                    // The payoff steps at the strike
+                    let range_padding = digital.strike_price * 0.5; // 50% padding
+                    min_relevant = min_relevant.min(digital.strike_price - range_padding);
+                    max_relevant = max_relevant.max(digital.strike_price + range_padding);
+                }
+                Position::Barrier(ref barrier) => {
+                    // Keep both the strike and the barrier in view
+                    let low = barrier.strike_price.min(barrier.barrier_level);
+                    let high = barrier.strike_price.max(barrier.barrier_level);
+                    min_relevant = min_relevant.min(low * 0.7); // 30% padding
+                    max_relevant = max_relevant.max(high * 1.3);
+                }
                 Position::Spot(ref spot) => {
                     let range_padding = spot.entry_price * 0.3; // 30% padding
                     min_relevant = min_relevant.min(spot.entry_price - range_padding);
@@ -123,6 +255,79 @@ pub fn App() -> Element {
         (start, end)
     });
 
+    // Current price of the price-axis underlying (falls back to the chart midpoint when unset)
+    let current_price = use_memo(move || {
+        current_portfolio()
+            .map(|p| {
+                PortfolioEngine::resolve_underlying_price(
+                    &p.settings.calculation_settings,
+                    view_underlying().as_deref(),
+                    price_start(),
+                    price_end(),
+                )
+            })
+            .unwrap_or((price_start() + price_end()) / 2.0)
+    });
+
+    // Current price of the primary underlying, used to imply option volatility
+    let primary_price = use_memo(move || {
+        current_portfolio()
+            .map(|p| {
+                PortfolioEngine::resolve_current_price(
+                    &p.settings.calculation_settings,
+                    price_start(),
+                    price_end(),
+                )
+            })
+            .unwrap_or((price_start() + price_end()) / 2.0)
+    });
+
+    // Prices and betas that place every underlying on the price axis
+    let beta_weighting = use_memo(move || {
+        if !is_combined() {
+            return None;
+        }
+        current_portfolio().map(|p| {
+            BetaWeighting::from_settings(
+                &p.settings.calculation_settings,
+                view_underlying().as_deref(),
+                current_price(),
+            )
+        })
+    });
+
+    // Monte Carlo P&L over the simulation horizon (single underlying views only)
+    let simulation = use_memo(move || {
+        if is_combined() {
+            return None;
+        }
+        current_portfolio().and_then(|p| {
+            SimulationEngine::simulate_portfolio(
+                &chart_positions(),
+                current_price(),
+                &SimulationConfig::from_settings(&p.settings.calculation_settings),
+                &valuation(),
+            )
+        })
+    });
+
+    // Scenario grid on the chart's positions, around the current price
+    let scenario_report = use_memo(move || {
+        let positions = chart_positions();
+        if positions.is_empty() {
+            return None;
+        }
+        current_portfolio().map(|p| {
+            ScenarioEngine::run(
+                &positions,
+                current_price(),
+                &p.settings.calculation_settings.scenario_grid,
+                &valuation(),
+                beta_weighting().as_ref(),
+            )
+        })
+    });
+
     // Update price range when positions change
     use_effect(move || {
         let (start, end) = auto_range();
@@ -177,6 +382,26 @@ pub fn App() -> Element {
         }
     };
 
+    // Update the current price of an underlying (None = primary)
+    let mut set_underlying_price = {
+        let mut current_portfolio = current_portfolio;
+        move |(underlying, price): (Option<String>, f64)| {
+            if let Some(mut portfolio) = current_portfolio() {
+                let settings = &mut portfolio.settings.calculation_settings;
+                match underlying {
+                    None => settings.underlying_price = Some(price),
+                    Some(symbol) => {
+                        let beta = settings.underlying_quote(&symbol).map_or(1.0, |quote| quote.beta);
+                        settings.set_underlying_quote(UnderlyingQuote { symbol, price: Some(price), beta });
+                    }
+                }
+                portfolio.update_timestamp();
+                let _ = LocalStorageManager::save_portfolio(&portfolio);
+                current_portfolio.set(Some(portfolio));
+            }
+        }
+    };
+
     let mut step_size = use_signal(|| 1.0);
 
     rsx! {
@@ -233,11 +458,62 @@ pub fn App() -> Element {
                     // Chart Section - 9/12 width on desktop, full width on mobile (order: 1)
                     div {
                         class: "section chart-section-side mobile-order-1",
+                        if underlying_groups().len() > 1 {
+                            UnderlyingSelector {
+                                underlyings: underlying_groups().into_iter().map(|group| group.underlying).collect::<Vec<_>>(),
+                                selected: view_underlying(),
+                                combined: combined_view(),
+                                primary_price: current_portfolio().and_then(|p| p.settings.calculation_settings.underlying_price),
+                                quotes: current_portfolio().map(|p| p.settings.calculation_settings.underlying_quotes).unwrap_or_default(),
+                                on_select: move |underlying: Option<String>| selected_underlying.set(underlying),
+                                on_combined_change: move |combined: bool| combined_view.set(combined),
+                                on_price_change: set_underlying_price,
+                                on_beta_change: move |(symbol, beta): (String, f64)| {
+                                    if let Some(mut portfolio) = current_portfolio() {
+                                        let settings = &mut portfolio.settings.calculation_settings;
+                                        let price = settings.underlying_quote(&symbol).and_then(|quote| quote.price);
+                                        settings.set_underlying_quote(UnderlyingQuote { symbol, price, beta });
+                                        portfolio.update_timestamp();
+                                        let _ = LocalStorageManager::save_portfolio(&portfolio);
+                                        current_portfolio.set(Some(portfolio));
+                                    }
+                                }
+                            }
+                        }
                         PayoffChart {
-                            positions: positions.read().clone(),
+                            positions: chart_positions(),
                             price_start: price_start(),
                             price_end: price_end(),
-                            step_size: step_size()
+                            step_size: step_size(),
+                            valuation: valuation(),
+                            current_price: current_price(),
+                            price_distribution: price_distribution(),
+                            margin_method: margin_settings().0,
+                            account_equity: margin_settings().1,
+                            beta_weighting: beta_weighting(),
+                            var_settings: current_portfolio().map(|p| p.settings.calculation_settings.var_settings).unwrap_or_default(),
+                            preview_positions: hedge_preview_positions(),
+                            simulation: simulation()
+                        }
+                        if let Some(report) = scenario_report() {
+                            ScenarioTable {
+                                report: report,
+                                on_grid_change: move |scenario_grid: ScenarioGrid| {
+                                    if let Some(mut portfolio) = current_portfolio() {
+                                        portfolio.settings.calculation_settings.scenario_grid = scenario_grid;
+                                        portfolio.update_timestamp();
+                                        let _ = LocalStorageManager::save_portfolio(&portfolio);
+                                        current_portfolio.set(Some(portfolio));
+                                    }
+                                },
+                                on_export: move |format: ExportFormat| {
+                                    if let (Some(portfolio), Some(report)) = (current_portfolio(), scenario_report()) {
+                                        if let Err(e) = BrowserFileManager::export_scenario_report_to_file(&report, &portfolio.name, format) {
+                                            web_sys::console::error_1(&format!("Scenario export error: {}", e).into());
+                                        }
+                                    }
+                                }
+                            }
                         }
                     }
 
@@ -250,6 +526,85 @@ pub fn App() -> Element {
                                 show_api_key_manager.set(true);
                             }
                         }
+                        TemplatePicker {
+                            templates: TemplateEngine::builtin_templates().into_iter().chain(user_templates()).collect::<Vec<_>>(),
+                            underlying_price: current_price(),
+                            underlying: view_underlying(),
+                            valuation: valuation(),
+                            on_apply: move |template_positions: Vec<Position>| {
+                                if let Some(mut portfolio) = current_portfolio() {
+                                    for position in template_positions {
+                                        portfolio.add_position(position);
+                                    }
+                                    let _ = LocalStorageManager::save_portfolio(&portfolio);
+                                    current_portfolio.set(Some(portfolio));
+                                }
+                            },
+                            on_save_current: move |name: String| {
+                                let id = format!("custom-{}", uuid::Uuid::new_v4());
+                                match TemplateEngine::template_from_positions(&id, &name, &view_positions(), current_price()) {
+                                    Ok(template) => {
+                                        let mut templates = user_templates();
+                                        templates.push(template);
+                                        let _ = LocalStorageManager::save_templates(&templates);
+                                        user_templates.set(templates);
+                                    }
+                                    Err(e) => web_sys::console::error_1(&format!("Template error: {}", e).into()),
+                                }
+                            },
+                            on_delete: move |id: String| {
+                                let mut templates = user_templates();
+                                templates.retain(|template| template.id != id);
+                                let _ = LocalStorageManager::save_templates(&templates);
+                                user_templates.set(templates);
+                            }
+                        }
+                        StrikeOptimizer {
+                            templates: TemplateEngine::builtin_templates().into_iter().chain(user_templates()).collect::<Vec<_>>(),
+                            underlying_price: current_price(),
+                            underlying: view_underlying(),
+                            valuation: valuation(),
+                            on_apply: move |candidate_positions: Vec<Position>| {
+                                if let Some(mut portfolio) = current_portfolio() {
+                                    for position in candidate_positions {
+                                        portfolio.add_position(position);
+                                    }
+                                    let _ = LocalStorageManager::save_portfolio(&portfolio);
+                                    current_portfolio.set(Some(portfolio));
+                                }
+                            }
+                        }
+                        HedgePanel {
+                            positions: chart_positions(),
+                            underlying_price: current_price(),
+                            underlying: view_underlying(),
+                            valuation: valuation(),
+                            beta_weighting: beta_weighting(),
+                            pending_hedges: pending_hedges().len(),
+                            on_propose: move |trades: Vec<Position>| {
+                                if let Some(mut portfolio) = current_portfolio() {
+                                    for trade in trades {
+                                        portfolio.add_position_from(trade, PositionSource::Hedge { proposed: true });
+                                    }
+                                    let _ = LocalStorageManager::save_portfolio(&portfolio);
+                                    current_portfolio.set(Some(portfolio));
+                                }
+                            },
+                            on_activate: move |_| {
+                                if let Some(mut portfolio) = current_portfolio() {
+                                    portfolio.accept_proposed_hedges();
+                                    let _ = LocalStorageManager::save_portfolio(&portfolio);
+                                    current_portfolio.set(Some(portfolio));
+                                }
+                            },
+                            on_discard: move |_| {
+                                if let Some(mut portfolio) = current_portfolio() {
+                                    portfolio.discard_proposed_hedges();
+                                    let _ = LocalStorageManager::save_portfolio(&portfolio);
+                                    current_portfolio.set(Some(portfolio));
+                                }
+                            }
+                        }
                     }
                 }
 
@@ -265,6 +620,10 @@ pub fn App() -> Element {
                             class: "section position-list-section",
                             PositionList {
                                 positions: positions.read().clone(),
+                                strategy_report: strategy_report(),
+                                current_price: primary_price(),
+                                underlying_quotes: current_portfolio().map(|p| p.settings.calculation_settings.underlying_quotes).unwrap_or_default(),
+                                valuation: valuation(),
                                 on_remove_position: remove_position,
                                 on_update_position: move |(index, updated_position): (usize, Position)| {
                                     if let Some(mut portfolio) = current_portfolio() {
@@ -279,8 +638,7 @@ pub fn App() -> Element {
                                 on_toggle_position: move |index: usize| {
                                     if let Some(mut portfolio) = current_portfolio() {
                                         if index < portfolio.positions.len() {
-                                            portfolio.positions[index].position.toggle_active();
-                                            portfolio.update_timestamp();
+                                            portfolio.toggle_position(index);
                                             let _ = LocalStorageManager::save_portfolio(&portfolio);
                                             current_portfolio.set(Some(portfolio));
                                         }
@@ -308,6 +666,8 @@ pub fn App() -> Element {
                                 price_start: price_start(),
                                 price_end: price_end(),
                                 step_size: step_size(),
+                                valuation: valuation(),
+                                current_price: current_price(),
                                 on_price_range_change: move |(start, end): (f64, f64)| {
                                     price_start.set(start);
                                     price_end.set(end);
@@ -315,6 +675,41 @@ pub fn App() -> Element {
                                 on_step_size_change: move |step: f64| {
                                     step_size.set(step);
                                 },
+                                on_current_price_change: move |price: f64| {
+                                    set_underlying_price((view_underlying(), price));
+                                },
+                                on_valuation_change: move |context: ValuationContext| {
+                                    if let Some(mut portfolio) = current_portfolio() {
+                                        let settings = &mut portfolio.settings.calculation_settings;
+                                        settings.days_to_expiry = context.days_to_expiry;
+                                        settings.days_elapsed = context.days_elapsed;
+                                        settings.volatility = context.volatility;
+                                        settings.risk_free_rate = context.risk_free_rate;
+                                        settings.default_pricing_model = context.pricing_model;
+                                        settings.futures_basis = context.futures_basis;
+                                        portfolio.update_timestamp();
+                                        let _ = LocalStorageManager::save_portfolio(&portfolio);
+                                        current_portfolio.set(Some(portfolio));
+                                    }
+                                },
+                                fee_schedule: current_portfolio().map(|p| p.settings.calculation_settings.fee_schedule).unwrap_or_default(),
+                                on_fee_schedule_change: move |fee_schedule: FeeSchedule| {
+                                    if let Some(mut portfolio) = current_portfolio() {
+                                        portfolio.settings.calculation_settings.fee_schedule = fee_schedule;
+                                        portfolio.update_timestamp();
+                                        let _ = LocalStorageManager::save_portfolio(&portfolio);
+                                        current_portfolio.set(Some(portfolio));
+                                    }
+                                },
+                                var_settings: current_portfolio().map(|p| p.settings.calculation_settings.var_settings).unwrap_or_default(),
+                                on_var_settings_change: move |var_settings: VarSettings| {
+                                    if let Some(mut portfolio) = current_portfolio() {
+                                        portfolio.settings.calculation_settings.var_settings = var_settings;
+                                        portfolio.update_timestamp();
+                                        let _ = LocalStorageManager::save_portfolio(&portfolio);
+                                        current_portfolio.set(Some(portfolio));
+                                    }
+                                },
                                 on_calculate: move |_| {
                                     // Force re-render of chart
                                     // The chart will automatically update due to reactive signals
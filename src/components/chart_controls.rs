@@ -1,3 +1,8 @@
+use super::scenario_table::{axis_text, parse_axis};
+use crate::engine::ValuationContext;
+use crate::models::{FeeSchedule, Liquidity, PricingModel, VarSettings};
+use crate::utils::web3_import;
+use chrono::NaiveDate;
 use dioxus::prelude::*;
 
 #[derive(Props, Clone, PartialEq)]
@@ -5,8 +10,16 @@ pub struct ChartControlsProps {
     pub price_start: f64,
     pub price_end: f64,
     pub step_size: f64,
+    pub valuation: ValuationContext,
+    pub current_price: f64,
     pub on_price_range_change: EventHandler<(f64, f64)>,
     pub on_step_size_change: EventHandler<f64>,
+    pub on_current_price_change: EventHandler<f64>,
+    pub on_valuation_change: EventHandler<ValuationContext>,
+    pub fee_schedule: FeeSchedule, // Portfolio fees for positions without their own
+    pub on_fee_schedule_change: EventHandler<FeeSchedule>,
+    pub var_settings: VarSettings, // Confidence levels, horizons and price history for VaR
+    pub on_var_settings_change: EventHandler<VarSettings>,
     pub on_calculate: EventHandler<()>,
 }
 
@@ -14,6 +27,7 @@ pub fn ChartControls(props: ChartControlsProps) -> Element {
     let mut price_start_input = use_signal(|| props.price_start.to_string());
     let mut price_end_input = use_signal(|| props.price_end.to_string());
     let mut step_size_input = use_signal(|| props.step_size.to_string());
+    let mut price_history_error = use_signal(|| None::<String>);
 
     // Update inputs when props change
     use_effect(move || {
@@ -113,6 +127,526 @@ pub fn ChartControls(props: ChartControlsProps) -> Element {
                     }
                 }
 
+                div {
+                    class: "control-section",
+                    h4 { "Valuation (T+n)" }
+
+                    div {
+                        class: "form-group",
+                        label { "Current Price" }
+                        input {
+                            class: "form-control",
+                            r#type: "number",
+                            step: "0.01",
+                            min: "0",
+                            value: "{props.current_price:.2}",
+                            oninput: move |e| {
+                                if let Ok(price) = e.value().parse::<f64>() {
+                                    if price > 0.0 {
+                                        props.on_current_price_change.call(price);
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    div {
+                        class: "range-inputs",
+                        div {
+                            class: "form-group",
+                            label { "Days to Expiry" }
+                            input {
+                                class: "form-control",
+                                r#type: "number",
+                                step: "1",
+                                min: "0",
+                                value: "{props.valuation.days_to_expiry}",
+                                oninput: move |e| {
+                                    if let Ok(days) = e.value().parse::<f64>() {
+                                        if days >= 0.0 {
+                                            props.on_valuation_change.call(ValuationContext {
+                                                days_to_expiry: days,
+                                                ..props.valuation
+                                            });
+                                        }
+                                    }
+                                }
+                            }
+                        }
+
+                        div {
+                            class: "form-group",
+                            label { "Days Elapsed (n)" }
+                            input {
+                                class: "form-control",
+                                r#type: "number",
+                                step: "1",
+                                min: "0",
+                                value: "{props.valuation.days_elapsed}",
+                                oninput: move |e| {
+                                    if let Ok(days) = e.value().parse::<f64>() {
+                                        if days >= 0.0 {
+                                            props.on_valuation_change.call(ValuationContext {
+                                                days_elapsed: days,
+                                                ..props.valuation
+                                            });
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    div {
+                        class: "form-group",
+                        label { "Valuation Date" }
+                        input {
+                            class: "form-control",
+                            r#type: "date",
+                            value: "{props.valuation.valuation_date().format(\"%Y-%m-%d\")}",
+                            oninput: move |e| {
+                                if let Ok(date) = NaiveDate::parse_from_str(&e.value(), "%Y-%m-%d") {
+                                    // Stored as days elapsed from today so T+n stays relative
+                                    let today = props.valuation.reference_date.date_naive();
+                                    let days = (date - today).num_days().max(0) as f64;
+                                    props.on_valuation_change.call(ValuationContext {
+                                        days_elapsed: days,
+                                        ..props.valuation
+                                    });
+                                }
+                            }
+                        }
+                    }
+
+                    div {
+                        class: "range-inputs",
+                        div {
+                            class: "form-group",
+                            label { "Volatility %" }
+                            input {
+                                class: "form-control",
+                                r#type: "number",
+                                step: "0.1",
+                                min: "0",
+                                value: "{props.valuation.volatility * 100.0:.1}",
+                                oninput: move |e| {
+                                    if let Ok(vol) = e.value().parse::<f64>() {
+                                        if vol > 0.0 {
+                                            props.on_valuation_change.call(ValuationContext {
+                                                volatility: vol / 100.0,
+                                                ..props.valuation
+                                            });
+                                        }
+                                    }
+                                }
+                            }
+                        }
+
+                        div {
+                            class: "form-group",
+                            label { "Risk-Free Rate %" }
+                            input {
+                                class: "form-control",
+                                r#type: "number",
+                                step: "0.1",
+                                value: "{props.valuation.risk_free_rate * 100.0:.2}",
+                                oninput: move |e| {
+                                    if let Ok(rate) = e.value().parse::<f64>() {
+                                        props.on_valuation_change.call(ValuationContext {
+                                            risk_free_rate: rate / 100.0,
+                                            ..props.valuation
+                                        });
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    div {
+                        class: "range-inputs",
+                        div {
+                            class: "form-group",
+                            label { "Pricing Model" }
+                            select {
+                                class: "form-control",
+                                value: "{props.valuation.pricing_model:?}",
+                                onchange: move |e| {
+                                    let pricing_model = match e.value().as_str() {
+                                        "Black76" => PricingModel::Black76,
+                                        _ => PricingModel::BlackScholes,
+                                    };
+                                    props.on_valuation_change.call(ValuationContext {
+                                        pricing_model,
+                                        ..props.valuation
+                                    });
+                                },
+                                option { value: "BlackScholes", "Black-Scholes (spot)" }
+                                option { value: "Black76", "Black-76 (futures)" }
+                            }
+                        }
+
+                        div {
+                            class: "form-group",
+                            label { "Futures Basis %/yr" }
+                            input {
+                                class: "form-control",
+                                r#type: "number",
+                                step: "0.1",
+                                value: "{props.valuation.futures_basis * 100.0:.2}",
+                                oninput: move |e| {
+                                    if let Ok(basis) = e.value().parse::<f64>() {
+                                        props.on_valuation_change.call(ValuationContext {
+                                            futures_basis: basis / 100.0,
+                                            ..props.valuation
+                                        });
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                div {
+                    class: "control-section",
+                    h4 { "Fees & Slippage" }
+
+                    div {
+                        class: "form-group",
+                        label {
+                            input {
+                                r#type: "checkbox",
+                                checked: props.fee_schedule.enabled,
+                                onchange: move |e| {
+                                    props.on_fee_schedule_change.call(FeeSchedule {
+                                        enabled: e.checked(),
+                                        ..props.fee_schedule
+                                    });
+                                }
+                            }
+                            " Deduct trading costs"
+                        }
+                    }
+
+                    if props.fee_schedule.enabled {
+                    div {
+                        class: "range-inputs",
+                        div {
+                            class: "form-group",
+                            label { "Futures Entry" }
+                            select {
+                                class: "form-control",
+                                value: "{props.fee_schedule.entry_liquidity:?}",
+                                onchange: move |e| {
+                                    let liquidity = match e.value().as_str() {
+                                        "Maker" => Liquidity::Maker,
+                                        _ => Liquidity::Taker,
+                                    };
+                                    props.on_fee_schedule_change.call(FeeSchedule {
+                                        entry_liquidity: liquidity,
+                                        ..props.fee_schedule
+                                    });
+                                },
+                                option { value: "Taker", "Taker" }
+                                option { value: "Maker", "Maker" }
+                            }
+                        }
+
+                        div {
+                            class: "form-group",
+                            label { "Futures Exit" }
+                            select {
+                                class: "form-control",
+                                value: "{props.fee_schedule.exit_liquidity:?}",
+                                onchange: move |e| {
+                                    let liquidity = match e.value().as_str() {
+                                        "Maker" => Liquidity::Maker,
+                                        _ => Liquidity::Taker,
+                                    };
+                                    props.on_fee_schedule_change.call(FeeSchedule {
+                                        exit_liquidity: liquidity,
+                                        ..props.fee_schedule
+                                    });
+                                },
+                                option { value: "Taker", "Taker" }
+                                option { value: "Maker", "Maker" }
+                            }
+                        }
+                    }
+
+                    div {
+                        class: "range-inputs",
+                        div {
+                            class: "form-group",
+                            label { "Futures Maker %" }
+                            input {
+                                class: "form-control",
+                                r#type: "number",
+                                step: "0.001",
+                                min: "0",
+                                value: "{props.fee_schedule.futures_maker_rate * 100.0}",
+                                oninput: move |e| {
+                                    if let Ok(rate) = e.value().parse::<f64>() {
+                                        if rate >= 0.0 {
+                                            props.on_fee_schedule_change.call(FeeSchedule {
+                                                futures_maker_rate: rate / 100.0,
+                                                ..props.fee_schedule
+                                            });
+                                        }
+                                    }
+                                }
+                            }
+                        }
+
+                        div {
+                            class: "form-group",
+                            label { "Futures Taker %" }
+                            input {
+                                class: "form-control",
+                                r#type: "number",
+                                step: "0.001",
+                                min: "0",
+                                value: "{props.fee_schedule.futures_taker_rate * 100.0}",
+                                oninput: move |e| {
+                                    if let Ok(rate) = e.value().parse::<f64>() {
+                                        if rate >= 0.0 {
+                                            props.on_fee_schedule_change.call(FeeSchedule {
+                                                futures_taker_rate: rate / 100.0,
+                                                ..props.fee_schedule
+                                            });
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    div {
+                        class: "range-inputs",
+                        div {
+                            class: "form-group",
+                            label { "Spot %" }
+                            input {
+                                class: "form-control",
+                                r#type: "number",
+                                step: "0.001",
+                                min: "0",
+                                value: "{props.fee_schedule.spot_rate * 100.0}",
+                                oninput: move |e| {
+                                    if let Ok(rate) = e.value().parse::<f64>() {
+                                        if rate >= 0.0 {
+                                            props.on_fee_schedule_change.call(FeeSchedule {
+                                                spot_rate: rate / 100.0,
+                                                ..props.fee_schedule
+                                            });
+                                        }
+                                    }
+                                }
+                            }
+                        }
+
+                        div {
+                            class: "form-group",
+                            label { "Slippage %/side" }
+                            input {
+                                class: "form-control",
+                                r#type: "number",
+                                step: "0.001",
+                                min: "0",
+                                value: "{props.fee_schedule.slippage * 100.0}",
+                                oninput: move |e| {
+                                    if let Ok(rate) = e.value().parse::<f64>() {
+                                        if rate >= 0.0 {
+                                            props.on_fee_schedule_change.call(FeeSchedule {
+                                                slippage: rate / 100.0,
+                                                ..props.fee_schedule
+                                            });
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    div {
+                        class: "range-inputs",
+                        div {
+                            class: "form-group",
+                            label { "Option % of Underlying" }
+                            input {
+                                class: "form-control",
+                                r#type: "number",
+                                step: "0.001",
+                                min: "0",
+                                value: "{props.fee_schedule.option_rate * 100.0}",
+                                oninput: move |e| {
+                                    if let Ok(rate) = e.value().parse::<f64>() {
+                                        if rate >= 0.0 {
+                                            props.on_fee_schedule_change.call(FeeSchedule {
+                                                option_rate: rate / 100.0,
+                                                ..props.fee_schedule
+                                            });
+                                        }
+                                    }
+                                }
+                            }
+                        }
+
+                        div {
+                            class: "form-group",
+                            label { "Exercise % of Underlying" }
+                            input {
+                                class: "form-control",
+                                r#type: "number",
+                                step: "0.001",
+                                min: "0",
+                                value: "{props.fee_schedule.option_exercise_rate * 100.0}",
+                                oninput: move |e| {
+                                    if let Ok(rate) = e.value().parse::<f64>() {
+                                        if rate >= 0.0 {
+                                            props.on_fee_schedule_change.call(FeeSchedule {
+                                                option_exercise_rate: rate / 100.0,
+                                                ..props.fee_schedule
+                                            });
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    div {
+                        class: "range-inputs",
+                        div {
+                            class: "form-group",
+                            label { "Option Fee Cap % of Premium" }
+                            input {
+                                class: "form-control",
+                                r#type: "number",
+                                step: "1",
+                                min: "0",
+                                value: "{props.fee_schedule.option_fee_cap * 100.0}",
+                                oninput: move |e| {
+                                    if let Ok(rate) = e.value().parse::<f64>() {
+                                        if rate >= 0.0 {
+                                            props.on_fee_schedule_change.call(FeeSchedule {
+                                                option_fee_cap: rate / 100.0,
+                                                ..props.fee_schedule
+                                            });
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    }
+                }
+
+                div {
+                    class: "control-section",
+                    h4 { "Value at Risk" }
+
+                    div {
+                        class: "range-inputs",
+                        div {
+                            class: "form-group",
+                            label { r#for: "var-confidence-levels", "Confidence (%)" }
+                            input {
+                                id: "var-confidence-levels",
+                                class: "form-control",
+                                r#type: "text",
+                                value: "{axis_text(&props.var_settings.confidence_levels, 100.0)}",
+                                onchange: {
+                                    let var_settings = props.var_settings.clone();
+                                    move |e: Event<FormData>| {
+                                        if let Some(confidence_levels) = parse_axis(&e.value(), 100.0)
+                                            .filter(|levels| levels.iter().all(|level| *level > 0.0 && *level < 1.0))
+                                        {
+                                            props.on_var_settings_change.call(VarSettings {
+                                                confidence_levels,
+                                                ..var_settings.clone()
+                                            });
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                        div {
+                            class: "form-group",
+                            label { r#for: "var-horizons", "Horizons (days)" }
+                            input {
+                                id: "var-horizons",
+                                class: "form-control",
+                                r#type: "text",
+                                value: "{axis_text(&props.var_settings.horizons_days, 1.0)}",
+                                onchange: {
+                                    let var_settings = props.var_settings.clone();
+                                    move |e: Event<FormData>| {
+                                        if let Some(horizons_days) = parse_axis(&e.value(), 1.0)
+                                            .filter(|horizons| horizons.iter().all(|days| *days > 0.0))
+                                        {
+                                            props.on_var_settings_change.call(VarSettings {
+                                                horizons_days,
+                                                ..var_settings.clone()
+                                            });
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    div {
+                        class: "form-group",
+                        label { r#for: "var-price-history", "Price History (CSV of daily closes)" }
+                        textarea {
+                            id: "var-price-history",
+                            class: "form-control",
+                            rows: "3",
+                            placeholder: "Date,Close\n2024-01-01,42000\n...",
+                            onchange: {
+                                let var_settings = props.var_settings.clone();
+                                move |e: Event<FormData>| {
+                                    match web3_import::import_price_history_csv(&e.value()) {
+                                        Ok(price_history) => {
+                                            price_history_error.set(None);
+                                            props.on_var_settings_change.call(VarSettings {
+                                                price_history,
+                                                ..var_settings.clone()
+                                            });
+                                        }
+                                        Err(error) => price_history_error.set(Some(error)),
+                                    }
+                                }
+                            }
+                        }
+                        if let Some(error) = price_history_error() {
+                            small { class: "loss", "{error}" }
+                        }
+                    }
+
+                    if props.var_settings.price_history.is_empty() {
+                        small { "No history loaded: VaR uses the volatility assumption" }
+                    } else {
+                        div {
+                            class: "form-group",
+                            small { "{props.var_settings.price_history.len()} closes loaded (historical VaR and volatility) " }
+                            button {
+                                class: "btn btn-sm btn-outline-secondary",
+                                onclick: {
+                                    let var_settings = props.var_settings.clone();
+                                    move |_| {
+                                        props.on_var_settings_change.call(VarSettings {
+                                            price_history: Vec::new(),
+                                            ..var_settings.clone()
+                                        });
+                                    }
+                                },
+                                "Clear"
+                            }
+                        }
+                    }
+                }
+
                 div {
                     class: "control-section",
 
@@ -0,0 +1,193 @@
+use crate::engine::{
+    BetaWeighting, Greeks, HedgeEngine, HedgeInstrument, HedgeSuggestion, HedgeTargets,
+    ValuationContext,
+};
+use crate::models::Position;
+use crate::utils::web3_export_import::import;
+use dioxus::prelude::*;
+
+#[derive(Props, Clone, PartialEq)]
+pub struct HedgePanelProps {
+    pub positions: Vec<Position>,    // Chart positions the hedge neutralizes
+    pub underlying_price: f64,       // Price the Greeks are measured at
+    pub underlying: Option<String>,  // Underlying of the hedge trades (None = primary)
+    pub valuation: ValuationContext, // Volatility and expiry for the Greeks
+    #[props(default)]
+    pub beta_weighting: Option<BetaWeighting>, // Some = combined view across underlyings
+    pub pending_hedges: usize,       // Proposed trades awaiting accept or discard
+    pub on_propose: EventHandler<Vec<Position>>, // Append trades as inactive positions
+    pub on_activate: EventHandler<()>, // Switch the proposed trades on
+    pub on_discard: EventHandler<()>, // Remove the proposed trades
+}
+
+pub fn HedgePanel(props: HedgePanelProps) -> Element {
+    let mut linear = use_signal(|| "Perpetual".to_string());
+    let mut hedge_gamma = use_signal(|| false);
+    let mut hedge_vega = use_signal(|| false);
+    let mut chain_text = use_signal(String::new);
+    let mut suggestion = use_signal(|| None::<Result<HedgeSuggestion, String>>);
+
+    let positions = props.positions.clone();
+    let underlying = props.underlying.clone();
+    let weighting = props.beta_weighting.clone();
+    let valuation = props.valuation;
+    let underlying_price = props.underlying_price;
+    let pending = props.pending_hedges;
+
+    rsx! {
+        div {
+            class: "hedge-panel",
+            h3 { "Delta Hedge" }
+
+            div {
+                class: "range-inputs",
+                div {
+                    class: "form-group",
+                    label { r#for: "hedge-linear", "Delta Instrument" }
+                    select {
+                        id: "hedge-linear",
+                        class: "form-control",
+                        onchange: move |e| linear.set(e.value()),
+                        for choice in ["Perpetual", "Spot", "None"] {
+                            option { value: "{choice}", selected: linear() == choice, "{choice}" }
+                        }
+                    }
+                }
+                div {
+                    class: "form-group hedge-targets",
+                    label {
+                        input {
+                            r#type: "checkbox",
+                            checked: hedge_gamma(),
+                            onchange: move |e| hedge_gamma.set(e.checked())
+                        }
+                        " Neutralize Gamma"
+                    }
+                    label {
+                        input {
+                            r#type: "checkbox",
+                            checked: hedge_vega(),
+                            onchange: move |e| hedge_vega.set(e.checked())
+                        }
+                        " Neutralize Vega"
+                    }
+                }
+            }
+
+            div {
+                class: "form-group",
+                label { r#for: "hedge-chain", "Listed Options (Type,Strike,Premium)" }
+                textarea {
+                    id: "hedge-chain",
+                    class: "form-control",
+                    rows: "3",
+                    placeholder: "Call,100,4.5\nPut,95,2.5",
+                    value: "{chain_text}",
+                    oninput: move |e| chain_text.set(e.value())
+                }
+            }
+
+            div {
+                class: "hedge-actions",
+                button {
+                    class: "btn btn-primary btn-sm",
+                    // Proposed trades are inactive, so a new suggestion would ignore them
+                    disabled: pending > 0 || positions.is_empty(),
+                    onclick: move |_| {
+                        let mut instruments = match linear().as_str() {
+                            "Perpetual" => vec![HedgeInstrument::Perpetual],
+                            "Spot" => vec![HedgeInstrument::Spot],
+                            _ => Vec::new(),
+                        };
+                        if !chain_text().trim().is_empty() {
+                            match import::import_option_chain_csv(&chain_text()) {
+                                Ok(chain) => instruments.extend(chain.into_iter().map(HedgeInstrument::Option)),
+                                Err(e) => {
+                                    suggestion.set(Some(Err(e)));
+                                    return;
+                                }
+                            }
+                        }
+                        let targets = HedgeTargets { gamma: hedge_gamma(), vega: hedge_vega() };
+                        let result = HedgeEngine::suggest(
+                            &positions,
+                            underlying_price,
+                            &instruments,
+                            targets,
+                            &valuation,
+                            weighting.as_ref(),
+                            underlying.clone(),
+                        );
+                        if let Ok(found) = &result {
+                            if !found.trades.is_empty() {
+                                props.on_propose.call(found.trades.clone());
+                            }
+                        }
+                        suggestion.set(Some(result));
+                    },
+                    "Suggest Hedge"
+                }
+                if pending > 0 {
+                    button {
+                        class: "btn btn-success btn-sm",
+                        onclick: move |_| {
+                            props.on_activate.call(());
+                            suggestion.set(None);
+                        },
+                        "Activate {pending} Hedge Trade(s)"
+                    }
+                    button {
+                        class: "btn btn-outline-secondary btn-sm",
+                        onclick: move |_| {
+                            props.on_discard.call(());
+                            suggestion.set(None);
+                        },
+                        "Discard"
+                    }
+                }
+            }
+
+            if pending > 0 {
+                small { class: "hedge-note", "Proposed trades are added inactive; the chart previews the hedged curve." }
+            }
+
+            match suggestion() {
+                Some(Ok(found)) if found.trades.is_empty() => rsx! {
+                    small { class: "hedge-note", "Already neutral - no trades needed." }
+                },
+                Some(Ok(found)) => rsx! {
+                    table {
+                        class: "data-table hedge-greeks",
+                        thead {
+                            tr {
+                                th {}
+                                th { "Delta" }
+                                th { "Gamma" }
+                                th { "Vega" }
+                            }
+                        }
+                        tbody {
+                            {greeks_row("Unhedged", &found.before)}
+                            {greeks_row("Hedged", &found.after)}
+                        }
+                    }
+                },
+                Some(Err(error)) => rsx! {
+                    div { class: "validation-errors", "{error}" }
+                },
+                None => rsx! {},
+            }
+        }
+    }
+}
+
+fn greeks_row(label: &str, greeks: &Greeks) -> Element {
+    rsx! {
+        tr {
+            th { "{label}" }
+            td { "{greeks.delta:.4}" }
+            td { "{greeks.gamma:.4}" }
+            td { "{greeks.vega:.4}" }
+        }
+    }
+}
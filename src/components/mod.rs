@@ -10,7 +10,12 @@ pub mod api_key_manager;
 pub mod api_key_list;
 pub mod api_key_form;
 pub mod data_import_dialog;
+pub mod hedge_panel;
 pub mod position_sync_dialog;
+pub mod scenario_table;
+pub mod strike_optimizer;
+pub mod template_picker;
+pub mod underlying_selector;
 
 // Re-export main components
 pub use app::App;
@@ -24,4 +29,9 @@ pub use api_key_manager::ApiKeyManager;
 pub use api_key_list::ApiKeyList;
 pub use api_key_form::ApiKeyForm;
 pub use data_import_dialog::DataImportDialog;
+pub use hedge_panel::HedgePanel;
 pub use position_sync_dialog::PositionSyncDialog;
+pub use scenario_table::ScenarioTable;
+pub use strike_optimizer::StrikeOptimizer;
+pub use template_picker::TemplatePicker;
+pub use underlying_selector::UnderlyingSelector;
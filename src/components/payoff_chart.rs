@@ -1,8 +1,15 @@
-use crate::engine::{PayoffEngine, PayoffPoint};
-use crate::models::Position;
+use crate::engine::{
+    BetaWeighting, Greeks, GreeksEngine, GreeksPoint, LiquidationEngine, MarginEngine,
+    MarginRequirement, PayoffBound, PayoffEngine, PayoffPoint, PortfolioEngine, SimulationResult,
+    ValuationContext, VarEstimate, VarMethod,
+};
+use crate::models::{MarginMethod, Position, PriceDistribution, VarSettings};
 use dioxus::prelude::*;
 use web_sys;
 
+/// Intervals the Greeks curve is sampled at across the price range
+const GREEKS_CURVE_SAMPLES: usize = 40;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum ChartEngine {
     SvgNative,
@@ -15,10 +22,23 @@ pub enum ChartEngine {
 #[derive(Debug, Clone)]
 pub struct ChartData {
     pub payoff_points: Vec<PayoffPoint>,
+    pub value_points: Vec<PayoffPoint>, // T+n curve (empty when valued at expiry)
+    pub preview_points: Vec<PayoffPoint>, // Curve with proposed hedges switched on (empty without)
     pub breakeven_points: Vec<f64>,
-    pub max_profit: Option<f64>,
-    pub max_loss: Option<f64>,
+    pub max_profit: Option<PayoffBound>,
+    pub max_loss: Option<PayoffBound>,
     pub price_range: (f64, f64),
+    pub greeks: Greeks,                 // Portfolio Greeks at the current price
+    pub greeks_curve: Vec<GreeksPoint>, // Portfolio Greeks across the price range (single underlying)
+    pub profit_probability: Option<f64>,
+    pub expected_value: Option<f64>,
+    pub expected_funding: f64, // Perpetual funding at the current price
+    pub trading_costs: f64,    // Fees and slippage held to expiry at the current price
+    pub liquidation_prices: Vec<f64>,
+    pub margin: Option<MarginRequirement>, // None without positions
+    pub return_on_margin: Option<f64>,     // Max profit / initial margin
+    pub barrier_levels: Vec<(f64, String)>, // Active barrier levels with their label
+    pub value_at_risk: Vec<VarEstimate>,   // VaR / ES per method, confidence and horizon
 }
 
 #[derive(Debug, Clone)]
@@ -58,6 +78,19 @@ pub struct PayoffChartProps {
     pub price_start: f64,
     pub price_end: f64,
     pub step_size: f64,
+    pub valuation: ValuationContext,
+    pub current_price: f64,
+    pub price_distribution: PriceDistribution,
+    pub margin_method: MarginMethod,
+    pub account_equity: Option<f64>,
+    #[props(default)]
+    pub beta_weighting: Option<BetaWeighting>, // Some = combined view across underlyings
+    #[props(default)]
+    pub var_settings: VarSettings, // Confidence levels, horizons and price history for VaR
+    #[props(default)]
+    pub preview_positions: Option<Vec<Position>>, // Same positions with proposed hedges switched on
+    #[props(default)]
+    pub simulation: Option<SimulationResult>, // Monte Carlo P&L distribution (None when off)
 }
 
 pub fn PayoffChart(props: PayoffChartProps) -> Element {
@@ -109,58 +142,238 @@ pub fn PayoffChart(props: PayoffChartProps) -> Element {
     let mut hover_data = use_signal(|| None::<(f64, f64, f64)>); // price, pnl, percent
     let mut last_hover_data = use_signal(|| (0.0, 0.0, 0.0)); // Keep last hovered data
 
+    // Beta-weighted view: every underlying follows the reference price through its beta
+    let weighting = props.beta_weighting.as_ref();
+    let reference_positions: Vec<Position> = match weighting {
+        Some(weighting) => props
+            .positions
+            .iter()
+            .filter(|pos| pos.underlying() == weighting.reference.as_deref())
+            .cloned()
+            .collect(),
+        None => props.positions.clone(),
+    };
+
     // Calculate payoff data
-    let payoff_data = if props.positions.is_empty() {
-        Vec::new()
-    } else {
-        PayoffEngine::generate_payoff_curve(
+    let payoff_data = match weighting {
+        _ if props.positions.is_empty() => Vec::new(),
+        Some(weighting) => PayoffEngine::generate_beta_weighted_curve(
             &props.positions,
             props.price_start,
             props.price_end,
             props.step_size,
-        )
+            weighting,
+            None,
+        ),
+        None => PayoffEngine::generate_payoff_curve(
+            &props.positions,
+            props.price_start,
+            props.price_end,
+            props.step_size,
+        ),
     };
 
-    let breakeven_points = if props.positions.is_empty() {
-        Vec::new()
-    } else {
-        PayoffEngine::find_breakeven_points(
+    // T+n value curve - only drawn while active options still carry time value
+    let value_data = match weighting {
+        _ if !PayoffEngine::has_live_options(&props.positions, &props.valuation) => Vec::new(),
+        Some(weighting) => PayoffEngine::generate_beta_weighted_curve(
             &props.positions,
             props.price_start,
             props.price_end,
             props.step_size,
-        )
+            weighting,
+            Some(&props.valuation),
+        ),
+        None => PayoffEngine::generate_value_curve(
+            &props.positions,
+            props.price_start,
+            props.price_end,
+            props.step_size,
+            &props.valuation,
+        ),
     };
 
-    let max_profit = if props.positions.is_empty() {
-        None
-    } else {
-        PayoffEngine::calculate_max_profit(
+    // Hedged preview, on the T+n value while options carry time value, else at expiry
+    let preview_data = match (&props.preview_positions, weighting) {
+        (None, _) => Vec::new(),
+        (Some(preview), weighting) => {
+            let context = PayoffEngine::has_live_options(preview, &props.valuation)
+                .then_some(&props.valuation);
+            match (weighting, context) {
+                (Some(weighting), context) => PayoffEngine::generate_beta_weighted_curve(
+                    preview,
+                    props.price_start,
+                    props.price_end,
+                    props.step_size,
+                    weighting,
+                    context,
+                ),
+                (None, Some(context)) => PayoffEngine::generate_value_curve(
+                    preview,
+                    props.price_start,
+                    props.price_end,
+                    props.step_size,
+                    context,
+                ),
+                (None, None) => PayoffEngine::generate_payoff_curve(
+                    preview,
+                    props.price_start,
+                    props.price_end,
+                    props.step_size,
+                ),
+            }
+        }
+    };
+
+    // The combined payoff is only known at sampled prices, so its break-evens are
+    // interpolated and its extremes are left out
+    let breakeven_points = match weighting {
+        _ if props.positions.is_empty() => Vec::new(),
+        Some(_) => PayoffEngine::find_curve_crossings(&payoff_data),
+        None => PayoffEngine::find_breakeven_points(
             &props.positions,
             props.price_start,
             props.price_end,
-            props.step_size,
-        )
+        ),
+    };
+
+    let max_profit = if props.positions.is_empty() || weighting.is_some() {
+        None
+    } else {
+        PayoffEngine::calculate_max_profit(&props.positions)
     };
 
-    let max_loss = if props.positions.is_empty() {
+    let max_loss = if props.positions.is_empty() || weighting.is_some() {
         None
     } else {
-        PayoffEngine::calculate_max_loss(
+        PayoffEngine::calculate_max_loss(&props.positions)
+    };
+
+    let greeks = match weighting {
+        Some(weighting) => GreeksEngine::calculate_beta_weighted_greeks(
             &props.positions,
-            props.price_start,
-            props.price_end,
-            props.step_size,
-        )
+            props.current_price,
+            weighting,
+            &props.valuation,
+        ),
+        None => GreeksEngine::calculate_portfolio_greeks(
+            &props.positions,
+            props.current_price,
+            &props.valuation,
+        ),
+    };
+
+    // Delta across the range, sampled coarser than the payoff since every point
+    // revalues each leg's bumps
+    let greeks_curve = match weighting {
+        None if !props.positions.is_empty() && props.price_end > props.price_start => {
+            GreeksEngine::generate_greeks_curve(
+                &props.positions,
+                props.price_start,
+                props.price_end,
+                (props.price_end - props.price_start) / GREEKS_CURVE_SAMPLES as f64,
+                &props.valuation,
+            )
+        }
+        _ => Vec::new(),
+    };
+
+    let (profit_probability, expected_value) = match weighting {
+        Some(_) => (None, None),
+        None => PortfolioEngine::calculate_probability_metrics(
+            &props.positions,
+            &payoff_data,
+            props.current_price,
+            props.price_distribution,
+            &props.valuation,
+        ),
+    };
+
+    let expected_funding = match weighting {
+        Some(weighting) => PayoffEngine::group_by_underlying(&props.positions)
+            .iter()
+            .filter_map(|group| {
+                let price =
+                    weighting.implied_price(group.underlying.as_deref(), props.current_price)?;
+                Some(PayoffEngine::calculate_expected_funding(
+                    &group.positions,
+                    price,
+                ))
+            })
+            .sum(),
+        None => PayoffEngine::calculate_expected_funding(&props.positions, props.current_price),
+    };
+
+    let trading_costs = match weighting {
+        Some(weighting) => PayoffEngine::group_by_underlying(&props.positions)
+            .iter()
+            .filter_map(|group| {
+                let price =
+                    weighting.implied_price(group.underlying.as_deref(), props.current_price)?;
+                Some(PayoffEngine::calculate_trading_costs(
+                    &group.positions,
+                    price,
+                ))
+            })
+            .sum(),
+        None => PayoffEngine::calculate_trading_costs(&props.positions, props.current_price),
     };
 
+    let margin = match weighting {
+        _ if props.positions.is_empty() => None,
+        Some(weighting) => Some(combined_margin(
+            &props.positions,
+            weighting,
+            props.margin_method,
+            &props.valuation,
+        )),
+        None => Some(MarginEngine::calculate_margin(
+            &props.positions,
+            props.current_price,
+            props.margin_method,
+            &props.valuation,
+        )),
+    };
+    let return_on_margin = match (max_profit, margin) {
+        (Some(PayoffBound::Bounded(profit)), Some(margin)) => {
+            MarginEngine::return_on_margin(profit, &margin)
+        }
+        _ => None,
+    };
+
+    let value_at_risk = PortfolioEngine::calculate_value_at_risk(
+        &props.positions,
+        props.current_price,
+        &props.var_settings,
+        &props.valuation,
+        weighting,
+    );
+
+    let unpriced_underlyings = weighting
+        .map(|weighting| weighting.unpriced_underlyings(&props.positions))
+        .unwrap_or_default();
+
     // Prepare chart data
     let chart_data = ChartData {
         payoff_points: payoff_data,
+        value_points: value_data,
+        preview_points: preview_data,
         breakeven_points,
         max_profit,
         max_loss,
         price_range: (props.price_start, props.price_end),
+        greeks,
+        greeks_curve,
+        profit_probability,
+        expected_value,
+        expected_funding,
+        trading_costs,
+        // Liquidation and barrier levels are only meaningful on the reference's price axis
+        liquidation_prices: LiquidationEngine::portfolio_liquidation_prices(&reference_positions),
+        margin,
+        return_on_margin,
+        barrier_levels: barrier_levels(&reference_positions),
+        value_at_risk,
     };
 
     // Chart configuration
@@ -217,18 +430,145 @@ pub fn PayoffChart(props: PayoffChartProps) -> Element {
                     strong { "Step Size: " }
                     span { "${props.step_size:.2}" }
                 }
+                if let Some(weighting) = weighting {
+                    div {
+                        class: "stat-item",
+                        strong { "View: " }
+                        span { "β-weighted to {underlying_label(weighting.reference.as_deref())}" }
+                    }
+                }
+                if !unpriced_underlyings.is_empty() {
+                    div {
+                        class: "stat-item loss",
+                        strong { "Not Priced (excluded): " }
+                        span {
+                            {unpriced_underlyings.iter().map(|underlying| underlying_label(underlying.as_deref())).collect::<Vec<_>>().join(", ")}
+                        }
+                    }
+                }
+                if !chart_data.value_points.is_empty() {
+                    div {
+                        class: "stat-item",
+                        strong { "Value Curve: " }
+                        span {
+                            "T+{props.valuation.days_elapsed:.0} ({props.valuation.days_to_expiry - props.valuation.days_elapsed:.0} DTE)"
+                        }
+                    }
+                }
+                if !props.positions.is_empty() {
+                    div {
+                        class: "stat-item",
+                        strong { "Greeks @ ${props.current_price:.2}: " }
+                        span {
+                            "Δ {chart_data.greeks.delta:.3} | Γ {chart_data.greeks.gamma:.4} | Θ {chart_data.greeks.theta:.2}/day | Vega {chart_data.greeks.vega:.2} | ρ {chart_data.greeks.rho:.2}"
+                        }
+                    }
+                }
+                if let Some(pop) = chart_data.profit_probability {
+                    div {
+                        class: "stat-item",
+                        strong { "Probability of Profit: " }
+                        span { "{pop * 100.0:.1}%" }
+                    }
+                }
+                if let Some(ev) = chart_data.expected_value {
+                    div {
+                        class: "stat-item",
+                        strong { "Expected Value: " }
+                        span { "${ev:.2}" }
+                    }
+                }
+                if chart_data.expected_funding != 0.0 {
+                    div {
+                        class: "stat-item",
+                        strong { "Expected Funding: " }
+                        span { "${chart_data.expected_funding:.2}" }
+                    }
+                }
+                if chart_data.trading_costs > 0.0 {
+                    div {
+                        class: "stat-item",
+                        strong { "Trading Costs: " }
+                        span { "${chart_data.trading_costs:.2} (included)" }
+                    }
+                }
+                if let Some(simulation) = props.simulation.as_ref() {
+                    div {
+                        class: "stat-item",
+                        strong { "Monte Carlo VaR {simulation.confidence * 100.0:.1}%: " }
+                        span { "${simulation.value_at_risk:.2} (CVaR ${simulation.conditional_value_at_risk:.2})" }
+                    }
+                    div {
+                        class: "stat-item",
+                        strong { "Monte Carlo P&L 5th / 95th: " }
+                        span { "${simulation.percentile(0.05):.2} / ${simulation.percentile(0.95):.2}" }
+                    }
+                }
+                for estimate in chart_data.value_at_risk.iter() {
+                    div {
+                        class: "stat-item",
+                        strong {
+                            "{format_var_method(estimate.method)} VaR {estimate.horizon_days}d {estimate.confidence * 100.0:.1}%: "
+                        }
+                        span { "${estimate.value_at_risk:.2} (ES ${estimate.expected_shortfall:.2})" }
+                    }
+                }
                 if let Some(max_p) = chart_data.max_profit {
                     div {
                         class: "stat-item profit",
                         strong { "Max Profit: " }
-                        span { "${max_p:.2}" }
+                        span { {format_bound(max_p)} }
                     }
                 }
                 if let Some(max_l) = chart_data.max_loss {
                     div {
                         class: "stat-item loss",
                         strong { "Max Loss: " }
-                        span { "${max_l:.2}" }
+                        span { {format_bound(max_l)} }
+                    }
+                }
+                if let Some(margin) = chart_data.margin {
+                    div {
+                        class: "stat-item",
+                        strong { "Margin ({format_margin_method(margin.method)}): " }
+                        span {
+                            "${margin.initial_margin:.2} initial / ${margin.maintenance_margin:.2} maintenance"
+                        }
+                    }
+                    if let Some(worst) = margin.worst_scenario {
+                        div {
+                            class: "stat-item",
+                            strong { "Worst Scenario: " }
+                            span {
+                                "Price {worst.price_shock * 100.0:+.0}%, Vol {worst.volatility_shock * 100.0:+.0}%"
+                            }
+                        }
+                    }
+                    if let Some(equity) = props.account_equity.filter(|equity| *equity > 0.0) {
+                        div {
+                            class: if margin.initial_margin > equity { "stat-item loss" } else { "stat-item" },
+                            strong { "Account Usage: " }
+                            span { "{margin.initial_margin / equity * 100.0:.1}% of ${equity:.2}" }
+                        }
+                    }
+                }
+                if let Some(rom) = chart_data.return_on_margin {
+                    div {
+                        class: "stat-item",
+                        strong { "Return on Margin: " }
+                        span { "{rom * 100.0:.1}%" }
+                    }
+                }
+                if !chart_data.liquidation_prices.is_empty() {
+                    div {
+                        class: "stat-item loss",
+                        strong { "Liquidation: " }
+                        span {
+                            for (i, price) in chart_data.liquidation_prices.iter().enumerate() {
+                                if i > 0 { ", " }
+                                "${price:.2}"
+                            }
+                        }
                     }
                 }
                 if !chart_data.breakeven_points.is_empty() {
@@ -358,8 +698,9 @@ pub fn PayoffChart(props: PayoffChartProps) -> Element {
                                             {
                                                 let min_price = chart_data.payoff_points.iter().map(|p| p.price).fold(f64::INFINITY, f64::min);
                                                 let max_price = chart_data.payoff_points.iter().map(|p| p.price).fold(f64::NEG_INFINITY, f64::max);
-                                                let min_payoff = chart_data.payoff_points.iter().map(|p| p.payoff).fold(f64::INFINITY, f64::min).min(0.0);
-                                                let max_payoff = chart_data.payoff_points.iter().map(|p| p.payoff).fold(f64::NEG_INFINITY, f64::max).max(0.0);
+                                                let all_points = || chart_data.payoff_points.iter().chain(chart_data.value_points.iter()).chain(chart_data.preview_points.iter());
+                                                let min_payoff = all_points().map(|p| p.payoff).fold(f64::INFINITY, f64::min).min(0.0);
+                                                let max_payoff = all_points().map(|p| p.payoff).fold(f64::NEG_INFINITY, f64::max).max(0.0);
 
                                                 let price_range = if max_price > min_price { max_price - min_price } else { 1.0 };
                                                 let payoff_range = if max_payoff > min_payoff { max_payoff - min_payoff } else { 1.0 };
@@ -399,6 +740,100 @@ pub fn PayoffChart(props: PayoffChartProps) -> Element {
                                                         stroke_linecap: "round"
                                                     }
 
+                                                    // T+n value curve (pre-expiry)
+                                                    if !chart_data.value_points.is_empty() {
+                                                        polyline {
+                                                            points: {
+                                                                chart_data.value_points.iter()
+                                                                    .map(|point| {
+                                                                        let x = (point.price - min_price) / price_range * 640.0;
+                                                                        let y = 320.0 - ((point.payoff - min_payoff) / payoff_range * 320.0);
+                                                                        format!("{:.1},{:.1}", x, y)
+                                                                    })
+                                                                    .collect::<Vec<_>>()
+                                                                    .join(" ")
+                                                            },
+                                                            fill: "none",
+                                                            stroke: "#6f42c1",
+                                                            stroke_width: "2",
+                                                            stroke_dasharray: "6,4",
+                                                            stroke_linejoin: "round"
+                                                        }
+                                                        text {
+                                                            x: "630",
+                                                            y: "15",
+                                                            text_anchor: "end",
+                                                            font_size: "11",
+                                                            fill: "#6f42c1",
+                                                            "- - T+{props.valuation.days_elapsed:.0} value   — Expiry"
+                                                        }
+                                                    }
+
+                                                    // Hedged preview curve
+                                                    if !chart_data.preview_points.is_empty() {
+                                                        polyline {
+                                                            points: {
+                                                                chart_data.preview_points.iter()
+                                                                    .map(|point| {
+                                                                        let x = (point.price - min_price) / price_range * 640.0;
+                                                                        let y = 320.0 - ((point.payoff - min_payoff) / payoff_range * 320.0);
+                                                                        format!("{:.1},{:.1}", x, y)
+                                                                    })
+                                                                    .collect::<Vec<_>>()
+                                                                    .join(" ")
+                                                            },
+                                                            fill: "none",
+                                                            stroke: "#20c997",
+                                                            stroke_width: "2",
+                                                            stroke_dasharray: "2,3",
+                                                            stroke_linejoin: "round"
+                                                        }
+                                                        text {
+                                                            x: "630",
+                                                            y: "30",
+                                                            text_anchor: "end",
+                                                            font_size: "11",
+                                                            fill: "#20c997",
+                                                            "··· Hedged preview"
+                                                        }
+                                                    }
+
+                                                    // Delta curve on its own scale, zero on the P&L zero line
+                                                    if !chart_data.greeks_curve.is_empty() {
+                                                        {
+                                                            let max_delta = chart_data.greeks_curve.iter().map(|point| point.greeks.delta.abs()).fold(0.0, f64::max).max(1e-9);
+                                                            let delta_scale = zero_y.min(320.0 - zero_y).max(40.0) / max_delta;
+                                                            rsx! {
+                                                                polyline {
+                                                                    points: {
+                                                                        chart_data.greeks_curve.iter()
+                                                                            .map(|point| {
+                                                                                let x = (point.price - min_price) / price_range * 640.0;
+                                                                                let y = (zero_y - point.greeks.delta * delta_scale).clamp(0.0, 320.0);
+                                                                                format!("{:.1},{:.1}", x, y)
+                                                                            })
+                                                                            .collect::<Vec<_>>()
+                                                                            .join(" ")
+                                                                    },
+                                                                    fill: "none",
+                                                                    stroke: "#e83e8c",
+                                                                    stroke_width: "1.5",
+                                                                    stroke_dasharray: "8,3,2,3",
+                                                                    stroke_linejoin: "round",
+                                                                    opacity: "0.8"
+                                                                }
+                                                                text {
+                                                                    x: "630",
+                                                                    y: "45",
+                                                                    text_anchor: "end",
+                                                                    font_size: "11",
+                                                                    fill: "#e83e8c",
+                                                                    "-·- Delta (±{max_delta:.2})"
+                                                                }
+                                                            }
+                                                        }
+                                                    }
+
                                                     // Data points (interactive)
                                                     {
                                                         let points = chart_data.payoff_points.clone();
@@ -413,9 +848,11 @@ pub fn PayoffChart(props: PayoffChartProps) -> Element {
                                                                         // Calculate percentage change
                                                                         let initial_value = positions.iter()
                                                                             .map(|pos| match pos {
-                                                                                Position::Option(opt) => opt.premium * opt.quantity.abs(),
+                                                                                Position::Option(opt) => opt.premium * opt.units().abs(),
                                                                                 Position::Spot(spot) => spot.entry_price * spot.quantity.abs(),
                                                                                 Position::Futures(fut) => fut.entry_price * fut.quantity.abs() * fut.contract_size,
+                                                                                Position::Digital(digital) => digital.premium * digital.quantity.abs(),
+                                                                                Position::Barrier(barrier) => barrier.premium * barrier.quantity.abs(),
                                                                             })
                                                                             .sum::<f64>();
                                                                         let percent_change = if initial_value > 0.0 {
@@ -514,6 +951,57 @@ pub fn PayoffChart(props: PayoffChartProps) -> Element {
                                                         }
                                                     }
 
+                                                    // Current price marker
+                                                    if props.current_price >= min_price && props.current_price <= max_price {
+                                                        {
+                                                            let x = (props.current_price - min_price) / price_range * 640.0;
+                                                            rsx! {
+                                                                line {
+                                                                    x1: "{x}",
+                                                                    y1: "0",
+                                                                    x2: "{x}",
+                                                                    y2: "320",
+                                                                    stroke: "#17a2b8",
+                                                                    stroke_width: "1.5",
+                                                                    opacity: "0.7"
+                                                                }
+                                                                text {
+                                                                    x: "{x + 4.0}",
+                                                                    y: "12",
+                                                                    font_size: "10",
+                                                                    fill: "#17a2b8",
+                                                                    "Spot ${props.current_price:.0}"
+                                                                }
+                                                            }
+                                                        }
+                                                    }
+
+                                                    // Barrier level annotations
+                                                    for (level, label) in chart_data.barrier_levels.iter().filter(|(level, _)| *level >= min_price && *level <= max_price) {
+                                                        {
+                                                            let x = (*level - min_price) / price_range * 640.0;
+                                                            rsx! {
+                                                                line {
+                                                                    x1: "{x}",
+                                                                    y1: "0",
+                                                                    x2: "{x}",
+                                                                    y2: "320",
+                                                                    stroke: "#dc3545",
+                                                                    stroke_width: "1.5",
+                                                                    stroke_dasharray: "2,3",
+                                                                    opacity: "0.8"
+                                                                }
+                                                                text {
+                                                                    x: "{x + 4.0}",
+                                                                    y: "26",
+                                                                    font_size: "10",
+                                                                    fill: "#dc3545",
+                                                                    "{label} ${level:.0}"
+                                                                }
+                                                            }
+                                                        }
+                                                    }
+
                                                     // Breakeven points markers
                                                     {
                                                         let breakeven_points = chart_data.breakeven_points.clone();
@@ -906,7 +1394,7 @@ pub fn PayoffChart(props: PayoffChartProps) -> Element {
                                                 div {
                                                     strong { style: "color: #28a745;", "💰 Max Profit" }
                                                     div { style: "font-size: 1.1em; font-weight: bold; color: #28a745; margin-top: 5px;",
-                                                        "${max_p:.2}" }
+                                                        {format_bound(max_p)} }
                                                 }
                                             }
 
@@ -914,7 +1402,7 @@ pub fn PayoffChart(props: PayoffChartProps) -> Element {
                                                 div {
                                                     strong { style: "color: #dc3545;", "⚠️ Max Loss" }
                                                     div { style: "font-size: 1.1em; font-weight: bold; color: #dc3545; margin-top: 5px;",
-                                                        "${max_l:.2}" }
+                                                        {format_bound(max_l)} }
                                                 }
                                             }
                                         }
@@ -940,3 +1428,84 @@ pub fn PayoffChart(props: PayoffChartProps) -> Element {
         }
     }
 }
+
+/// Format a max profit/loss figure for display
+fn format_bound(bound: PayoffBound) -> String {
+    match bound {
+        PayoffBound::Bounded(value) => format!("${value:.2}"),
+        PayoffBound::Unbounded => "Unlimited".to_string(),
+    }
+}
+
+/// Short label for the margin methodology
+fn format_margin_method(method: MarginMethod) -> &'static str {
+    match method {
+        MarginMethod::Standard => "Standard",
+        MarginMethod::PortfolioMargin => "Portfolio",
+    }
+}
+
+/// Display name of a VaR method
+fn format_var_method(method: VarMethod) -> &'static str {
+    match method {
+        VarMethod::Parametric => "Parametric",
+        VarMethod::Historical => "Historical",
+    }
+}
+
+/// Display name of an underlying
+fn underlying_label(underlying: Option<&str>) -> &str {
+    underlying.unwrap_or("Primary")
+}
+
+/// Sum of each underlying's margin at its own current price (no offsets across underlyings)
+fn combined_margin(
+    positions: &[Position],
+    weighting: &BetaWeighting,
+    method: MarginMethod,
+    valuation: &ValuationContext,
+) -> MarginRequirement {
+    PayoffEngine::group_by_underlying(positions)
+        .iter()
+        .filter_map(|group| {
+            let market = weighting.market(group.underlying.as_deref())?;
+            Some(MarginEngine::calculate_margin(
+                &group.positions,
+                market.price,
+                method,
+                valuation,
+            ))
+        })
+        .fold(
+            MarginRequirement {
+                method,
+                initial_margin: 0.0,
+                maintenance_margin: 0.0,
+                worst_scenario: None,
+            },
+            |total, margin| MarginRequirement {
+                initial_margin: total.initial_margin + margin.initial_margin,
+                maintenance_margin: total.maintenance_margin + margin.maintenance_margin,
+                ..total
+            },
+        )
+}
+
+/// Barrier levels of the active barrier options, labelled knock-in / knock-out
+fn barrier_levels(positions: &[Position]) -> Vec<(f64, String)> {
+    positions
+        .iter()
+        .filter(|pos| pos.is_active())
+        .filter_map(|pos| match pos {
+            Position::Barrier(barrier) => {
+                let label = if barrier.barrier_type.is_knock_in() {
+                    "KI"
+                } else {
+                    "KO"
+                };
+                Some((barrier.barrier_level, label.to_string()))
+            }
+            _ => None,
+        })
+        .collect()
+}
@@ -1,6 +1,9 @@
 use crate::models::{
-    FuturesPosition, OptionPosition, OptionType, Position, PositionType, SpotPosition,
+    BarrierPath, BarrierPosition, BarrierType, BarrierValuation, ContractType, DigitalPosition,
+    ExerciseStyle, FuturesPosition, MarginMode, OptionPosition, OptionType, Position,
+    PositionType, PricingModel, SpotPosition,
 };
+use chrono::{DateTime, NaiveDate, Utc};
 use dioxus::prelude::*;
 
 #[derive(Props, Clone, PartialEq)]
@@ -24,9 +27,27 @@ pub fn PositionForm(props: PositionFormProps) -> Element {
     let mut entry_price = use_signal(|| String::new());
     let mut strike_price = use_signal(|| String::new());
     let mut premium = use_signal(|| String::new());
+    let mut payout = use_signal(String::new);
+    let mut barrier_type = use_signal(BarrierType::default);
+    let mut barrier_level = use_signal(String::new);
+    let mut rebate = use_signal(String::new);
+    let mut barrier_path = use_signal(BarrierPath::default);
+    let mut barrier_valuation = use_signal(BarrierValuation::default);
+    let mut implied_volatility = use_signal(String::new);
     let mut contract_size = use_signal(|| String::new());
+    let mut settlement_currency = use_signal(String::new);
+    let mut expiry_date = use_signal(String::new);
+    let mut contract_type = use_signal(ContractType::default);
+    let mut pnl_in_base = use_signal(|| false);
+    let mut funding_rate = use_signal(String::new);
+    let mut funding_horizon = use_signal(String::new);
+    let mut leverage = use_signal(String::new);
+    let mut margin_mode = use_signal(MarginMode::default);
     let mut option_type = use_signal(|| OptionType::Call);
+    let mut exercise_style = use_signal(ExerciseStyle::default);
+    let mut pricing_model = use_signal(|| None::<PricingModel>);
     let mut description = use_signal(|| String::new());
+    let mut underlying = use_signal(String::new); // Blank = primary underlying, kept between adds
     let mut error_message = use_signal(|| String::new());
 
     let mut reset_form = move || {
@@ -35,7 +56,16 @@ pub fn PositionForm(props: PositionFormProps) -> Element {
         entry_price.set(String::new());
         strike_price.set(String::new());
         premium.set(String::new());
+        payout.set(String::new());
+        barrier_level.set(String::new());
+        rebate.set(String::new());
+        implied_volatility.set(String::new());
         contract_size.set(String::new());
+        settlement_currency.set(String::new());
+        expiry_date.set(String::new());
+        funding_rate.set(String::new());
+        funding_horizon.set(String::new());
+        leverage.set(String::new());
         description.set(String::new());
         error_message.set(String::new());
     };
@@ -58,7 +88,19 @@ pub fn PositionForm(props: PositionFormProps) -> Element {
             PositionDirection::Short => -base_qty,
         };
 
-        let position = match position_type() {
+        // Optional expiry date (YYYY-MM-DD); blank falls back to the portfolio days to expiry
+        let expiry = match expiry_date().trim() {
+            "" => None,
+            value => match parse_expiry_date(value) {
+                Some(date) => Some(date),
+                None => {
+                    error_message.set("Expiry must be a valid date".to_string());
+                    return;
+                }
+            },
+        };
+
+        let mut position = match position_type() {
             PositionType::Spot => {
                 let price = match entry_price().parse::<f64>() {
                     Ok(p) if p > 0.0 => p,
@@ -87,13 +129,40 @@ pub fn PositionForm(props: PositionFormProps) -> Element {
                     }
                 };
 
-                Position::Option(OptionPosition::new(
-                    option_type(),
-                    qty,
-                    strike,
-                    prem,
-                    Some(description()),
-                ))
+                // Optional IV (entered in percent); blank falls back to the portfolio default
+                let iv = match implied_volatility().trim() {
+                    "" => None,
+                    value => match value.parse::<f64>() {
+                        Ok(v) if v > 0.0 => Some(v / 100.0),
+                        _ => {
+                            error_message.set("Implied volatility must be a positive percentage".to_string());
+                            return;
+                        }
+                    },
+                };
+
+                // Optional contract multiplier; blank = one underlying unit per contract
+                let multiplier = match contract_size().trim() {
+                    "" => None,
+                    value => match value.parse::<f64>() {
+                        Ok(m) if m > 0.0 => Some(m),
+                        _ => {
+                            error_message.set("Multiplier must be a positive number".to_string());
+                            return;
+                        }
+                    },
+                };
+                let settlement = settlement_currency().trim().to_uppercase();
+
+                Position::Option(OptionPosition {
+                    implied_volatility: iv,
+                    expiry,
+                    exercise_style: exercise_style(),
+                    pricing_model: pricing_model(),
+                    multiplier,
+                    settlement_currency: (!settlement.is_empty()).then_some(settlement),
+                    ..OptionPosition::new(option_type(), qty, strike, prem, Some(description()))
+                })
             }
             PositionType::Futures => {
                 let price = match entry_price().parse::<f64>() {
@@ -112,10 +181,163 @@ pub fn PositionForm(props: PositionFormProps) -> Element {
                     }
                 };
 
-                Position::Futures(FuturesPosition::new(qty, price, size, Some(description())))
+                // Funding rate is entered as % per 8h interval
+                let funding_rate = match funding_rate().trim() {
+                    "" => None,
+                    value => match value.parse::<f64>() {
+                        Ok(rate) => Some(rate / 100.0),
+                        Err(_) => {
+                            error_message.set("Funding rate must be a number".to_string());
+                            return;
+                        }
+                    },
+                };
+
+                let funding_horizon_days = match funding_horizon().trim() {
+                    "" => None,
+                    value => match value.parse::<f64>() {
+                        Ok(days) if days >= 0.0 => Some(days),
+                        _ => {
+                            error_message.set("Holding days must be zero or more".to_string());
+                            return;
+                        }
+                    },
+                };
+
+                // Blank leverage means the position is fully collateralized
+                let leverage = match leverage().trim() {
+                    "" => None,
+                    value => match value.parse::<f64>() {
+                        Ok(leverage) if leverage >= 1.0 => Some(leverage),
+                        _ => {
+                            error_message.set("Leverage must be at least 1".to_string());
+                            return;
+                        }
+                    },
+                };
+
+                Position::Futures(FuturesPosition {
+                    expiry,
+                    leverage,
+                    margin_mode: margin_mode(),
+                    funding_rate,
+                    funding_horizon_days,
+                    contract_type: contract_type(),
+                    pnl_in_base: contract_type() == ContractType::Inverse && pnl_in_base(),
+                    ..FuturesPosition::new(qty, price, size, Some(description()))
+                })
+            }
+            PositionType::Digital => {
+                let strike = match strike_price().parse::<f64>() {
+                    Ok(s) if s > 0.0 => s,
+                    _ => {
+                        error_message.set("Strike price must be a positive number".to_string());
+                        return;
+                    }
+                };
+
+                let pay = match payout().parse::<f64>() {
+                    Ok(p) if p > 0.0 => p,
+                    _ => {
+                        error_message.set("Payout must be a positive number".to_string());
+                        return;
+                    }
+                };
+
+                let prem = match premium().parse::<f64>() {
+                    Ok(p) if p >= 0.0 => p,
+                    _ => {
+                        error_message.set("Premium must be a non-negative number".to_string());
+                        return;
+                    }
+                };
+
+                let iv = match implied_volatility().trim() {
+                    "" => None,
+                    value => match value.parse::<f64>() {
+                        Ok(v) if v > 0.0 => Some(v / 100.0),
+                        _ => {
+                            error_message.set("Implied volatility must be a positive percentage".to_string());
+                            return;
+                        }
+                    },
+                };
+
+                Position::Digital(DigitalPosition {
+                    implied_volatility: iv,
+                    expiry,
+                    ..DigitalPosition::new(option_type(), qty, strike, pay, prem, Some(description()))
+                })
+            }
+            PositionType::Barrier => {
+                let strike = match strike_price().parse::<f64>() {
+                    Ok(s) if s > 0.0 => s,
+                    _ => {
+                        error_message.set("Strike price must be a positive number".to_string());
+                        return;
+                    }
+                };
+
+                let level = match barrier_level().parse::<f64>() {
+                    Ok(b) if b > 0.0 => b,
+                    _ => {
+                        error_message.set("Barrier level must be a positive number".to_string());
+                        return;
+                    }
+                };
+
+                let prem = match premium().parse::<f64>() {
+                    Ok(p) if p >= 0.0 => p,
+                    _ => {
+                        error_message.set("Premium must be a non-negative number".to_string());
+                        return;
+                    }
+                };
+
+                // Blank rebate means nothing is paid when the option is dead at expiry
+                let rebate = match rebate().trim() {
+                    "" => 0.0,
+                    value => match value.parse::<f64>() {
+                        Ok(r) if r >= 0.0 => r,
+                        _ => {
+                            error_message.set("Rebate must be a non-negative number".to_string());
+                            return;
+                        }
+                    },
+                };
+
+                let iv = match implied_volatility().trim() {
+                    "" => None,
+                    value => match value.parse::<f64>() {
+                        Ok(v) if v > 0.0 => Some(v / 100.0),
+                        _ => {
+                            error_message.set("Implied volatility must be a positive percentage".to_string());
+                            return;
+                        }
+                    },
+                };
+
+                Position::Barrier(BarrierPosition {
+                    rebate,
+                    implied_volatility: iv,
+                    expiry,
+                    path: barrier_path(),
+                    valuation: barrier_valuation(),
+                    ..BarrierPosition::new(
+                        option_type(),
+                        barrier_type(),
+                        qty,
+                        strike,
+                        level,
+                        prem,
+                        Some(description()),
+                    )
+                })
             }
         };
 
+        position.set_underlying(Some(underlying()));
+
         props.on_add_position.call(position);
         reset_form();
     };
@@ -168,12 +390,16 @@ pub fn PositionForm(props: PositionFormProps) -> Element {
                                     "Spot" => position_type.set(PositionType::Spot),
                                     "Option" => position_type.set(PositionType::Option),
                                     "Futures" => position_type.set(PositionType::Futures),
+                                    "Digital" => position_type.set(PositionType::Digital),
+                                    "Barrier" => position_type.set(PositionType::Barrier),
                                     _ => {}
                                 }
                             },
                             option { value: "Spot", "Spot" }
                             option { value: "Option", "Option" }
                             option { value: "Futures", "Futures" }
+                            option { value: "Digital", "Digital" }
+                            option { value: "Barrier", "Barrier" }
                         }
                     }
 
@@ -254,6 +480,44 @@ pub fn PositionForm(props: PositionFormProps) -> Element {
                                 }
                             }
 
+                            div {
+                                class: "form-group",
+                                label { r#for: "exercise-style", "Exercise" }
+                                select {
+                                    id: "exercise-style",
+                                    class: "form-control",
+                                    value: "{exercise_style():?}",
+                                    onchange: move |e| {
+                                        match e.value().as_str() {
+                                            "European" => exercise_style.set(ExerciseStyle::European),
+                                            "American" => exercise_style.set(ExerciseStyle::American),
+                                            _ => {}
+                                        }
+                                    },
+                                    option { value: "European", "European" }
+                                    option { value: "American", "American" }
+                                }
+                            }
+
+                            div {
+                                class: "form-group",
+                                label { r#for: "pricing-model", "Pricing Model" }
+                                select {
+                                    id: "pricing-model",
+                                    class: "form-control",
+                                    onchange: move |e| {
+                                        match e.value().as_str() {
+                                            "BlackScholes" => pricing_model.set(Some(PricingModel::BlackScholes)),
+                                            "Black76" => pricing_model.set(Some(PricingModel::Black76)),
+                                            _ => pricing_model.set(None),
+                                        }
+                                    },
+                                    option { value: "Default", "Portfolio default" }
+                                    option { value: "BlackScholes", "Black-Scholes (spot)" }
+                                    option { value: "Black76", "Black-76 (futures)" }
+                                }
+                            }
+
                             div {
                                 class: "form-group",
                                 label { r#for: "strike-price", "Strike Price" }
@@ -281,6 +545,60 @@ pub fn PositionForm(props: PositionFormProps) -> Element {
                                     oninput: move |e| premium.set(e.value())
                                 }
                             }
+
+                            div {
+                                class: "form-group",
+                                label { r#for: "option-multiplier", "Multiplier (Optional)" }
+                                input {
+                                    id: "option-multiplier",
+                                    class: "form-control",
+                                    r#type: "number",
+                                    step: "any",
+                                    placeholder: "e.g., 100",
+                                    title: "Underlying units per contract; premium and strike are per unit",
+                                    value: "{contract_size()}",
+                                    oninput: move |e| contract_size.set(e.value())
+                                }
+                            }
+
+                            div {
+                                class: "form-group",
+                                label { r#for: "settlement-currency", "Settlement (Optional)" }
+                                input {
+                                    id: "settlement-currency",
+                                    class: "form-control",
+                                    r#type: "text",
+                                    placeholder: "e.g., USDT",
+                                    value: "{settlement_currency()}",
+                                    oninput: move |e| settlement_currency.set(e.value())
+                                }
+                            }
+
+                            div {
+                                class: "form-group",
+                                label { r#for: "implied-volatility", "IV % (Optional)" }
+                                input {
+                                    id: "implied-volatility",
+                                    class: "form-control",
+                                    r#type: "number",
+                                    step: "0.1",
+                                    placeholder: "e.g., 65",
+                                    value: "{implied_volatility()}",
+                                    oninput: move |e| implied_volatility.set(e.value())
+                                }
+                            }
+
+                            div {
+                                class: "form-group",
+                                label { r#for: "expiry-date", "Expiry (Optional)" }
+                                input {
+                                    id: "expiry-date",
+                                    class: "form-control",
+                                    r#type: "date",
+                                    value: "{expiry_date()}",
+                                    oninput: move |e| expiry_date.set(e.value())
+                                }
+                            }
                         }
                     },
                     PositionType::Futures => rsx! {
@@ -313,10 +631,388 @@ pub fn PositionForm(props: PositionFormProps) -> Element {
                                     oninput: move |e| contract_size.set(e.value())
                                 }
                             }
+
+                            div {
+                                class: "form-group",
+                                label { r#for: "contract-type", "Contract Type" }
+                                select {
+                                    id: "contract-type",
+                                    class: "form-control",
+                                    value: "{contract_type():?}",
+                                    onchange: move |e| {
+                                        match e.value().as_str() {
+                                            "Linear" => contract_type.set(ContractType::Linear),
+                                            "Inverse" => contract_type.set(ContractType::Inverse),
+                                            _ => {}
+                                        }
+                                    },
+                                    option { value: "Linear", "Linear (USD-M)" }
+                                    option { value: "Inverse", "Inverse (COIN-M)" }
+                                }
+                            }
+
+                            if contract_type() == ContractType::Inverse {
+                                div {
+                                    class: "form-group",
+                                    label {
+                                        input {
+                                            r#type: "checkbox",
+                                            checked: pnl_in_base(),
+                                            onchange: move |e| pnl_in_base.set(e.checked()),
+                                        }
+                                        " P&L in coin"
+                                    }
+                                }
+                            }
+
+                            div {
+                                class: "form-group",
+                                label { r#for: "expiry-date-futures", "Expiry (Optional)" }
+                                input {
+                                    id: "expiry-date-futures",
+                                    class: "form-control",
+                                    r#type: "date",
+                                    value: "{expiry_date()}",
+                                    oninput: move |e| expiry_date.set(e.value())
+                                }
+                            }
+
+                            if expiry_date().trim().is_empty() {
+                                div {
+                                    class: "form-group",
+                                    label { r#for: "funding-rate", "Funding Rate % / 8h (Optional)" }
+                                    input {
+                                        id: "funding-rate",
+                                        class: "form-control",
+                                        r#type: "number",
+                                        step: "any",
+                                        placeholder: "e.g., 0.01",
+                                        value: "{funding_rate()}",
+                                        oninput: move |e| funding_rate.set(e.value())
+                                    }
+                                }
+
+                                div {
+                                    class: "form-group",
+                                    label { r#for: "funding-horizon", "Holding Days (Optional)" }
+                                    input {
+                                        id: "funding-horizon",
+                                        class: "form-control",
+                                        r#type: "number",
+                                        step: "any",
+                                        placeholder: "e.g., 30",
+                                        value: "{funding_horizon()}",
+                                        oninput: move |e| funding_horizon.set(e.value())
+                                    }
+                                }
+                            }
+
+                            div {
+                                class: "form-group",
+                                label { r#for: "leverage", "Leverage (Optional)" }
+                                input {
+                                    id: "leverage",
+                                    class: "form-control",
+                                    r#type: "number",
+                                    step: "any",
+                                    placeholder: "e.g., 10",
+                                    value: "{leverage()}",
+                                    oninput: move |e| leverage.set(e.value())
+                                }
+                            }
+
+                            div {
+                                class: "form-group",
+                                label { r#for: "margin-mode", "Margin Mode" }
+                                select {
+                                    id: "margin-mode",
+                                    class: "form-control",
+                                    value: "{margin_mode():?}",
+                                    onchange: move |e| {
+                                        match e.value().as_str() {
+                                            "Cross" => margin_mode.set(MarginMode::Cross),
+                                            "Isolated" => margin_mode.set(MarginMode::Isolated),
+                                            _ => {}
+                                        }
+                                    },
+                                    option { value: "Cross", "Cross" }
+                                    option { value: "Isolated", "Isolated" }
+                                }
+                            }
+                        }
+                    },
+                    PositionType::Digital => rsx! {
+                        div {
+                            class: "form-row",
+                            div {
+                                class: "form-group",
+                                label { r#for: "digital-type", "Digital Type" }
+                                select {
+                                    id: "digital-type",
+                                    class: "form-control",
+                                    value: "{option_type():?}",
+                                    onchange: move |e| {
+                                        match e.value().as_str() {
+                                            "Call" => option_type.set(OptionType::Call),
+                                            "Put" => option_type.set(OptionType::Put),
+                                            _ => {}
+                                        }
+                                    },
+                                    option { value: "Call", "Call (pays above strike)" }
+                                    option { value: "Put", "Put (pays below strike)" }
+                                }
+                            }
+
+                            div {
+                                class: "form-group",
+                                label { r#for: "strike-price-digital", "Strike Price" }
+                                input {
+                                    id: "strike-price-digital",
+                                    class: "form-control",
+                                    r#type: "number",
+                                    step: "0.01",
+                                    placeholder: "e.g., 105.00",
+                                    value: "{strike_price()}",
+                                    oninput: move |e| strike_price.set(e.value())
+                                }
+                            }
+
+                            div {
+                                class: "form-group",
+                                label { r#for: "payout", "Payout" }
+                                input {
+                                    id: "payout",
+                                    class: "form-control",
+                                    r#type: "number",
+                                    step: "0.01",
+                                    placeholder: "e.g., 10.00",
+                                    value: "{payout()}",
+                                    oninput: move |e| payout.set(e.value())
+                                }
+                            }
+
+                            div {
+                                class: "form-group",
+                                label { r#for: "premium-digital", "Premium" }
+                                input {
+                                    id: "premium-digital",
+                                    class: "form-control",
+                                    r#type: "number",
+                                    step: "0.01",
+                                    placeholder: "e.g., 4.50",
+                                    value: "{premium()}",
+                                    oninput: move |e| premium.set(e.value())
+                                }
+                            }
+
+                            div {
+                                class: "form-group",
+                                label { r#for: "implied-volatility-digital", "IV % (Optional)" }
+                                input {
+                                    id: "implied-volatility-digital",
+                                    class: "form-control",
+                                    r#type: "number",
+                                    step: "0.1",
+                                    placeholder: "e.g., 65",
+                                    value: "{implied_volatility()}",
+                                    oninput: move |e| implied_volatility.set(e.value())
+                                }
+                            }
+
+                            div {
+                                class: "form-group",
+                                label { r#for: "expiry-date-digital", "Expiry (Optional)" }
+                                input {
+                                    id: "expiry-date-digital",
+                                    class: "form-control",
+                                    r#type: "date",
+                                    value: "{expiry_date()}",
+                                    oninput: move |e| expiry_date.set(e.value())
+                                }
+                            }
+                        }
+                    },
+                    PositionType::Barrier => rsx! {
+                        div {
+                            class: "form-row",
+                            div {
+                                class: "form-group",
+                                label { r#for: "barrier-option-type", "Option Type" }
+                                select {
+                                    id: "barrier-option-type",
+                                    class: "form-control",
+                                    value: "{option_type():?}",
+                                    onchange: move |e| {
+                                        match e.value().as_str() {
+                                            "Call" => option_type.set(OptionType::Call),
+                                            "Put" => option_type.set(OptionType::Put),
+                                            _ => {}
+                                        }
+                                    },
+                                    option { value: "Call", "Call" }
+                                    option { value: "Put", "Put" }
+                                }
+                            }
+
+                            div {
+                                class: "form-group",
+                                label { r#for: "barrier-type", "Barrier Type" }
+                                select {
+                                    id: "barrier-type",
+                                    class: "form-control",
+                                    value: "{barrier_type():?}",
+                                    onchange: move |e| {
+                                        match e.value().as_str() {
+                                            "UpAndOut" => barrier_type.set(BarrierType::UpAndOut),
+                                            "UpAndIn" => barrier_type.set(BarrierType::UpAndIn),
+                                            "DownAndOut" => barrier_type.set(BarrierType::DownAndOut),
+                                            "DownAndIn" => barrier_type.set(BarrierType::DownAndIn),
+                                            _ => {}
+                                        }
+                                    },
+                                    option { value: "UpAndOut", "Up-and-Out" }
+                                    option { value: "UpAndIn", "Up-and-In" }
+                                    option { value: "DownAndOut", "Down-and-Out" }
+                                    option { value: "DownAndIn", "Down-and-In" }
+                                }
+                            }
+
+                            div {
+                                class: "form-group",
+                                label { r#for: "strike-price-barrier", "Strike Price" }
+                                input {
+                                    id: "strike-price-barrier",
+                                    class: "form-control",
+                                    r#type: "number",
+                                    step: "0.01",
+                                    placeholder: "e.g., 100.00",
+                                    value: "{strike_price()}",
+                                    oninput: move |e| strike_price.set(e.value())
+                                }
+                            }
+
+                            div {
+                                class: "form-group",
+                                label { r#for: "barrier-level", "Barrier Level" }
+                                input {
+                                    id: "barrier-level",
+                                    class: "form-control",
+                                    r#type: "number",
+                                    step: "0.01",
+                                    placeholder: "e.g., 120.00",
+                                    value: "{barrier_level()}",
+                                    oninput: move |e| barrier_level.set(e.value())
+                                }
+                            }
+
+                            div {
+                                class: "form-group",
+                                label { r#for: "rebate", "Rebate (Optional)" }
+                                input {
+                                    id: "rebate",
+                                    class: "form-control",
+                                    r#type: "number",
+                                    step: "0.01",
+                                    placeholder: "e.g., 1.00",
+                                    value: "{rebate()}",
+                                    oninput: move |e| rebate.set(e.value())
+                                }
+                            }
+
+                            div {
+                                class: "form-group",
+                                label { r#for: "premium-barrier", "Premium" }
+                                input {
+                                    id: "premium-barrier",
+                                    class: "form-control",
+                                    r#type: "number",
+                                    step: "0.01",
+                                    placeholder: "e.g., 2.75",
+                                    value: "{premium()}",
+                                    oninput: move |e| premium.set(e.value())
+                                }
+                            }
+
+                            div {
+                                class: "form-group",
+                                label { r#for: "barrier-path", "Expiry Path" }
+                                select {
+                                    id: "barrier-path",
+                                    class: "form-control",
+                                    value: "{barrier_path():?}",
+                                    onchange: move |e| {
+                                        match e.value().as_str() {
+                                            "Monotone" => barrier_path.set(BarrierPath::Monotone),
+                                            "Touched" => barrier_path.set(BarrierPath::Touched),
+                                            _ => {}
+                                        }
+                                    },
+                                    option { value: "Monotone", "Touched only if expiry is past the barrier" }
+                                    option { value: "Touched", "Barrier already touched" }
+                                }
+                            }
+
+                            div {
+                                class: "form-group",
+                                label { r#for: "barrier-valuation", "Valuation" }
+                                select {
+                                    id: "barrier-valuation",
+                                    class: "form-control",
+                                    value: "{barrier_valuation():?}",
+                                    onchange: move |e| {
+                                        match e.value().as_str() {
+                                            "ClosedForm" => barrier_valuation.set(BarrierValuation::ClosedForm),
+                                            "MonteCarlo" => barrier_valuation.set(BarrierValuation::MonteCarlo),
+                                            _ => {}
+                                        }
+                                    },
+                                    option { value: "ClosedForm", "Closed form (continuous)" }
+                                    option { value: "MonteCarlo", "Monte Carlo (daily)" }
+                                }
+                            }
+
+                            div {
+                                class: "form-group",
+                                label { r#for: "implied-volatility-barrier", "IV % (Optional)" }
+                                input {
+                                    id: "implied-volatility-barrier",
+                                    class: "form-control",
+                                    r#type: "number",
+                                    step: "0.1",
+                                    placeholder: "e.g., 65",
+                                    value: "{implied_volatility()}",
+                                    oninput: move |e| implied_volatility.set(e.value())
+                                }
+                            }
+
+                            div {
+                                class: "form-group",
+                                label { r#for: "expiry-date-barrier", "Expiry (Optional)" }
+                                input {
+                                    id: "expiry-date-barrier",
+                                    class: "form-control",
+                                    r#type: "date",
+                                    value: "{expiry_date()}",
+                                    oninput: move |e| expiry_date.set(e.value())
+                                }
+                            }
                         }
                     }
                 }
 
+                div {
+                    class: "form-group",
+                    label { r#for: "underlying", "Underlying (Optional)" }
+                    input {
+                        id: "underlying",
+                        class: "form-control",
+                        r#type: "text",
+                        placeholder: "Blank = primary underlying, e.g., ETHUSDT",
+                        value: "{underlying()}",
+                        oninput: move |e| underlying.set(e.value())
+                    }
+                }
+
                 div {
                     class: "form-group",
                     label { r#for: "description", "Description (Optional)" }
@@ -348,3 +1044,11 @@ pub fn PositionForm(props: PositionFormProps) -> Element {
         }
     }
 }
+
+/// Parse a date input (YYYY-MM-DD) as the 08:00 UTC exchange expiry on that day
+fn parse_expiry_date(value: &str) -> Option<DateTime<Utc>> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()?
+        .and_hms_opt(8, 0, 0)
+        .map(|datetime| datetime.and_utc())
+}
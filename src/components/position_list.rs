@@ -1,4 +1,7 @@
-use crate::models::Position;
+use crate::engine::{LiquidationEngine, PricingEngine, StrategyReport, ValuationContext};
+use crate::models::{
+    BarrierPath, BarrierValuation, ContractType, MarginMode, Position, UnderlyingQuote,
+};
 use dioxus::prelude::*;
 
 /// Position direction (Long or Short)
@@ -11,13 +14,19 @@ pub enum PositionDirection {
 #[derive(Props, Clone, PartialEq)]
 pub struct PositionListProps {
     pub positions: Vec<Position>,
+    pub strategy_report: StrategyReport, // Structures held by the active legs
     pub on_remove_position: EventHandler<usize>,
     pub on_update_position: EventHandler<(usize, Position)>, // New: for updating positions
     pub on_toggle_position: EventHandler<usize>,             // New: for toggling active state
     pub on_clear_all: EventHandler<()>,
+    pub current_price: f64, // Primary underlying price used to imply volatility
+    pub underlying_quotes: Vec<UnderlyingQuote>, // Prices of the named underlyings
+    pub valuation: ValuationContext, // Days to expiry and rate used to imply volatility
 }
 
 pub fn PositionList(props: PositionListProps) -> Element {
+    let strategy_report = &props.strategy_report;
+
     rsx! {
         div {
             class: "position-list",
@@ -46,6 +55,18 @@ pub fn PositionList(props: PositionListProps) -> Element {
                             key: "{index}",
                             position: position.clone(),
                             index,
+                            current_price: match position.underlying() {
+                                None => Some(props.current_price),
+                                Some(symbol) => props
+                                    .underlying_quotes
+                                    .iter()
+                                    .find(|quote| quote.symbol == symbol)
+                                    .and_then(|quote| quote.price),
+                            },
+                            valuation: props.valuation,
+                            strategy: strategy_report
+                                .strategy_of(index)
+                                .map(|(strategy, role)| format!("{}: {}", strategy.kind.label(), role)),
                             on_remove: move |idx| props.on_remove_position.call(idx),
                             on_update: move |(idx, pos): (usize, Position)| props.on_update_position.call((idx, pos)),
                             on_toggle: move |idx| props.on_toggle_position.call(idx)
@@ -87,6 +108,61 @@ pub fn PositionList(props: PositionListProps) -> Element {
                                 "{props.positions.iter().filter(|p| matches!(p, Position::Futures(_))).count()}"
                             }
                         }
+                        div {
+                            class: "stat-item",
+                            span { class: "stat-label", "Digital Positions:" }
+                            span {
+                                class: "stat-value",
+                                "{props.positions.iter().filter(|p| matches!(p, Position::Digital(_))).count()}"
+                            }
+                        }
+                        div {
+                            class: "stat-item",
+                            span { class: "stat-label", "Barrier Positions:" }
+                            span {
+                                class: "stat-value",
+                                "{props.positions.iter().filter(|p| matches!(p, Position::Barrier(_))).count()}"
+                            }
+                        }
+                    }
+
+                    if !strategy_report.strategies.is_empty() || !strategy_report.residuals.is_empty() {
+                        div {
+                            class: "strategy-summary",
+                            h4 { "Strategies" }
+                            for (i, strategy) in strategy_report.strategies.iter().enumerate() {
+                                div {
+                                    class: "strategy-item",
+                                    key: "{i}",
+                                    strong { "{strategy.kind.label()}" }
+                                    if let Some(underlying) = &strategy.underlying {
+                                        span { class: "strategy-underlying", " {underlying}" }
+                                    }
+                                    if !strategy.widths.is_empty() {
+                                        span {
+                                            class: "strategy-widths",
+                                            " (width "
+                                            {strategy.widths.iter().map(|width| format!("{:.2}", width)).collect::<Vec<_>>().join(" / ")}
+                                            ")"
+                                        }
+                                    }
+                                    ul {
+                                        for leg in strategy.legs.iter() {
+                                            li { "#{leg.index + 1} {leg.role}" }
+                                        }
+                                    }
+                                }
+                            }
+                            if !strategy_report.residuals.is_empty() {
+                                div {
+                                    class: "strategy-item strategy-residuals",
+                                    strong { "Unrecognized legs: " }
+                                    span {
+                                        {strategy_report.residuals.iter().map(|index| format!("#{}", index + 1)).collect::<Vec<_>>().join(", ")}
+                                    }
+                                }
+                            }
+                        }
                     }
                 }
             }
@@ -101,6 +177,10 @@ pub struct PositionCardProps {
     pub on_remove: EventHandler<usize>,
     pub on_update: EventHandler<(usize, Position)>, // New: for updating positions
     pub on_toggle: EventHandler<usize>,             // New: for toggling active state
+    pub current_price: Option<f64>, // Price of the position's underlying (None = not quoted)
+    pub valuation: ValuationContext,
+    #[props(default)]
+    pub strategy: Option<String>, // "Strategy: role" when the leg is part of a recognized structure
 }
 
 pub fn PositionCard(props: PositionCardProps) -> Element {
@@ -120,9 +200,13 @@ pub fn PositionCard(props: PositionCardProps) -> Element {
         Position::Spot(spot) => spot.entry_price,
         Position::Option(option) => option.premium,
         Position::Futures(futures) => futures.entry_price,
+        Position::Digital(digital) => digital.premium,
+        Position::Barrier(barrier) => barrier.premium,
     });
     let mut edit_strike_price = use_signal(|| match &props.position {
         Position::Option(option) => option.strike_price,
+        Position::Digital(digital) => digital.strike_price,
+        Position::Barrier(barrier) => barrier.strike_price,
         _ => 0.0,
     });
     let mut edit_description = use_signal(|| props.position.description().to_string());
@@ -144,9 +228,13 @@ pub fn PositionCard(props: PositionCardProps) -> Element {
                 Position::Spot(spot) => spot.entry_price,
                 Position::Option(option) => option.premium,
                 Position::Futures(futures) => futures.entry_price,
+                Position::Digital(digital) => digital.premium,
+                Position::Barrier(barrier) => barrier.premium,
             });
             edit_strike_price.set(match &pos {
                 Position::Option(option) => option.strike_price,
+                Position::Digital(digital) => digital.strike_price,
+                Position::Barrier(barrier) => barrier.strike_price,
                 _ => 0.0,
             });
             edit_description.set(pos.description().to_string());
@@ -165,35 +253,52 @@ pub fn PositionCard(props: PositionCardProps) -> Element {
             };
 
             let updated_position = match &pos {
-                Position::Spot(_) => {
+                Position::Spot(spot) => {
                     use crate::models::SpotPosition;
                     Position::Spot(SpotPosition {
                         quantity: final_quantity,
                         entry_price: edit_entry_price(),
                         description: edit_description(),
-                        active: pos.is_active(), // Preserve current active state
+                        ..spot.clone() // Preserve active state and underlying
                     })
                 }
                 Position::Option(option) => {
                     use crate::models::OptionPosition;
                     Position::Option(OptionPosition {
-                        option_type: option.option_type,
                         strike_price: edit_strike_price(),
                         quantity: final_quantity,
                         premium: edit_entry_price(),
-                        expiry_price: option.expiry_price, // Keep existing expiry_price
                         description: edit_description(),
-                        active: pos.is_active(), // Preserve current active state
+                        ..option.clone() // Keep type, multiplier, expiry price, IV and active state
                     })
                 }
-                Position::Futures(_) => {
+                Position::Futures(futures) => {
                     use crate::models::FuturesPosition;
                     Position::Futures(FuturesPosition {
                         quantity: final_quantity,
                         entry_price: edit_entry_price(),
-                        contract_size: 1.0, // Default contract size - should make this editable too
                         description: edit_description(),
-                        active: pos.is_active(), // Preserve current active state
+                        ..futures.clone() // Keep contract size, expiry and active state
+                    })
+                }
+                Position::Digital(digital) => {
+                    use crate::models::DigitalPosition;
+                    Position::Digital(DigitalPosition {
+                        strike_price: edit_strike_price(),
+                        quantity: final_quantity,
+                        premium: edit_entry_price(),
+                        description: edit_description(),
+                        ..digital.clone() // Keep type, payout, expiry and active state
+                    })
+                }
+                Position::Barrier(barrier) => {
+                    use crate::models::BarrierPosition;
+                    Position::Barrier(BarrierPosition {
+                        strike_price: edit_strike_price(),
+                        quantity: final_quantity,
+                        premium: edit_entry_price(),
+                        description: edit_description(),
+                        ..barrier.clone() // Keep types, barrier, rebate, expiry and active state
                     })
                 }
             };
@@ -249,6 +354,41 @@ pub fn PositionCard(props: PositionCardProps) -> Element {
                 option.strike_price,
                 option.premium
             );
+            let info = match option.multiplier.filter(|multiplier| *multiplier != 1.0) {
+                Some(multiplier) => format!(
+                    "{} x{} (Total Premium ${:.2})",
+                    info,
+                    multiplier,
+                    option.premium * option.units().abs()
+                ),
+                None => info,
+            };
+            let info = match &option.settlement_currency {
+                Some(currency) => format!("{}, Settles in {}", info, currency),
+                None => info,
+            };
+            let info = match option.exercise_style {
+                crate::models::ExerciseStyle::American => format!("{} (American)", info),
+                crate::models::ExerciseStyle::European => info,
+            };
+            let info = match option.pricing_model {
+                Some(crate::models::PricingModel::Black76) => format!("{} (Black-76)", info),
+                _ => info,
+            };
+            let info = match option.expiry {
+                Some(expiry) => format!("{}, Exp {}", info, expiry.format("%Y-%m-%d")),
+                None => info,
+            };
+            // Entered IV wins; otherwise back it out of the premium
+            let info = match option.implied_volatility {
+                Some(iv) => format!("{}, IV {:.1}%", info, iv * 100.0),
+                None => match props.current_price.and_then(|price| {
+                    PricingEngine::implied_volatility_from_premium(option, price, &props.valuation)
+                }) {
+                    Some(iv) => format!("{}, IV {:.1}% (implied)", info, iv * 100.0),
+                    None => info,
+                },
+            };
             ("option-position", info, direction_class)
         }
         Position::Futures(futures) => {
@@ -269,8 +409,117 @@ pub fn PositionCard(props: PositionCardProps) -> Element {
                 futures.entry_price,
                 futures.contract_size
             );
+            let info = match (futures.contract_type, futures.pnl_in_base) {
+                (ContractType::Inverse, true) => format!("{} (Inverse, P&L in coin)", info),
+                (ContractType::Inverse, false) => format!("{} (Inverse)", info),
+                (ContractType::Linear, _) => info,
+            };
+            let info = match (futures.expiry, futures.funding_rate) {
+                (Some(expiry), _) => format!("{}, Exp {}", info, expiry.format("%Y-%m-%d")),
+                (None, Some(rate)) => format!(
+                    "{}, Funding {:.4}%/8h over {}d",
+                    info,
+                    rate * 100.0,
+                    futures.funding_horizon_days.unwrap_or(0.0)
+                ),
+                (None, None) => info,
+            };
+            let info = match (futures.leverage, futures.margin_mode) {
+                (Some(leverage), MarginMode::Isolated) => {
+                    match LiquidationEngine::liquidation_price(futures) {
+                        Some(price) => {
+                            format!("{}, {}x Isolated, Liq ${:.2}", info, leverage, price)
+                        }
+                        None => format!("{}, {}x Isolated", info, leverage),
+                    }
+                }
+                (Some(leverage), MarginMode::Cross) => format!("{}, {}x Cross", info, leverage),
+                (None, _) => info,
+            };
             ("futures-position", info, direction_class)
         }
+        Position::Digital(digital) => {
+            let direction = if digital.quantity >= 0.0 {
+                "Long"
+            } else {
+                "Short"
+            };
+            let direction_class = if digital.quantity >= 0.0 {
+                "long"
+            } else {
+                "short"
+            };
+            let option_type = match digital.option_type {
+                crate::models::OptionType::Call => "Call",
+                crate::models::OptionType::Put => "Put",
+            };
+            let info = format!(
+                "{} {} Digital {} @ Strike ${:.2}, Pays ${:.2}, Premium ${:.2}",
+                direction,
+                digital.quantity.abs(),
+                option_type,
+                digital.strike_price,
+                digital.payout,
+                digital.premium
+            );
+            let info = match digital.expiry {
+                Some(expiry) => format!("{}, Exp {}", info, expiry.format("%Y-%m-%d")),
+                None => info,
+            };
+            let info = match digital.implied_volatility {
+                Some(iv) => format!("{}, IV {:.1}%", info, iv * 100.0),
+                None => info,
+            };
+            ("digital-position", info, direction_class)
+        }
+        Position::Barrier(barrier) => {
+            let direction = if barrier.quantity >= 0.0 {
+                "Long"
+            } else {
+                "Short"
+            };
+            let direction_class = if barrier.quantity >= 0.0 {
+                "long"
+            } else {
+                "short"
+            };
+            let option_type = match barrier.option_type {
+                crate::models::OptionType::Call => "Call",
+                crate::models::OptionType::Put => "Put",
+            };
+            let info = format!(
+                "{} {} {} {} @ Strike ${:.2}, Barrier ${:.2}, Premium ${:.2}",
+                direction,
+                barrier.quantity.abs(),
+                barrier.barrier_type.label(),
+                option_type,
+                barrier.strike_price,
+                barrier.barrier_level,
+                barrier.premium
+            );
+            let info = if barrier.rebate > 0.0 {
+                format!("{}, Rebate ${:.2}", info, barrier.rebate)
+            } else {
+                info
+            };
+            let info = match barrier.path {
+                BarrierPath::Touched => format!("{} (touched)", info),
+                BarrierPath::Monotone => info,
+            };
+            let info = match barrier.valuation {
+                BarrierValuation::MonteCarlo => format!("{} (Monte Carlo)", info),
+                BarrierValuation::ClosedForm => info,
+            };
+            let info = match barrier.expiry {
+                Some(expiry) => format!("{}, Exp {}", info, expiry.format("%Y-%m-%d")),
+                None => info,
+            };
+            let info = match barrier.implied_volatility {
+                Some(iv) => format!("{}, IV {:.1}%", info, iv * 100.0),
+                None => info,
+            };
+            ("barrier-position", info, direction_class)
+        }
     };
 
     rsx! {
@@ -299,7 +548,19 @@ pub fn PositionCard(props: PositionCardProps) -> Element {
 
                 div {
                     class: "position-type-badge",
-                    "{props.position.position_type():?}"
+                    "#{props.index + 1} {props.position.position_type():?}"
+                }
+                if let Some(symbol) = position_clone.underlying() {
+                    div {
+                        class: "position-underlying-badge",
+                        "{symbol}"
+                    }
+                }
+                if let Some(strategy) = &props.strategy {
+                    div {
+                        class: "position-strategy-badge",
+                        "{strategy}"
+                    }
                 }
                 div {
                     class: "position-actions",
@@ -390,6 +651,8 @@ pub fn PositionCard(props: PositionCardProps) -> Element {
                                     Position::Spot(_) => "Entry Price:",
                                     Position::Option(_) => "Premium:",
                                     Position::Futures(_) => "Entry Price:",
+                                    Position::Digital(_) => "Premium:",
+                                    Position::Barrier(_) => "Premium:",
                                 }
                             }
                             input {
@@ -406,8 +669,11 @@ pub fn PositionCard(props: PositionCardProps) -> Element {
                             }
                         }
 
-                        // Strike price for options
-                        if matches!(&props.position, Position::Option(_)) {
+                        // Strike price for option-like legs
+                        if matches!(
+                            &props.position,
+                            Position::Option(_) | Position::Digital(_) | Position::Barrier(_)
+                        ) {
                             div {
                                 class: "form-row",
                                 label {
@@ -1,8 +1,9 @@
 use dioxus::prelude::*;
 use crate::{
+    engine::PricingEngine,
     models::{
         Position, Portfolio, SpotPosition, FuturesPosition, OptionPosition,
-        position::OptionType,
+        position::{MarginMode, OptionType},
         api_keys::ApiKey,
         import_data::{ImportMode, AssetSelection}
     },
@@ -347,6 +348,14 @@ pub fn PositionSyncDialog(props: PositionSyncDialogProps) -> Element {
                                                                 span { class: "position-type", "🎯 " }
                                                                 span { "{option.description}: {option.quantity}" }
                                                             },
+                                                            Position::Digital(digital) => rsx! {
+                                                                span { class: "position-type", "🎲 " }
+                                                                span { "{digital.description}: {digital.quantity}" }
+                                                            },
+                                                            Position::Barrier(barrier) => rsx! {
+                                                                span { class: "position-type", "🚧 " }
+                                                                span { "{barrier.description}: {barrier.quantity}" }
+                                                            },
                                                         }
                                                     }
                                                 }
@@ -742,11 +751,12 @@ async fn import_spot_positions(
                 if pair.starts_with(asset) && total > 0.001 {
                     // Get current price for this pair
                     if let Ok(price) = get_current_price(pair).await {
-                        let position = Position::Spot(SpotPosition::new(
+                        let mut position = Position::Spot(SpotPosition::new(
                             total,
                             price,
                             Some(pair.clone()),
                         ));
+                        position.set_underlying(Some(pair.clone()));
                         positions.push(position);
                     }
                     break;
@@ -800,6 +810,16 @@ async fn import_futures_positions(
             let position_amt: f64 = position_amt_str.parse().unwrap_or(0.0);
             let entry_price: f64 = entry_price_str.parse().unwrap_or(0.0);
             let unrealized_profit: f64 = unrealized_profit_str.parse().unwrap_or(0.0);
+
+            // positionRisk reports leverage as a string and marginType as "cross" / "isolated"
+            let leverage: Option<f64> = position_obj
+                .get("leverage")
+                .and_then(|value| value.as_str())
+                .and_then(|value| value.parse().ok());
+            let margin_mode = match position_obj.get("marginType").and_then(|value| value.as_str()) {
+                Some("isolated") => MarginMode::Isolated,
+                _ => MarginMode::Cross,
+            };
             
             // Check if this symbol matches any of our selected pairs and has open position
             if position_amt.abs() > 0.000001 && pairs.iter().any(|pair| pair == symbol) {
@@ -810,12 +830,23 @@ async fn import_futures_positions(
                     "Short"
                 };
                 
-                let position = Position::Futures(FuturesPosition::new(
-                    position_amt.abs(),
-                    entry_price,
-                    1.0, // contract_size
-                    Some(symbol.to_string()),
-                ));
+                // Perpetuals accrue funding; dated contracts have no premium index rate
+                let funding_rate = get_funding_rate(client, symbol).await.ok();
+
+                let mut position = Position::Futures(FuturesPosition {
+                    leverage,
+                    margin_mode,
+                    funding_rate,
+                    funding_horizon_days: funding_rate
+                        .map(|_| crate::models::CalculationSettings::default().days_to_expiry),
+                    ..FuturesPosition::new(
+                        position_amt.abs(),
+                        entry_price,
+                        1.0, // contract_size
+                        Some(symbol.to_string()),
+                    )
+                });
+                position.set_underlying(Some(symbol.to_string()));
                 positions.push(position);
             }
         }
@@ -853,6 +884,9 @@ async fn import_options_positions(
         .as_array()
         .ok_or("Invalid options response format")?;
 
+    // Contract units and settlement assets; without them options fall back to one unit
+    let contracts = get_option_contracts(client).await.unwrap_or_default();
+
     let mut positions = Vec::new();
     
     for position_value in positions_data {
@@ -866,7 +900,8 @@ async fn import_options_positions(
             let strike_price: f64 = strike_price_str.parse().unwrap_or(0.0);
             
             // Check if this symbol matches any of our selected pairs and has open position
-            if quantity > 0.0 && pairs.iter().any(|pair| symbol.contains(&pair.replace("USDT", ""))) {
+            let pair = pairs.iter().find(|pair| symbol.contains(&pair.replace("USDT", "")));
+            if let Some(pair) = pair.filter(|_| quantity > 0.0) {
                 // Determine option type from side string
                 let opt_type = if option_type.to_lowercase().contains("call") { 
                     OptionType::Call 
@@ -874,13 +909,30 @@ async fn import_options_positions(
                     OptionType::Put 
                 };
                 
-                let position = Position::Option(OptionPosition::new(
-                    opt_type,
-                    quantity,
-                    strike_price,
-                    0.0, // premium - default to 0 since we don't have this data
-                    Some(symbol.to_string()),
-                ));
+                // Price the premium from Binance's mark IV when it is available
+                let expiry_ms = position_obj["expiryDate"].as_i64().unwrap_or(0);
+                let (premium, implied_volatility) =
+                    match price_option_from_mark_iv(client, symbol, opt_type, strike_price, expiry_ms).await {
+                        Ok((premium, iv)) => (premium, Some(iv)),
+                        Err(_) => (0.0, None), // premium - default to 0 when mark data is unavailable
+                    };
+
+                let contract = contracts.get(symbol);
+                let mut position = Position::Option(OptionPosition {
+                    implied_volatility,
+                    expiry: chrono::DateTime::from_timestamp_millis(expiry_ms).filter(|_| expiry_ms > 0),
+                    multiplier: contract.map(|(unit, _)| *unit),
+                    settlement_currency: contract.map(|(_, quote_asset)| quote_asset.clone()),
+                    ..OptionPosition::new(
+                        opt_type,
+                        quantity,
+                        strike_price,
+                        premium,
+                        Some(symbol.to_string()),
+                    )
+                });
+                // Options settle against the index of the pair they were matched to
+                position.set_underlying(Some(pair.clone()));
                 positions.push(position);
             }
         }
@@ -889,6 +941,125 @@ async fn import_options_positions(
     Ok(positions)
 }
 
+/// Contract unit and quote (settlement) asset of every option symbol from Binance's exchange info
+async fn get_option_contracts(
+    client: &reqwest::Client,
+) -> Result<std::collections::HashMap<String, (f64, String)>, String> {
+    let url = "http://127.0.0.1:3001/api/binance/options/exchangeInfo";
+
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Network error: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err("Failed to get options exchange info".to_string());
+    }
+
+    let exchange_info: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse exchange info response: {}", e))?;
+
+    let symbols = exchange_info["optionSymbols"]
+        .as_array()
+        .ok_or("Option symbols not found in response")?;
+
+    Ok(symbols
+        .iter()
+        .filter_map(|contract| {
+            let symbol = contract["symbol"].as_str()?;
+            // eapi/v1/exchangeInfo reports the unit as a number; accept strings too
+            let unit = contract["unit"]
+                .as_f64()
+                .or_else(|| contract["unit"].as_str()?.parse().ok())
+                .filter(|unit| *unit > 0.0)?;
+            let quote_asset = contract["quoteAsset"].as_str().unwrap_or("USDT");
+            Some((symbol.to_string(), (unit, quote_asset.to_string())))
+        })
+        .collect())
+}
+
+/// Premium and IV for an option from Binance's mark IV and the current underlying price
+async fn price_option_from_mark_iv(
+    client: &reqwest::Client,
+    symbol: &str,
+    option_type: OptionType,
+    strike_price: f64,
+    expiry_ms: i64,
+) -> Result<(f64, f64), String> {
+    let url = format!("http://127.0.0.1:3001/api/binance/options/mark?symbol={}", symbol);
+
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Network error: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err("Failed to get option mark".to_string());
+    }
+
+    let mark_data: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse mark response: {}", e))?;
+
+    // eapi/v1/mark returns an array even for a single symbol
+    let mark_iv: f64 = mark_data[0]["markIV"]
+        .as_str()
+        .ok_or("Mark IV not found in response")?
+        .parse()
+        .map_err(|e| format!("Failed to parse mark IV: {}", e))?;
+
+    // Option symbols look like BTC-250328-80000-C; the underlying trades as BTCUSDT
+    let underlying = format!("{}USDT", symbol.split('-').next().unwrap_or_default());
+    let underlying_price = get_current_price(&underlying).await?;
+
+    let now_ms = chrono::Utc::now().timestamp_millis();
+    let time_to_expiry = (expiry_ms - now_ms).max(0) as f64 / (365.0 * 24.0 * 60.0 * 60.0 * 1000.0);
+    let risk_free_rate = crate::models::CalculationSettings::default().risk_free_rate;
+
+    let premium = PricingEngine::black_scholes_price(
+        option_type,
+        underlying_price,
+        strike_price,
+        time_to_expiry,
+        risk_free_rate,
+        mark_iv,
+    );
+
+    Ok((premium, mark_iv))
+}
+
+/// Current funding rate per 8h interval from Binance's premium index
+async fn get_funding_rate(client: &reqwest::Client, symbol: &str) -> Result<f64, String> {
+    let url = format!("http://127.0.0.1:3001/api/binance/futures/premiumIndex?symbol={}", symbol);
+
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Network error: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err("Failed to get premium index".to_string());
+    }
+
+    let index_data: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse premium index response: {}", e))?;
+
+    // Delivery contracts report an empty funding rate
+    index_data["lastFundingRate"]
+        .as_str()
+        .ok_or("Funding rate not found in response")?
+        .parse::<f64>()
+        .map_err(|e| format!("Failed to parse funding rate: {}", e))
+}
+
 async fn get_current_price(symbol: &str) -> Result<f64, String> {
     let client = reqwest::Client::new();
     
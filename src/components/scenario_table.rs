@@ -0,0 +1,196 @@
+use crate::engine::ScenarioReport;
+use crate::models::{ExportFormat, ScenarioGrid};
+use dioxus::prelude::*;
+
+#[derive(Props, Clone, PartialEq)]
+pub struct ScenarioTableProps {
+    pub report: ScenarioReport, // Scenario P&L on the chart's positions
+    pub on_grid_change: EventHandler<ScenarioGrid>,
+    pub on_export: EventHandler<ExportFormat>,
+}
+
+pub fn ScenarioTable(props: ScenarioTableProps) -> Element {
+    let report = props.report.clone();
+    let grid = report.grid.clone();
+    let max_abs_pnl = report.max_abs_pnl();
+
+    // Axes are edited as comma-separated lists in display units
+    let price_moves_text = axis_text(&grid.price_moves, 100.0);
+    let volatility_shifts_text = axis_text(&grid.volatility_shifts, 100.0);
+    let days_forward_text = axis_text(&grid.days_forward, 1.0);
+
+    let price_grid = grid.clone();
+    let volatility_grid = grid.clone();
+    let days_grid = grid.clone();
+
+    rsx! {
+        div {
+            class: "scenario-report",
+
+            div {
+                class: "scenario-header",
+                h3 { "Scenario Analysis" }
+                div {
+                    class: "scenario-export",
+                    button {
+                        class: "btn btn-sm btn-outline-secondary",
+                        onclick: move |_| props.on_export.call(ExportFormat::CSV),
+                        "Export CSV"
+                    }
+                    button {
+                        class: "btn btn-sm btn-outline-secondary",
+                        onclick: move |_| props.on_export.call(ExportFormat::JSON),
+                        "Export JSON"
+                    }
+                }
+            }
+
+            div {
+                class: "scenario-axes",
+                div {
+                    class: "form-group",
+                    label { r#for: "scenario-price-moves", "Price Moves (%)" }
+                    input {
+                        id: "scenario-price-moves",
+                        class: "form-control",
+                        r#type: "text",
+                        value: "{price_moves_text}",
+                        onchange: move |e| {
+                            // Prices can fall at most to zero
+                            if let Some(price_moves) = parse_axis(&e.value(), 100.0)
+                                .filter(|moves| moves.iter().all(|price_move| *price_move > -1.0))
+                            {
+                                props.on_grid_change.call(ScenarioGrid { price_moves, ..price_grid.clone() });
+                            }
+                        }
+                    }
+                }
+                div {
+                    class: "form-group",
+                    label { r#for: "scenario-volatility-shifts", "IV Shifts (vol points)" }
+                    input {
+                        id: "scenario-volatility-shifts",
+                        class: "form-control",
+                        r#type: "text",
+                        value: "{volatility_shifts_text}",
+                        onchange: move |e| {
+                            if let Some(volatility_shifts) = parse_axis(&e.value(), 100.0) {
+                                props.on_grid_change.call(ScenarioGrid { volatility_shifts, ..volatility_grid.clone() });
+                            }
+                        }
+                    }
+                }
+                div {
+                    class: "form-group",
+                    label { r#for: "scenario-days-forward", "Days Forward" }
+                    input {
+                        id: "scenario-days-forward",
+                        class: "form-control",
+                        r#type: "text",
+                        value: "{days_forward_text}",
+                        onchange: move |e| {
+                            // Days are measured from today
+                            if let Some(days_forward) = parse_axis(&e.value(), 1.0)
+                                .filter(|days| days.iter().all(|day| *day >= 0.0))
+                            {
+                                props.on_grid_change.call(ScenarioGrid { days_forward, ..days_grid.clone() });
+                            }
+                        }
+                    }
+                }
+            }
+
+            if let Some(worst) = report.worst {
+                div {
+                    class: "scenario-worst",
+                    "Worst scenario: "
+                    strong { class: if worst.pnl < 0.0 { "loss" } else { "profit" }, "${worst.pnl:.2}" }
+                    " at {worst.price_move * 100.0:+.1}% (${worst.underlying_price:.2}), IV {worst.volatility_shift * 100.0:+.1} pts, T+{worst.days_forward}"
+                }
+            }
+
+            div {
+                class: "scenario-table-wrapper",
+                table {
+                    class: "data-table scenario-table",
+                    thead {
+                        tr {
+                            th { rowspan: "2", "Move" }
+                            for days in grid.days_forward.iter() {
+                                th { colspan: "{grid.volatility_shifts.len()}", "T+{days}" }
+                            }
+                        }
+                        tr {
+                            for _ in grid.days_forward.iter() {
+                                for shift in grid.volatility_shifts.iter() {
+                                    th { "IV {shift * 100.0:+.0}" }
+                                }
+                            }
+                        }
+                    }
+                    tbody {
+                        for (price_index, price_move) in grid.price_moves.iter().enumerate() {
+                            tr {
+                                key: "{price_index}",
+                                th { "{price_move * 100.0:+.1}%" }
+                                for day_index in 0..grid.days_forward.len() {
+                                    for volatility_index in 0..grid.volatility_shifts.len() {
+                                        if let Some(cell) = report.cell(day_index, volatility_index, price_index) {
+                                            td {
+                                                class: if report.worst == Some(*cell) { "scenario-worst-cell" } else { "" },
+                                                style: "{heat_color(cell.pnl, max_abs_pnl)}",
+                                                title: "Underlying ${cell.underlying_price:.2}",
+                                                "{cell.pnl:.2}"
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Background shaded green for gains and red for losses, by size relative to the largest
+fn heat_color(pnl: f64, max_abs_pnl: f64) -> String {
+    if max_abs_pnl <= 0.0 || !pnl.is_finite() {
+        return String::new();
+    }
+    let alpha = (pnl.abs() / max_abs_pnl).min(1.0) * 0.6;
+    if pnl >= 0.0 {
+        format!("background-color: rgba(40, 167, 69, {:.3});", alpha)
+    } else {
+        format!("background-color: rgba(220, 53, 69, {:.3});", alpha)
+    }
+}
+
+/// Axis values as a comma-separated list, multiplied into display units
+pub(crate) fn axis_text(values: &[f64], scale: f64) -> String {
+    values
+        .iter()
+        .map(|value| format!("{}", (value * scale * 1e6).round() / 1e6))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Parse a comma-separated axis in display units; sorted, without duplicates
+pub(crate) fn parse_axis(text: &str, scale: f64) -> Option<Vec<f64>> {
+    let mut values = text
+        .split(',')
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|value| {
+            value
+                .parse::<f64>()
+                .ok()
+                .filter(|v| v.is_finite())
+                .map(|v| v / scale)
+        })
+        .collect::<Option<Vec<f64>>>()?;
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    values.dedup();
+    (!values.is_empty()).then_some(values)
+}
@@ -0,0 +1,266 @@
+use crate::engine::{
+    OptimizationConstraints, OptimizationObjective, OptimizationResult, OptimizerEngine,
+    PayoffBound, TemplateInputs, ValuationContext,
+};
+use crate::models::{Position, PositionTemplate};
+use crate::utils::web3_export_import::import;
+use dioxus::prelude::*;
+
+const OBJECTIVES: [OptimizationObjective; 3] = [
+    OptimizationObjective::ProfitProbability,
+    OptimizationObjective::ExpectedValue,
+    OptimizationObjective::RewardRisk,
+];
+
+#[derive(Props, Clone, PartialEq)]
+pub struct StrikeOptimizerProps {
+    pub templates: Vec<PositionTemplate>, // Strategy shapes to search strikes for
+    pub underlying_price: f64,            // Price the candidates are scored around
+    pub underlying: Option<String>,       // Underlying of the created legs (None = primary)
+    pub valuation: ValuationContext,      // Expiry and volatility for the lognormal metrics
+    pub on_apply: EventHandler<Vec<Position>>,
+}
+
+pub fn StrikeOptimizer(props: StrikeOptimizerProps) -> Element {
+    let mut selected_id = use_signal(String::new);
+    let mut chain_text = use_signal(String::new);
+    let mut objective = use_signal(|| OptimizationObjective::ProfitProbability);
+    let mut size = use_signal(|| 1.0);
+    let mut max_loss = use_signal(|| None::<f64>);
+    let mut min_net_credit = use_signal(|| None::<f64>);
+    let mut max_width = use_signal(|| None::<f64>);
+    let mut result = use_signal(|| None::<Result<OptimizationResult, String>>);
+
+    let selected = props
+        .templates
+        .iter()
+        .find(|template| template.id == selected_id())
+        .or(props.templates.first())
+        .cloned();
+
+    let run_template = selected.clone();
+    let run_underlying = props.underlying.clone();
+    let run_valuation = props.valuation;
+    let underlying_price = props.underlying_price;
+
+    rsx! {
+        div {
+            class: "strike-optimizer",
+            h3 { "Strike Optimizer" }
+
+            div {
+                class: "form-group",
+                label { r#for: "optimizer-template", "Strategy" }
+                select {
+                    id: "optimizer-template",
+                    class: "form-control",
+                    onchange: move |e| selected_id.set(e.value()),
+                    for template in props.templates.iter() {
+                        option {
+                            value: "{template.id}",
+                            selected: selected.as_ref().is_some_and(|selected| selected.id == template.id),
+                            "{template.name}"
+                        }
+                    }
+                }
+            }
+
+            div {
+                class: "form-group",
+                label { r#for: "optimizer-chain", "Listed Options (Type,Strike,Premium)" }
+                textarea {
+                    id: "optimizer-chain",
+                    class: "form-control",
+                    rows: "5",
+                    placeholder: "Call,100,4.5\nCall,105,2.5\nPut,95,2.5",
+                    value: "{chain_text}",
+                    oninput: move |e| chain_text.set(e.value())
+                }
+            }
+
+            div {
+                class: "range-inputs",
+                div {
+                    class: "form-group",
+                    label { r#for: "optimizer-objective", "Objective" }
+                    select {
+                        id: "optimizer-objective",
+                        class: "form-control",
+                        onchange: move |e| {
+                            if let Some(choice) = OBJECTIVES.iter().find(|choice| format!("{:?}", choice) == e.value()) {
+                                objective.set(*choice);
+                            }
+                        },
+                        for choice in OBJECTIVES.iter() {
+                            option {
+                                value: "{choice:?}",
+                                selected: objective() == *choice,
+                                "{choice.label()}"
+                            }
+                        }
+                    }
+                }
+                div {
+                    class: "form-group",
+                    label { r#for: "optimizer-size", "Size" }
+                    input {
+                        id: "optimizer-size",
+                        class: "form-control",
+                        r#type: "number",
+                        step: "any",
+                        min: "0",
+                        value: "{size}",
+                        oninput: move |e| {
+                            if let Ok(value) = e.value().parse::<f64>() {
+                                if value > 0.0 {
+                                    size.set(value);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            div {
+                class: "range-inputs",
+                div {
+                    class: "form-group",
+                    label { r#for: "optimizer-max-loss", "Max Loss ($)" }
+                    input {
+                        id: "optimizer-max-loss",
+                        class: "form-control",
+                        r#type: "number",
+                        step: "any",
+                        min: "0",
+                        placeholder: "No limit",
+                        oninput: move |e| max_loss.set(e.value().parse::<f64>().ok().filter(|value| *value >= 0.0))
+                    }
+                }
+                div {
+                    class: "form-group",
+                    label { r#for: "optimizer-min-credit", "Min Net Credit ($)" }
+                    input {
+                        id: "optimizer-min-credit",
+                        class: "form-control",
+                        r#type: "number",
+                        step: "any",
+                        placeholder: "Any",
+                        oninput: move |e| min_net_credit.set(e.value().parse::<f64>().ok().filter(|value| value.is_finite()))
+                    }
+                }
+                div {
+                    class: "form-group",
+                    label { r#for: "optimizer-max-width", "Max Width ($)" }
+                    input {
+                        id: "optimizer-max-width",
+                        class: "form-control",
+                        r#type: "number",
+                        step: "any",
+                        min: "0",
+                        placeholder: "No limit",
+                        oninput: move |e| max_width.set(e.value().parse::<f64>().ok().filter(|value| *value >= 0.0))
+                    }
+                }
+            }
+
+            button {
+                class: "btn btn-primary btn-sm",
+                disabled: selected.is_none() || chain_text().trim().is_empty(),
+                onclick: move |_| {
+                    let Some(template) = run_template.as_ref() else {
+                        return;
+                    };
+                    let inputs = TemplateInputs {
+                        underlying_price,
+                        size: size(),
+                        width: 0.0,
+                        underlying: run_underlying.clone(),
+                    };
+                    let constraints = OptimizationConstraints {
+                        max_loss: max_loss(),
+                        min_net_credit: min_net_credit(),
+                        max_width: max_width(),
+                        ..OptimizationConstraints::default()
+                    };
+                    result.set(Some(import::import_option_chain_csv(&chain_text()).and_then(|chain| {
+                        OptimizerEngine::optimize(template, &chain, &inputs, objective(), &constraints, &run_valuation)
+                    })));
+                },
+                "Find Best Strikes"
+            }
+
+            match result() {
+                Some(Ok(found)) => rsx! {
+                    small {
+                        class: "optimizer-summary",
+                        "{found.feasible} of {found.evaluated} strike combinations meet the constraints"
+                        if found.truncated {
+                            " (search stopped early - narrow the chain or set a max width)"
+                        }
+                    }
+                    if !found.candidates.is_empty() {
+                        table {
+                            class: "data-table optimizer-table",
+                            thead {
+                                tr {
+                                    th { "Strikes" }
+                                    th { "Net Credit" }
+                                    th { "Max Profit" }
+                                    th { "Max Loss" }
+                                    th { "PoP" }
+                                    th { "EV" }
+                                    th { "R/R" }
+                                    th {}
+                                }
+                            }
+                            tbody {
+                                for (i, candidate) in found.candidates.into_iter().enumerate() {
+                                    tr {
+                                        key: "{i}",
+                                        td { {format_strikes(&candidate.strikes)} }
+                                        td {
+                                            class: if candidate.net_credit >= 0.0 { "profit" } else { "loss" },
+                                            "${candidate.net_credit:.2}"
+                                        }
+                                        td { {format_bound(candidate.max_profit)} }
+                                        td { {format_bound(candidate.max_loss)} }
+                                        td { {candidate.profit_probability.map(|p| format!("{:.1}%", p * 100.0)).unwrap_or("-".to_string())} }
+                                        td { {candidate.expected_value.map(|ev| format!("${:.2}", ev)).unwrap_or("-".to_string())} }
+                                        td { {candidate.reward_risk.map(|rr| format!("{:.2}", rr)).unwrap_or("-".to_string())} }
+                                        td {
+                                            button {
+                                                class: "btn btn-outline-secondary btn-sm",
+                                                onclick: move |_| props.on_apply.call(candidate.positions.clone()),
+                                                "Add"
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                },
+                Some(Err(error)) => rsx! {
+                    div { class: "validation-errors", "{error}" }
+                },
+                None => rsx! {},
+            }
+        }
+    }
+}
+
+fn format_strikes(strikes: &[f64]) -> String {
+    strikes
+        .iter()
+        .map(|strike| format!("{}", strike))
+        .collect::<Vec<_>>()
+        .join(" / ")
+}
+
+fn format_bound(bound: Option<PayoffBound>) -> String {
+    match bound {
+        Some(PayoffBound::Bounded(value)) => format!("${value:.2}"),
+        Some(PayoffBound::Unbounded) => "Unlimited".to_string(),
+        None => "-".to_string(),
+    }
+}
@@ -0,0 +1,194 @@
+use crate::engine::{TemplateEngine, TemplateInputs, ValuationContext};
+use crate::models::{Position, PositionTemplate, TemplateCategory};
+use dioxus::prelude::*;
+
+#[derive(Props, Clone, PartialEq)]
+pub struct TemplatePickerProps {
+    pub templates: Vec<PositionTemplate>, // Built-in library followed by the user's templates
+    pub underlying_price: f64,            // Price the template's strikes are placed around
+    pub underlying: Option<String>,       // Underlying of the created legs (None = primary)
+    pub valuation: ValuationContext,      // Prices the option premiums
+    pub on_apply: EventHandler<Vec<Position>>,
+    pub on_save_current: EventHandler<String>, // Save the current positions under a name
+    pub on_delete: EventHandler<String>,       // Delete a user template by id
+}
+
+pub fn TemplatePicker(props: TemplatePickerProps) -> Element {
+    let mut selected_id = use_signal(String::new);
+    let mut size = use_signal(|| 1.0);
+    let mut width_percent = use_signal(|| 5.0);
+    let mut template_name = use_signal(String::new);
+
+    let selected = props
+        .templates
+        .iter()
+        .find(|template| template.id == selected_id())
+        .or(props.templates.first())
+        .cloned();
+    let inputs = TemplateInputs {
+        underlying_price: props.underlying_price,
+        size: size(),
+        width: width_percent() / 100.0,
+        underlying: props.underlying.clone(),
+    };
+    let preview = selected
+        .as_ref()
+        .map(|template| TemplateEngine::instantiate(template, &inputs, &props.valuation));
+
+    rsx! {
+        div {
+            class: "template-picker",
+            h3 { "Strategy Templates" }
+
+            div {
+                class: "form-group",
+                label { r#for: "template-select", "Template" }
+                select {
+                    id: "template-select",
+                    class: "form-control",
+                    onchange: move |e| selected_id.set(e.value()),
+                    for template in props.templates.iter() {
+                        option {
+                            value: "{template.id}",
+                            selected: selected.as_ref().is_some_and(|selected| selected.id == template.id),
+                            "{template.name} ({format_category(template.category)})"
+                        }
+                    }
+                }
+                if let Some(description) = selected.as_ref().and_then(|template| template.description.clone()) {
+                    small { "{description}" }
+                }
+            }
+
+            div {
+                class: "range-inputs",
+                div {
+                    class: "form-group",
+                    label { r#for: "template-size", "Size" }
+                    input {
+                        id: "template-size",
+                        class: "form-control",
+                        r#type: "number",
+                        step: "any",
+                        min: "0",
+                        value: "{size}",
+                        oninput: move |e| {
+                            if let Ok(value) = e.value().parse::<f64>() {
+                                if value > 0.0 {
+                                    size.set(value);
+                                }
+                            }
+                        }
+                    }
+                }
+                div {
+                    class: "form-group",
+                    label { r#for: "template-width", "Width (% of price)" }
+                    input {
+                        id: "template-width",
+                        class: "form-control",
+                        r#type: "number",
+                        step: "0.5",
+                        min: "0",
+                        value: "{width_percent}",
+                        oninput: move |e| {
+                            if let Ok(value) = e.value().parse::<f64>() {
+                                if value >= 0.0 {
+                                    width_percent.set(value);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            match preview {
+                Some(Ok(positions)) => rsx! {
+                    ul {
+                        class: "template-preview",
+                        for (i, position) in positions.iter().enumerate() {
+                            li { key: "{i}", {describe_leg(position)} }
+                        }
+                    }
+                    div {
+                        class: "template-actions",
+                        button {
+                            class: "btn btn-primary btn-sm",
+                            onclick: move |_| props.on_apply.call(positions.clone()),
+                            "Add to Portfolio @ ${props.underlying_price:.2}"
+                        }
+                        if let Some(template) = selected.as_ref().filter(|template| template.category == TemplateCategory::Custom) {
+                            button {
+                                class: "btn btn-outline-secondary btn-sm",
+                                onclick: {
+                                    let id = template.id.clone();
+                                    move |_| props.on_delete.call(id.clone())
+                                },
+                                "Delete Template"
+                            }
+                        }
+                    }
+                },
+                Some(Err(error)) => rsx! {
+                    div { class: "validation-errors", "{error}" }
+                },
+                None => rsx! {},
+            }
+
+            div {
+                class: "template-save",
+                input {
+                    class: "form-control",
+                    r#type: "text",
+                    placeholder: "Template name",
+                    value: "{template_name}",
+                    oninput: move |e| template_name.set(e.value())
+                }
+                button {
+                    class: "btn btn-outline-secondary btn-sm",
+                    disabled: template_name().trim().is_empty(),
+                    onclick: move |_| {
+                        props.on_save_current.call(template_name().trim().to_string());
+                        template_name.set(String::new());
+                    },
+                    "Save Current Positions as Template"
+                }
+            }
+        }
+    }
+}
+
+/// One-line summary of an instantiated leg
+fn describe_leg(position: &Position) -> String {
+    let direction = if position.quantity() >= 0.0 {
+        "Long"
+    } else {
+        "Short"
+    };
+    match position {
+        Position::Option(option) => format!(
+            "{} {} {:?} @ ${:.2} (premium ${:.2})",
+            direction,
+            option.quantity.abs(),
+            option.option_type,
+            option.strike_price,
+            option.premium
+        ),
+        _ => format!(
+            "{} {} {:?}",
+            direction,
+            position.quantity().abs(),
+            position.position_type()
+        ),
+    }
+}
+
+fn format_category(category: TemplateCategory) -> &'static str {
+    match category {
+        TemplateCategory::BasicStrategies => "Basic",
+        TemplateCategory::Options => "Options",
+        TemplateCategory::Futures => "Futures",
+        TemplateCategory::Complex => "Complex",
+        TemplateCategory::Custom => "Custom",
+    }
+}
@@ -0,0 +1,134 @@
+use crate::models::UnderlyingQuote;
+use dioxus::prelude::*;
+
+#[derive(Props, Clone, PartialEq)]
+pub struct UnderlyingSelectorProps {
+    pub underlyings: Vec<Option<String>>, // Underlyings of the positions, in order of appearance
+    pub selected: Option<String>,         // Underlying on the chart's price axis
+    pub combined: bool,                   // Beta-weight every underlying onto the selected one
+    pub primary_price: Option<f64>,       // Current price of the primary underlying
+    pub quotes: Vec<UnderlyingQuote>,     // Prices and betas of the named underlyings
+    pub on_select: EventHandler<Option<String>>,
+    pub on_combined_change: EventHandler<bool>,
+    pub on_price_change: EventHandler<(Option<String>, f64)>,
+    pub on_beta_change: EventHandler<(String, f64)>,
+}
+
+pub fn UnderlyingSelector(props: UnderlyingSelectorProps) -> Element {
+    let selected_value = props.selected.clone().unwrap_or_default();
+    let combined = props.combined;
+
+    // Underlyings off the price axis need a price, and named underlyings a beta to
+    // the primary; the axis underlying's own price comes from the chart controls
+    let rows: Vec<(Option<String>, Option<f64>, Option<f64>)> = props
+        .underlyings
+        .iter()
+        .map(|underlying| match underlying {
+            None => (None, props.primary_price, None),
+            Some(symbol) => {
+                let quote = props.quotes.iter().find(|quote| &quote.symbol == symbol);
+                (
+                    Some(symbol.clone()),
+                    quote.and_then(|quote| quote.price),
+                    Some(quote.map_or(1.0, |quote| quote.beta)),
+                )
+            }
+        })
+        .collect();
+
+    rsx! {
+        div {
+            class: "underlying-selector",
+
+            div {
+                class: "form-group",
+                label { r#for: "chart-underlying", "Underlying" }
+                select {
+                    id: "chart-underlying",
+                    class: "form-control",
+                    value: "{selected_value}",
+                    onchange: move |e| {
+                        let value = e.value();
+                        props.on_select.call(if value.is_empty() { None } else { Some(value) });
+                    },
+                    for underlying in props.underlyings.iter() {
+                        option {
+                            value: "{underlying.clone().unwrap_or_default()}",
+                            selected: *underlying == props.selected,
+                            {underlying.clone().unwrap_or_else(|| "Primary".to_string())}
+                        }
+                    }
+                }
+            }
+
+            div {
+                class: "form-group",
+                label {
+                    input {
+                        r#type: "checkbox",
+                        checked: combined,
+                        onchange: move |e| props.on_combined_change.call(e.checked())
+                    }
+                    " Combine all underlyings (β-weighted)"
+                }
+            }
+
+            if combined {
+                div {
+                    class: "underlying-quotes",
+                    for (underlying, price, beta) in rows {
+                        {
+                            let on_axis = underlying == props.selected;
+                            let label = underlying.clone().unwrap_or_else(|| "Primary".to_string());
+                            let price_target = underlying.clone();
+                            let beta_target = underlying.clone();
+                            rsx! {
+                                div {
+                                    class: "underlying-quote-row",
+                                    key: "{label}",
+                                    strong { "{label}" }
+                                    if on_axis {
+                                        span { class: "underlying-on-axis", "Price axis" }
+                                    } else {
+                                        input {
+                                            class: "form-control",
+                                            r#type: "number",
+                                            step: "any",
+                                            min: "0",
+                                            placeholder: "Current price",
+                                            value: "{price.map(|price| price.to_string()).unwrap_or_default()}",
+                                            onchange: move |e| {
+                                                if let Ok(price) = e.value().parse::<f64>() {
+                                                    if price > 0.0 {
+                                                        props.on_price_change.call((price_target.clone(), price));
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                    if let Some(beta) = beta {
+                                        input {
+                                            class: "form-control",
+                                            r#type: "number",
+                                            step: "any",
+                                            placeholder: "Beta",
+                                            title: "Return sensitivity to the primary underlying",
+                                            value: "{beta}",
+                                            onchange: move |e| {
+                                                if let (Some(symbol), Ok(beta)) = (beta_target.clone(), e.value().parse::<f64>()) {
+                                                    props.on_beta_change.call((symbol, beta));
+                                                }
+                                            }
+                                        }
+                                    } else {
+                                        span { class: "underlying-beta-fixed", "β 1.0" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,685 @@
+use super::payoff_engine::{BetaWeighting, PayoffEngine};
+use super::pricing_engine::{PricingEngine, ValuationContext, BINOMIAL_STEPS};
+use crate::models::{
+    BarrierPosition, ContractType, DigitalPosition, ExerciseStyle, FuturesPosition, OptionPosition,
+    OptionType, Position, PricingModel,
+};
+use std::ops::{Add, AddAssign};
+
+const DAYS_PER_YEAR: f64 = 365.0;
+
+/// Position sensitivities
+///
+/// Units follow trading-desk conventions: theta per calendar day, vega per
+/// 1 volatility point (1%) and rho per 1% change in the risk-free rate.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Greeks {
+    pub delta: f64,
+    pub gamma: f64,
+    pub theta: f64,
+    pub vega: f64,
+    pub rho: f64,
+}
+
+impl Greeks {
+    /// Scale all sensitivities by a factor (e.g. position quantity)
+    pub fn scaled(&self, factor: f64) -> Self {
+        Self {
+            delta: self.delta * factor,
+            gamma: self.gamma * factor,
+            theta: self.theta * factor,
+            vega: self.vega * factor,
+            rho: self.rho * factor,
+        }
+    }
+}
+
+impl Add for Greeks {
+    type Output = Greeks;
+
+    fn add(self, other: Greeks) -> Greeks {
+        Greeks {
+            delta: self.delta + other.delta,
+            gamma: self.gamma + other.gamma,
+            theta: self.theta + other.theta,
+            vega: self.vega + other.vega,
+            rho: self.rho + other.rho,
+        }
+    }
+}
+
+impl AddAssign for Greeks {
+    fn add_assign(&mut self, other: Greeks) {
+        *self = *self + other;
+    }
+}
+
+/// Portfolio Greeks at a single underlying price
+#[derive(Debug, Clone, PartialEq)]
+pub struct GreeksPoint {
+    pub price: f64,
+    pub greeks: Greeks,
+}
+
+/// Greeks calculation engine (WASM-compatible)
+pub struct GreeksEngine;
+
+impl GreeksEngine {
+    /// Calculate Greeks for a single position at a given underlying price
+    pub fn calculate_position_greeks(
+        position: &Position,
+        underlying_price: f64,
+        context: &ValuationContext,
+    ) -> Greeks {
+        match position {
+            // Linear instruments only carry delta
+            Position::Spot(spot) => Greeks { delta: spot.quantity, ..Greeks::default() },
+            Position::Futures(futures) => {
+                Self::calculate_futures_greeks(futures, underlying_price, context)
+            }
+            Position::Option(option) => {
+                Self::calculate_option_greeks(option, underlying_price, context)
+            }
+            Position::Digital(digital) => {
+                Self::calculate_digital_greeks(digital, underlying_price, context)
+            }
+            Position::Barrier(barrier) => {
+                Self::calculate_barrier_greeks(barrier, underlying_price, context)
+            }
+        }
+    }
+
+    /// Aggregate Greeks across all active positions
+    pub fn calculate_portfolio_greeks(
+        positions: &[Position],
+        underlying_price: f64,
+        context: &ValuationContext,
+    ) -> Greeks {
+        positions
+            .iter()
+            .filter(|pos| pos.is_active())
+            .map(|pos| Self::calculate_position_greeks(pos, underlying_price, context))
+            .fold(Greeks::default(), |acc, greeks| acc + greeks)
+    }
+
+    /// Portfolio Greeks against the reference underlying of a beta-weighted view
+    ///
+    /// Each underlying's delta and gamma are scaled by how far its price moves
+    /// per unit of the reference price; unpriced underlyings are left out.
+    pub fn calculate_beta_weighted_greeks(
+        positions: &[Position],
+        reference_price: f64,
+        weighting: &BetaWeighting,
+        context: &ValuationContext,
+    ) -> Greeks {
+        PayoffEngine::group_by_underlying(positions)
+            .iter()
+            .filter_map(|group| {
+                let underlying = group.underlying.as_deref();
+                let price = weighting.implied_price(underlying, reference_price)?;
+                let sensitivity = weighting.price_sensitivity(underlying)?;
+                let greeks = Self::calculate_portfolio_greeks(&group.positions, price, context);
+                Some(Greeks {
+                    delta: greeks.delta * sensitivity,
+                    gamma: greeks.gamma * sensitivity * sensitivity,
+                    ..greeks
+                })
+            })
+            .fold(Greeks::default(), |acc, greeks| acc + greeks)
+    }
+
+    /// Generate portfolio Greeks across a price range
+    pub fn generate_greeks_curve(
+        positions: &[Position],
+        price_start: f64,
+        price_end: f64,
+        step_size: f64,
+        context: &ValuationContext,
+    ) -> Vec<GreeksPoint> {
+        let mut points = Vec::new();
+        let mut current_price = price_start;
+
+        while current_price <= price_end {
+            let greeks = Self::calculate_portfolio_greeks(positions, current_price, context);
+            points.push(GreeksPoint { price: current_price, greeks });
+            current_price += step_size;
+        }
+
+        points
+    }
+
+    /// Black-Scholes Greeks for one unit of a European option
+    pub fn black_scholes_greeks(
+        option_type: OptionType,
+        spot: f64,
+        strike: f64,
+        time_to_expiry: f64,
+        risk_free_rate: f64,
+        volatility: f64,
+    ) -> Greeks {
+        // At expiry only the (step) delta of the intrinsic payoff remains
+        if time_to_expiry <= 0.0 || volatility <= 0.0 || spot <= 0.0 || strike <= 0.0 {
+            let delta = match option_type {
+                OptionType::Call if spot > strike => 1.0,
+                OptionType::Put if spot < strike => -1.0,
+                _ => 0.0,
+            };
+            return Greeks { delta, ..Greeks::default() };
+        }
+
+        let (d1, d2) =
+            PricingEngine::d1_d2(spot, strike, time_to_expiry, risk_free_rate, volatility);
+        let sqrt_t = time_to_expiry.sqrt();
+        let pdf_d1 = PricingEngine::norm_pdf(d1);
+        let discounted_strike = strike * (-risk_free_rate * time_to_expiry).exp();
+
+        let gamma = pdf_d1 / (spot * volatility * sqrt_t);
+        let vega = spot * pdf_d1 * sqrt_t / 100.0;
+        let time_decay = -spot * pdf_d1 * volatility / (2.0 * sqrt_t);
+
+        let (delta, theta, rho) = match option_type {
+            OptionType::Call => (
+                PricingEngine::norm_cdf(d1),
+                time_decay - risk_free_rate * discounted_strike * PricingEngine::norm_cdf(d2),
+                discounted_strike * time_to_expiry * PricingEngine::norm_cdf(d2),
+            ),
+            OptionType::Put => (
+                PricingEngine::norm_cdf(d1) - 1.0,
+                time_decay + risk_free_rate * discounted_strike * PricingEngine::norm_cdf(-d2),
+                -discounted_strike * time_to_expiry * PricingEngine::norm_cdf(-d2),
+            ),
+        };
+
+        Greeks {
+            delta,
+            gamma,
+            theta: theta / DAYS_PER_YEAR,
+            vega,
+            rho: rho / 100.0,
+        }
+    }
+
+    /// Greeks for one unit of an American option from the CRR binomial tree
+    ///
+    /// Delta, gamma and theta come from the tree's first nodes; vega and rho
+    /// from re-running the tree with bumped volatility and rate.
+    pub fn binomial_greeks(
+        option_type: OptionType,
+        spot: f64,
+        strike: f64,
+        time_to_expiry: f64,
+        risk_free_rate: f64,
+        volatility: f64,
+    ) -> Greeks {
+        let value = |rate: f64, vol: f64| {
+            PricingEngine::binomial_valuation(
+                option_type,
+                ExerciseStyle::American,
+                spot,
+                strike,
+                time_to_expiry,
+                rate,
+                rate,
+                vol,
+                BINOMIAL_STEPS,
+            )
+        };
+
+        if time_to_expiry <= 0.0 || volatility <= 0.0 || spot <= 0.0 {
+            return Self::black_scholes_greeks(
+                option_type,
+                spot,
+                strike,
+                time_to_expiry,
+                risk_free_rate,
+                volatility,
+            );
+        }
+
+        let tree = value(risk_free_rate, volatility);
+        let bump = 0.01;
+        let vega = (value(risk_free_rate, volatility + bump).price
+            - value(risk_free_rate, (volatility - bump).max(1e-4)).price)
+            / 2.0;
+        let rho = (value(risk_free_rate + bump, volatility).price
+            - value(risk_free_rate - bump, volatility).price)
+            / 2.0;
+
+        Greeks {
+            delta: tree.delta,
+            gamma: tree.gamma,
+            theta: tree.theta / DAYS_PER_YEAR,
+            vega,
+            rho,
+        }
+    }
+
+    // === Private helper functions ===
+
+    /// Futures Greeks; dated futures move with the forward: dF/dS = e^(basis * T)
+    fn calculate_futures_greeks(
+        futures: &FuturesPosition,
+        underlying_price: f64,
+        context: &ValuationContext,
+    ) -> Greeks {
+        let growth = context.forward_price(1.0, context.leg_time_to_expiry(futures.expiry));
+        let notional = futures.quantity * futures.contract_size;
+        // Funding on perpetuals scales with position value, like a small short
+        let carry = PayoffEngine::funding_carry(futures);
+
+        match futures.contract_type {
+            ContractType::Linear => Greeks {
+                delta: notional * growth * (1.0 - carry),
+                ..Greeks::default()
+            },
+            // Quote P&L = notional * (F / entry - 1)
+            ContractType::Inverse if !futures.pnl_in_base => Greeks {
+                delta: notional * growth / futures.entry_price,
+                ..Greeks::default()
+            },
+            // Coin P&L = notional * (1 / entry - 1 / F)
+            ContractType::Inverse => {
+                let forward = underlying_price * growth;
+                if forward <= 0.0 {
+                    return Greeks::default();
+                }
+                let notional = notional * (1.0 + carry);
+                Greeks {
+                    delta: notional * growth / forward.powi(2),
+                    gamma: -2.0 * notional * growth * growth / forward.powi(3),
+                    ..Greeks::default()
+                }
+            }
+        }
+    }
+
+    /// Greeks for an option position (per-unit Greeks scaled by quantity and multiplier)
+    fn calculate_option_greeks(
+        option: &OptionPosition,
+        underlying_price: f64,
+        context: &ValuationContext,
+    ) -> Greeks {
+        let time_to_expiry = context.leg_time_to_expiry(option.expiry);
+        let volatility = option.implied_volatility.unwrap_or(context.volatility);
+        let greeks_fn = match (
+            option.pricing_model.unwrap_or(context.pricing_model),
+            option.exercise_style,
+        ) {
+            (PricingModel::BlackScholes, ExerciseStyle::European) => Self::black_scholes_greeks,
+            (PricingModel::BlackScholes, ExerciseStyle::American) => Self::binomial_greeks,
+            (PricingModel::Black76, _) => {
+                return Self::finite_difference_greeks(
+                    option,
+                    underlying_price,
+                    time_to_expiry,
+                    context,
+                )
+                .scaled(option.units());
+            }
+        };
+
+        greeks_fn(
+            option.option_type,
+            underlying_price,
+            option.strike_price,
+            time_to_expiry,
+            context.risk_free_rate,
+            volatility,
+        )
+        .scaled(option.units())
+    }
+
+    /// Black-Scholes Greeks of a cash-or-nothing digital, scaled by payout and quantity
+    fn calculate_digital_greeks(
+        digital: &DigitalPosition,
+        spot: f64,
+        context: &ValuationContext,
+    ) -> Greeks {
+        let time_to_expiry = context.leg_time_to_expiry(digital.expiry);
+        let volatility = digital.implied_volatility.unwrap_or(context.volatility);
+        if time_to_expiry <= 0.0 || spot <= 0.0 || volatility <= 0.0 {
+            // A settled step payoff has no sensitivities away from the strike
+            return Greeks::default();
+        }
+
+        let rate = context.risk_free_rate;
+        let (d1, d2) =
+            PricingEngine::d1_d2(spot, digital.strike_price, time_to_expiry, rate, volatility);
+        let discount = (-rate * time_to_expiry).exp();
+        let density = discount * PricingEngine::norm_pdf(d2);
+        let sign = match digital.option_type {
+            OptionType::Call => 1.0,
+            OptionType::Put => -1.0,
+        };
+        let sqrt_t = time_to_expiry.sqrt();
+
+        let price = |time: f64| {
+            PricingEngine::digital_price(
+                digital.option_type,
+                spot,
+                digital.strike_price,
+                time,
+                rate,
+                volatility,
+            )
+        };
+        let one_day_later = (time_to_expiry - 1.0 / DAYS_PER_YEAR).max(0.0);
+
+        let unit = Greeks {
+            delta: sign * density / (spot * volatility * sqrt_t),
+            gamma: -sign * density * d1 / (spot * spot * volatility * volatility * time_to_expiry),
+            theta: price(one_day_later) - price(time_to_expiry),
+            vega: -sign * density * d1 / volatility * 0.01,
+            rho: (-time_to_expiry * price(time_to_expiry) + sign * density * sqrt_t / volatility)
+                * 0.01,
+        };
+
+        unit.scaled(digital.quantity * digital.payout)
+    }
+
+    /// Barrier option Greeks by bumping its valuation, scaled by quantity
+    ///
+    /// Spot is bumped by 1% so simulated legs' knock indicators don't dominate.
+    fn calculate_barrier_greeks(
+        barrier: &BarrierPosition,
+        spot: f64,
+        context: &ValuationContext,
+    ) -> Greeks {
+        if spot <= 0.0 {
+            return Greeks::default();
+        }
+
+        let time_to_expiry = context.leg_time_to_expiry(barrier.expiry);
+        let value =
+            |barrier: &BarrierPosition, spot: f64, time: f64, context: &ValuationContext| {
+                PricingEngine::value_barrier(barrier, spot, time, context)
+            };
+        let base = value(barrier, spot, time_to_expiry, context);
+
+        let h = spot * 1e-2;
+        let up = value(barrier, spot + h, time_to_expiry, context);
+        let down = value(barrier, spot - h, time_to_expiry, context);
+
+        let volatility = barrier.implied_volatility.unwrap_or(context.volatility);
+        let with_volatility =
+            |vol: f64| BarrierPosition { implied_volatility: Some(vol), ..barrier.clone() };
+        let vega = (value(
+            &with_volatility(volatility + 0.01),
+            spot,
+            time_to_expiry,
+            context,
+        ) - value(
+            &with_volatility((volatility - 0.01).max(1e-4)),
+            spot,
+            time_to_expiry,
+            context,
+        )) / 2.0;
+
+        let with_rate = |rate: f64| ValuationContext { risk_free_rate: rate, ..*context };
+        let rho = (value(
+            barrier,
+            spot,
+            time_to_expiry,
+            &with_rate(context.risk_free_rate + 0.01),
+        ) - value(
+            barrier,
+            spot,
+            time_to_expiry,
+            &with_rate(context.risk_free_rate - 0.01),
+        )) / 2.0;
+
+        let one_day_later = (time_to_expiry - 1.0 / DAYS_PER_YEAR).max(0.0);
+        let theta = value(barrier, spot, one_day_later, context) - base;
+
+        Greeks {
+            delta: (up - down) / (2.0 * h),
+            gamma: (up - 2.0 * base + down) / (h * h),
+            theta,
+            vega,
+            rho,
+        }
+        .scaled(barrier.quantity)
+    }
+
+    /// Per-unit Greeks by bumping the leg's own valuation (used for Black-76 legs)
+    ///
+    /// Delta and gamma are with respect to spot, so the futures basis is included.
+    fn finite_difference_greeks(
+        option: &OptionPosition,
+        spot: f64,
+        time_to_expiry: f64,
+        context: &ValuationContext,
+    ) -> Greeks {
+        if time_to_expiry <= 0.0 || spot <= 0.0 {
+            return Self::black_scholes_greeks(
+                option.option_type,
+                spot,
+                option.strike_price,
+                0.0,
+                0.0,
+                0.0,
+            );
+        }
+
+        let value = |option: &OptionPosition, spot: f64, time: f64, context: &ValuationContext| {
+            PricingEngine::value_option(option, spot, time, context)
+        };
+        let base = value(option, spot, time_to_expiry, context);
+
+        let h = spot * 1e-3;
+        let up = value(option, spot + h, time_to_expiry, context);
+        let down = value(option, spot - h, time_to_expiry, context);
+
+        let volatility = option.implied_volatility.unwrap_or(context.volatility);
+        let with_volatility =
+            |vol: f64| OptionPosition { implied_volatility: Some(vol), ..option.clone() };
+        let vega = (value(
+            &with_volatility(volatility + 0.01),
+            spot,
+            time_to_expiry,
+            context,
+        ) - value(
+            &with_volatility((volatility - 0.01).max(1e-4)),
+            spot,
+            time_to_expiry,
+            context,
+        )) / 2.0;
+
+        let with_rate = |rate: f64| ValuationContext { risk_free_rate: rate, ..*context };
+        let rho = (value(
+            option,
+            spot,
+            time_to_expiry,
+            &with_rate(context.risk_free_rate + 0.01),
+        ) - value(
+            option,
+            spot,
+            time_to_expiry,
+            &with_rate(context.risk_free_rate - 0.01),
+        )) / 2.0;
+
+        let one_day_later = (time_to_expiry - 1.0 / DAYS_PER_YEAR).max(0.0);
+        let theta = value(option, spot, one_day_later, context) - base;
+
+        Greeks {
+            delta: (up - down) / (2.0 * h),
+            gamma: (up - 2.0 * base + down) / (h * h),
+            theta,
+            vega,
+            rho,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::*;
+
+    #[test]
+    fn test_linear_position_delta() {
+        let context = ValuationContext::default();
+        let spot = Position::Spot(SpotPosition::new(10.0, 100.0, None));
+        let futures = Position::Futures(FuturesPosition::new(-2.0, 100.0, 5.0, None));
+
+        let greeks = GreeksEngine::calculate_portfolio_greeks(&[spot, futures], 120.0, &context);
+
+        // 10 spot units hedged by 2 short contracts of size 5
+        assert_eq!(greeks.delta, 0.0);
+        assert_eq!(greeks.gamma, 0.0);
+    }
+
+    #[test]
+    fn test_option_greeks_match_finite_differences() {
+        let (s, k, t, r, v) = (100.0, 105.0, 0.25, 0.03, 0.4);
+        let greeks = GreeksEngine::black_scholes_greeks(OptionType::Call, s, k, t, r, v);
+        let price = |s: f64, t: f64, r: f64, v: f64| {
+            PricingEngine::black_scholes_price(OptionType::Call, s, k, t, r, v)
+        };
+
+        let h = 0.01;
+        let delta = (price(s + h, t, r, v) - price(s - h, t, r, v)) / (2.0 * h);
+        let gamma =
+            (price(s + h, t, r, v) - 2.0 * price(s, t, r, v) + price(s - h, t, r, v)) / (h * h);
+        let vega = (price(s, t, r, v + 0.01) - price(s, t, r, v - 0.01)) / 2.0;
+        let theta = price(s, t - 1.0 / 365.0, r, v) - price(s, t, r, v);
+
+        assert!((greeks.delta - delta).abs() < 1e-4);
+        assert!((greeks.gamma - gamma).abs() < 1e-3);
+        assert!((greeks.vega - vega).abs() < 1e-3);
+        assert!((greeks.theta - theta).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_straddle_is_delta_neutral_at_the_money_forward() {
+        let context = ValuationContext::new(30.0, 0.0, 0.5, 0.0);
+        let call = OptionPosition::new(OptionType::Call, 1.0, 100.0, 5.0, None);
+        let put = OptionPosition::new(OptionType::Put, 1.0, 100.0, 5.0, None);
+        let positions = vec![Position::Option(call), Position::Option(put)];
+
+        // Delta of an ATM straddle is small but slightly positive (d1 > 0)
+        let greeks = GreeksEngine::calculate_portfolio_greeks(&positions, 100.0, &context);
+        assert!(greeks.delta > 0.0 && greeks.delta < 0.1);
+        assert!(greeks.gamma > 0.0);
+        assert!(greeks.theta < 0.0);
+
+        let curve = GreeksEngine::generate_greeks_curve(&positions, 80.0, 120.0, 10.0, &context);
+        assert_eq!(curve.len(), 5);
+        assert!(curve[0].greeks.delta < 0.0 && curve[4].greeks.delta > 0.0);
+    }
+
+    #[test]
+    fn test_american_greeks_from_tree() {
+        let context = ValuationContext::new(365.0, 0.0, 0.3, 0.08);
+        let put = OptionPosition {
+            exercise_style: ExerciseStyle::American,
+            ..OptionPosition::new(OptionType::Put, 2.0, 100.0, 25.0, None)
+        };
+        let unit_put = OptionPosition { quantity: 1.0, ..put.clone() };
+        let position = Position::Option(put);
+
+        let greeks = GreeksEngine::calculate_position_greeks(&position, 80.0, &context);
+        let european =
+            GreeksEngine::black_scholes_greeks(OptionType::Put, 80.0, 100.0, 1.0, 0.08, 0.3);
+
+        // Early exercise pushes a deep ITM put's delta towards -1 per unit
+        assert!(greeks.delta / 2.0 < european.delta);
+        assert!(greeks.delta / 2.0 > -1.0 - 1e-9);
+        assert!(greeks.gamma > 0.0 && greeks.vega > 0.0 && greeks.rho < 0.0);
+
+        // Tree delta agrees with a finite difference of tree prices
+        let price = |s: f64| PricingEngine::value_option(&unit_put, s, 1.0, &context);
+        let fd_delta = (price(80.5) - price(79.5)) / 1.0;
+        assert!((greeks.delta / 2.0 - fd_delta).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_black76_greeks_include_basis() {
+        let context = ValuationContext {
+            pricing_model: PricingModel::Black76,
+            futures_basis: 0.2,
+            ..ValuationContext::new(91.25, 0.0, 0.5, 0.0)
+        };
+        let call = Position::Option(OptionPosition::new(OptionType::Call, 1.0, 100.0, 0.0, None));
+        let greeks = GreeksEngine::calculate_position_greeks(&call, 100.0, &context);
+
+        // Spot delta = forward delta * dF/dS with F = S * e^(0.2 * 0.25)
+        let growth = (0.2_f64 * 0.25).exp();
+        let forward_greeks = GreeksEngine::black_scholes_greeks(
+            OptionType::Call,
+            100.0 * growth,
+            100.0,
+            0.25,
+            0.0,
+            0.5,
+        );
+        assert!((greeks.delta - forward_greeks.delta * growth).abs() < 1e-4);
+        assert!((greeks.vega - forward_greeks.vega).abs() < 1e-3);
+
+        // A dated futures leg hedges with the same growth factor
+        let futures = Position::Futures(FuturesPosition {
+            expiry: Some(context.reference_date + chrono::Duration::hours(24 * 91 + 6)),
+            ..FuturesPosition::new(-1.0, 100.0, 1.0, None)
+        });
+        let futures_delta =
+            GreeksEngine::calculate_position_greeks(&futures, 100.0, &context).delta;
+        assert!((futures_delta + growth).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_digital_greeks_match_finite_differences() {
+        let context = ValuationContext::new(60.0, 0.0, 0.5, 0.03);
+        let digital = DigitalPosition::new(OptionType::Put, -3.0, 95.0, 10.0, 4.0, None);
+        let position = Position::Digital(digital);
+        let greeks = GreeksEngine::calculate_position_greeks(&position, 100.0, &context);
+
+        let value = |spot: f64, context: &ValuationContext| {
+            PayoffEngine::calculate_single_value(&position, spot, context)
+        };
+        let h = 0.01;
+        let delta = (value(100.0 + h, &context) - value(100.0 - h, &context)) / (2.0 * h);
+        let gamma = (value(100.0 + h, &context) - 2.0 * value(100.0, &context)
+            + value(100.0 - h, &context))
+            / (h * h);
+        let with_volatility = |volatility: f64| ValuationContext { volatility, ..context };
+        let vega =
+            (value(100.0, &with_volatility(0.51)) - value(100.0, &with_volatility(0.49))) / 2.0;
+        let with_rate = |risk_free_rate: f64| ValuationContext { risk_free_rate, ..context };
+        let rho = (value(100.0, &with_rate(0.04)) - value(100.0, &with_rate(0.02))) / 2.0;
+
+        assert!((greeks.delta - delta).abs() < 1e-5);
+        assert!((greeks.gamma - gamma).abs() < 1e-4);
+        assert!((greeks.vega - vega).abs() < 1e-3);
+        assert!((greeks.rho - rho).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_beta_weighted_greeks_match_finite_differences() {
+        let context = ValuationContext::new(30.0, 0.0, 0.8, 0.05);
+        let mut sol =
+            Position::Option(OptionPosition::new(OptionType::Call, 10.0, 22.0, 1.0, None));
+        sol.set_underlying(Some("SOLUSDT".to_string()));
+        let positions = vec![Position::Spot(SpotPosition::new(1.0, 100.0, None)), sol];
+        let settings = crate::models::CalculationSettings {
+            underlying_quotes: vec![UnderlyingQuote {
+                symbol: "SOLUSDT".to_string(),
+                price: Some(20.0),
+                beta: 1.5,
+            }],
+            ..Default::default()
+        };
+        let weighting = BetaWeighting::from_settings(&settings, None, 100.0);
+        let greeks =
+            GreeksEngine::calculate_beta_weighted_greeks(&positions, 100.0, &weighting, &context);
+
+        let value = |price: f64| {
+            PayoffEngine::calculate_beta_weighted_value(&positions, price, &weighting, &context)
+        };
+        let h = 0.01;
+        let delta = (value(100.0 + h) - value(100.0 - h)) / (2.0 * h);
+        let gamma = (value(100.0 + h) - 2.0 * value(100.0) + value(100.0 - h)) / (h * h);
+
+        assert!((greeks.delta - delta).abs() < 1e-5);
+        assert!((greeks.gamma - gamma).abs() < 1e-4);
+    }
+}
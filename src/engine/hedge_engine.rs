@@ -0,0 +1,350 @@
+use super::greeks_engine::{Greeks, GreeksEngine};
+use super::optimizer_engine::ListedOption;
+use super::payoff_engine::BetaWeighting;
+use super::pricing_engine::ValuationContext;
+use crate::models::{FuturesPosition, OptionPosition, Position, SpotPosition};
+
+/// Sensitivities at or below this size are treated as already neutral
+const NEUTRAL_TOLERANCE: f64 = 1e-9;
+
+/// Smallest normalized determinant for two options to offset gamma and vega separately
+const MIN_PAIR_DETERMINANT: f64 = 1e-6;
+
+/// Instrument the hedge can trade on the reference underlying
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HedgeInstrument {
+    Spot,
+    Perpetual,            // Linear perpetual, one underlying unit per contract
+    Option(ListedOption), // Listed option at its quoted premium
+}
+
+/// Sensitivities to neutralize besides delta
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct HedgeTargets {
+    pub gamma: bool,
+    pub vega: bool,
+}
+
+/// Proposed hedge trades and the Greeks they leave behind
+#[derive(Debug, Clone, PartialEq)]
+pub struct HedgeSuggestion {
+    pub trades: Vec<Position>, // Proposed positions, inactive until the user switches them on
+    pub before: Greeks,        // Portfolio Greeks today at the current price
+    pub after: Greeks,         // Greeks with the trades added
+}
+
+/// Delta-neutral hedge engine (WASM-compatible)
+pub struct HedgeEngine;
+
+impl HedgeEngine {
+    /// Trades that bring the active positions' delta (and optionally gamma / vega)
+    /// to zero at the current price, valued today
+    ///
+    /// Gamma and vega are offset with listed options first, then the first spot or
+    /// perpetual instrument takes the remaining delta. When no pair of listed options
+    /// separates gamma from vega (one expiry at one volatility), gamma is neutralized
+    /// and the vega left over shows in `after`. With a beta weighting the Greeks are
+    /// measured against the reference underlying the trades are placed on.
+    pub fn suggest(
+        positions: &[Position],
+        underlying_price: f64,
+        instruments: &[HedgeInstrument],
+        targets: HedgeTargets,
+        context: &ValuationContext,
+        weighting: Option<&BetaWeighting>,
+        underlying: Option<String>,
+    ) -> Result<HedgeSuggestion, String> {
+        if underlying_price <= 0.0 {
+            return Err("Underlying price must be positive".to_string());
+        }
+
+        let today = ValuationContext { days_elapsed: 0.0, ..*context };
+        let before = match weighting {
+            Some(weighting) => GreeksEngine::calculate_beta_weighted_greeks(
+                positions,
+                underlying_price,
+                weighting,
+                &today,
+            ),
+            None => GreeksEngine::calculate_portfolio_greeks(positions, underlying_price, &today),
+        };
+
+        // One-unit position and its Greeks for every instrument on offer
+        let units: Vec<(Position, Greeks)> = instruments
+            .iter()
+            .map(|instrument| {
+                let unit = Self::unit_position(instrument, underlying_price);
+                let greeks =
+                    GreeksEngine::calculate_position_greeks(&unit, underlying_price, &today);
+                (unit, greeks)
+            })
+            .collect();
+        let options: Vec<usize> = (0..units.len())
+            .filter(|&i| matches!(units[i].0, Position::Option(_)))
+            .collect();
+        let linear = (0..units.len()).find(|&i| !matches!(units[i].0, Position::Option(_)));
+
+        let mut trades: Vec<(usize, f64, &str)> = Vec::new();
+
+        // Gamma and vega first: options also carry delta the linear leg picks up
+        match (targets.gamma, targets.vega) {
+            (false, false) => {}
+            _ if options.is_empty() => {
+                return Err("Listed options are needed to hedge gamma or vega".to_string())
+            }
+            (true, true) => match Self::best_pair(&units, &options) {
+                Some((first, second)) => {
+                    let (g1, v1) = (units[first].1.gamma, units[first].1.vega);
+                    let (g2, v2) = (units[second].1.gamma, units[second].1.vega);
+                    let det = g1 * v2 - g2 * v1;
+                    let q1 = (-before.gamma * v2 + before.vega * g2) / det;
+                    let q2 = (-before.vega * g1 + before.gamma * v1) / det;
+                    trades.push((first, q1, "Gamma/Vega"));
+                    trades.push((second, q2, "Gamma/Vega"));
+                }
+                None => {
+                    let (index, quantity) =
+                        Self::single_option(&units, &options, before.gamma, |g| g.gamma)?;
+                    trades.push((index, quantity, "Gamma"));
+                }
+            },
+            (true, false) => {
+                let (index, quantity) =
+                    Self::single_option(&units, &options, before.gamma, |g| g.gamma)?;
+                trades.push((index, quantity, "Gamma"));
+            }
+            (false, true) => {
+                let (index, quantity) =
+                    Self::single_option(&units, &options, before.vega, |g| g.vega)?;
+                trades.push((index, quantity, "Vega"));
+            }
+        }
+
+        let remaining_delta = before.delta
+            + trades
+                .iter()
+                .map(|&(index, quantity, _)| units[index].1.delta * quantity)
+                .sum::<f64>();
+        if remaining_delta.abs() > NEUTRAL_TOLERANCE {
+            match linear {
+                Some(index) => {
+                    trades.push((index, -remaining_delta / units[index].1.delta, "Delta"))
+                }
+                None if trades.is_empty() && !options.is_empty() => {
+                    let (index, quantity) =
+                        Self::single_option(&units, &options, remaining_delta, |g| g.delta)?;
+                    trades.push((index, quantity, "Delta"));
+                }
+                None if instruments.is_empty() => {
+                    return Err("No hedge instruments selected".to_string())
+                }
+                None => {
+                    return Err("A spot or perpetual is needed to offset the delta left".to_string())
+                }
+            }
+        }
+
+        let mut after = before;
+        let mut positions_out = Vec::new();
+        for (index, quantity, purpose) in trades {
+            if quantity.abs() <= NEUTRAL_TOLERANCE {
+                continue;
+            }
+            after += units[index].1.scaled(quantity);
+            positions_out.push(Self::trade(
+                &units[index].0,
+                quantity,
+                purpose,
+                underlying.clone(),
+            ));
+        }
+
+        Ok(HedgeSuggestion { trades: positions_out, before, after })
+    }
+
+    // === Private helper functions ===
+
+    /// One long unit of the instrument, entered at the current price
+    fn unit_position(instrument: &HedgeInstrument, underlying_price: f64) -> Position {
+        match instrument {
+            HedgeInstrument::Spot => Position::Spot(SpotPosition::new(1.0, underlying_price, None)),
+            HedgeInstrument::Perpetual => {
+                Position::Futures(FuturesPosition::new(1.0, underlying_price, 1.0, None))
+            }
+            HedgeInstrument::Option(listed) => Position::Option(OptionPosition::new(
+                listed.option_type,
+                1.0,
+                listed.strike,
+                listed.premium,
+                None,
+            )),
+        }
+    }
+
+    /// Option with the largest unit exposure to one sensitivity, sized to offset it
+    fn single_option(
+        units: &[(Position, Greeks)],
+        options: &[usize],
+        exposure: f64,
+        sensitivity: fn(&Greeks) -> f64,
+    ) -> Result<(usize, f64), String> {
+        let index = options
+            .iter()
+            .copied()
+            .max_by(|&a, &b| {
+                sensitivity(&units[a].1)
+                    .abs()
+                    .partial_cmp(&sensitivity(&units[b].1).abs())
+                    .unwrap()
+            })
+            .filter(|&index| sensitivity(&units[index].1).abs() > NEUTRAL_TOLERANCE)
+            .ok_or("Listed options carry no exposure to hedge with")?;
+        Ok((index, -exposure / sensitivity(&units[index].1)))
+    }
+
+    /// Pair of options whose gamma / vega mix is furthest from proportional
+    fn best_pair(units: &[(Position, Greeks)], options: &[usize]) -> Option<(usize, usize)> {
+        let mut best: Option<(usize, usize, f64)> = None;
+        for (n, &first) in options.iter().enumerate() {
+            for &second in &options[n + 1..] {
+                let (a, b) = (&units[first].1, &units[second].1);
+                let scale = (a.gamma * b.vega).abs() + (b.gamma * a.vega).abs();
+                if scale <= 0.0 {
+                    continue;
+                }
+                let separation = (a.gamma * b.vega - b.gamma * a.vega).abs() / scale;
+                if best.is_none_or(|(_, _, current)| separation > current) {
+                    best = Some((first, second, separation));
+                }
+            }
+        }
+        best.filter(|&(_, _, separation)| separation > MIN_PAIR_DETERMINANT)
+            .map(|(first, second, _)| (first, second))
+    }
+
+    /// Proposed trade: the unit instrument scaled to the quantity, inactive
+    fn trade(
+        unit: &Position,
+        quantity: f64,
+        purpose: &str,
+        underlying: Option<String>,
+    ) -> Position {
+        let description = format!("Hedge - {}", purpose);
+        let mut position = match unit {
+            Position::Spot(spot) => {
+                Position::Spot(SpotPosition { quantity, description, ..spot.clone() })
+            }
+            Position::Futures(futures) => {
+                Position::Futures(FuturesPosition { quantity, description, ..futures.clone() })
+            }
+            Position::Option(option) => {
+                Position::Option(OptionPosition { quantity, description, ..option.clone() })
+            }
+            other => other.clone(),
+        };
+        position.set_underlying(underlying);
+        position.set_active(false);
+        position
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::*;
+
+    fn listed(option_type: OptionType, strike: f64, premium: f64) -> HedgeInstrument {
+        HedgeInstrument::Option(ListedOption { option_type, strike, premium })
+    }
+
+    #[test]
+    fn test_delta_hedge_with_perpetual() {
+        let call = OptionPosition::new(OptionType::Call, 2.0, 100.0, 5.0, None);
+        let positions = vec![Position::Option(call)];
+        let context = ValuationContext::new(30.0, 0.0, 0.5, 0.05);
+
+        let suggestion = HedgeEngine::suggest(
+            &positions,
+            100.0,
+            &[HedgeInstrument::Perpetual],
+            HedgeTargets::default(),
+            &context,
+            None,
+            None,
+        )
+        .unwrap();
+
+        assert!(suggestion.before.delta > 1.0);
+        assert_eq!(suggestion.trades.len(), 1);
+        let trade = &suggestion.trades[0];
+        assert!(matches!(trade, Position::Futures(_)));
+        assert!(!trade.is_active());
+        assert!((trade.quantity() + suggestion.before.delta).abs() < 1e-9);
+        assert!(suggestion.after.delta.abs() < 1e-9);
+        assert_eq!(suggestion.after.gamma, suggestion.before.gamma);
+
+        // Activating the trade flattens the portfolio delta
+        let mut hedged = positions.clone();
+        let mut activated = trade.clone();
+        activated.set_active(true);
+        hedged.push(activated);
+        let greeks = GreeksEngine::calculate_portfolio_greeks(&hedged, 100.0, &context);
+        assert!(greeks.delta.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_gamma_hedge_with_options_and_spot() {
+        let positions = vec![
+            Position::Option(OptionPosition::new(
+                OptionType::Call,
+                -1.0,
+                100.0,
+                5.0,
+                None,
+            )),
+            Position::Option(OptionPosition::new(OptionType::Put, -2.0, 100.0, 5.0, None)),
+        ];
+        let context = ValuationContext::new(30.0, 0.0, 0.5, 0.05);
+        let instruments = [
+            HedgeInstrument::Spot,
+            listed(OptionType::Call, 95.0, 8.0),
+            listed(OptionType::Call, 100.0, 5.0),
+            listed(OptionType::Put, 105.0, 8.0),
+        ];
+        let targets = HedgeTargets { gamma: true, vega: true };
+
+        let suggestion = HedgeEngine::suggest(
+            &positions,
+            100.0,
+            &instruments,
+            targets,
+            &context,
+            None,
+            Some("btc".to_string()),
+        )
+        .unwrap();
+
+        // One expiry at one volatility: gamma is offset, vega follows it to zero
+        assert!(suggestion.before.gamma < 0.0);
+        assert!(suggestion.after.gamma.abs() < 1e-9);
+        assert!(suggestion.after.vega.abs() < 1e-6);
+        assert!(suggestion.after.delta.abs() < 1e-9);
+        assert!(suggestion
+            .trades
+            .iter()
+            .all(|trade| !trade.is_active() && trade.underlying() == Some("BTC")));
+        assert!(matches!(suggestion.trades.last(), Some(Position::Spot(_))));
+
+        // Gamma cannot be offset with a linear instrument alone
+        let error = HedgeEngine::suggest(
+            &positions,
+            100.0,
+            &[HedgeInstrument::Spot],
+            targets,
+            &context,
+            None,
+            None,
+        );
+        assert!(error.is_err());
+    }
+}
@@ -0,0 +1,329 @@
+use crate::models::{ContractType, FuturesPosition, MaintenanceTier, MarginMode, Position};
+
+/// Binance USDT-M style brackets (notional and deduction in quote)
+const DEFAULT_LINEAR_TIERS: [MaintenanceTier; 6] = [
+    MaintenanceTier { max_notional: Some(50_000.0), rate: 0.004, deduction: 0.0 },
+    MaintenanceTier { max_notional: Some(500_000.0), rate: 0.005, deduction: 50.0 },
+    MaintenanceTier {
+        max_notional: Some(8_000_000.0),
+        rate: 0.01,
+        deduction: 2_550.0,
+    },
+    MaintenanceTier {
+        max_notional: Some(50_000_000.0),
+        rate: 0.025,
+        deduction: 122_550.0,
+    },
+    MaintenanceTier {
+        max_notional: Some(80_000_000.0),
+        rate: 0.05,
+        deduction: 1_372_550.0,
+    },
+    MaintenanceTier { max_notional: None, rate: 0.1, deduction: 5_372_550.0 },
+];
+
+/// Coin-margined bracket (notional and deduction in the base coin)
+const DEFAULT_INVERSE_TIERS: [MaintenanceTier; 1] =
+    [MaintenanceTier { max_notional: None, rate: 0.005, deduction: 0.0 }];
+
+/// Grid used to bracket cross-margin liquidation prices, as multiples of the
+/// largest entry price
+const CROSS_SCAN_MIN: f64 = 1e-3;
+const CROSS_SCAN_MAX: f64 = 1e3;
+const CROSS_SCAN_POINTS: usize = 2000;
+const BISECTION_ITERATIONS: usize = 100;
+
+/// Liquidation and maintenance margin engine for leveraged futures
+///
+/// Amounts are in the margin currency of each contract: quote for linear
+/// contracts and the base coin for inverse ones.
+pub struct LiquidationEngine;
+
+impl LiquidationEngine {
+    /// Margin posted for a leveraged leg (None when unleveraged)
+    pub fn initial_margin(futures: &FuturesPosition) -> Option<f64> {
+        let leverage = futures.leverage.filter(|leverage| *leverage > 0.0)?;
+        Some(Self::notional(futures, futures.entry_price) / leverage)
+    }
+
+    /// Maintenance margin required at a price, from the leg's notional bracket
+    pub fn maintenance_margin(futures: &FuturesPosition, price: f64) -> f64 {
+        let notional = Self::notional(futures, price);
+        let tier = Self::tier_for(futures, notional);
+        (notional * tier.rate - tier.deduction).max(0.0)
+    }
+
+    /// Price at which the leg is liquidated on its own margin (None when it never is)
+    pub fn liquidation_price(futures: &FuturesPosition) -> Option<f64> {
+        let margin = Self::initial_margin(futures)?;
+        let quantity = futures.quantity * futures.contract_size;
+        let tiers = Self::tiers(futures);
+
+        // Solve margin + pnl(price) = maintenance(price) within each bracket and
+        // keep the first solution that actually falls inside it
+        let mut lower_notional = 0.0;
+        for tier in tiers {
+            let upper_notional = tier.max_notional.unwrap_or(f64::INFINITY);
+            let candidate = match futures.contract_type {
+                ContractType::Linear => {
+                    (quantity * futures.entry_price - margin - tier.deduction)
+                        / (quantity - quantity.abs() * tier.rate)
+                }
+                ContractType::Inverse => {
+                    (quantity + quantity.abs() * tier.rate)
+                        / (margin + quantity / futures.entry_price + tier.deduction)
+                }
+            };
+
+            if candidate.is_finite() && candidate > 0.0 {
+                let notional = Self::notional(futures, candidate);
+                if notional > lower_notional && notional <= upper_notional {
+                    return Some(candidate);
+                }
+            }
+            lower_notional = upper_notional;
+        }
+
+        None
+    }
+
+    /// Prices at which the cross-margin wallets (linear and inverse) run out of margin
+    pub fn cross_liquidation_prices(positions: &[Position]) -> Vec<f64> {
+        let mut prices: Vec<f64> = [ContractType::Linear, ContractType::Inverse]
+            .into_iter()
+            .flat_map(|contract_type| Self::cross_wallet_roots(positions, contract_type))
+            .collect();
+        prices.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        prices
+    }
+
+    /// Every liquidation price in the portfolio: isolated legs and cross wallets
+    pub fn portfolio_liquidation_prices(positions: &[Position]) -> Vec<f64> {
+        let mut prices: Vec<f64> = positions
+            .iter()
+            .filter_map(|pos| match pos {
+                Position::Futures(futures)
+                    if futures.active && futures.margin_mode == MarginMode::Isolated =>
+                {
+                    Self::liquidation_price(futures)
+                }
+                _ => None,
+            })
+            .chain(Self::cross_liquidation_prices(positions))
+            .collect();
+        prices.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        prices
+    }
+
+    /// Which positions have been liquidated at a price (aligned with `positions`)
+    pub fn liquidated_legs(positions: &[Position], price: f64) -> Vec<bool> {
+        let linear_cross = Self::cross_equity(positions, ContractType::Linear, price);
+        let inverse_cross = Self::cross_equity(positions, ContractType::Inverse, price);
+
+        positions
+            .iter()
+            .map(|pos| match pos {
+                Position::Futures(futures) if pos.is_active() => {
+                    let Some(margin) = Self::initial_margin(futures) else {
+                        return false;
+                    };
+                    match futures.margin_mode {
+                        MarginMode::Isolated => {
+                            margin + Self::margin_pnl(futures, price)
+                                < Self::maintenance_margin(futures, price)
+                        }
+                        MarginMode::Cross => {
+                            let equity = match futures.contract_type {
+                                ContractType::Linear => linear_cross,
+                                ContractType::Inverse => inverse_cross,
+                            };
+                            equity.is_some_and(|equity| equity < 0.0)
+                        }
+                    }
+                }
+                _ => false,
+            })
+            .collect()
+    }
+
+    /// P&L of a liquidated leg: the whole posted margin is lost
+    pub fn liquidation_payoff(futures: &FuturesPosition, price: f64) -> f64 {
+        let margin = Self::initial_margin(futures).unwrap_or(0.0);
+        match futures.contract_type {
+            ContractType::Inverse if !futures.pnl_in_base => -margin * price,
+            _ => -margin,
+        }
+    }
+
+    // === Private helper functions ===
+
+    /// Position notional in the margin currency
+    fn notional(futures: &FuturesPosition, price: f64) -> f64 {
+        let quantity = (futures.quantity * futures.contract_size).abs();
+        match futures.contract_type {
+            ContractType::Linear => quantity * price,
+            ContractType::Inverse => quantity / price,
+        }
+    }
+
+    /// Unrealized P&L from the price move, in the margin currency
+    fn margin_pnl(futures: &FuturesPosition, price: f64) -> f64 {
+        let quantity = futures.quantity * futures.contract_size;
+        match futures.contract_type {
+            ContractType::Linear => quantity * (price - futures.entry_price),
+            ContractType::Inverse => quantity * (1.0 / futures.entry_price - 1.0 / price),
+        }
+    }
+
+    fn tiers(futures: &FuturesPosition) -> &[MaintenanceTier] {
+        match (futures.maintenance_tiers.is_empty(), futures.contract_type) {
+            (false, _) => &futures.maintenance_tiers,
+            (true, ContractType::Linear) => &DEFAULT_LINEAR_TIERS,
+            (true, ContractType::Inverse) => &DEFAULT_INVERSE_TIERS,
+        }
+    }
+
+    fn tier_for(futures: &FuturesPosition, notional: f64) -> MaintenanceTier {
+        let tiers = Self::tiers(futures);
+        tiers
+            .iter()
+            .find(|tier| tier.max_notional.is_none_or(|cap| notional <= cap))
+            .or(tiers.last())
+            .copied()
+            .unwrap_or(DEFAULT_INVERSE_TIERS[0])
+    }
+
+    /// Active leveraged cross legs settled in one margin currency
+    fn cross_legs(
+        positions: &[Position],
+        contract_type: ContractType,
+    ) -> impl Iterator<Item = &FuturesPosition> {
+        positions.iter().filter_map(move |pos| match pos {
+            Position::Futures(futures)
+                if futures.active
+                    && futures.margin_mode == MarginMode::Cross
+                    && futures.contract_type == contract_type
+                    && Self::initial_margin(futures).is_some() =>
+            {
+                Some(futures)
+            }
+            _ => None,
+        })
+    }
+
+    /// Cross wallet equity above maintenance at a price (None without cross legs)
+    ///
+    /// The wallet holds the margin posted for each cross leg.
+    fn cross_equity(
+        positions: &[Position],
+        contract_type: ContractType,
+        price: f64,
+    ) -> Option<f64> {
+        let mut legs = Self::cross_legs(positions, contract_type).peekable();
+        legs.peek()?;
+
+        Some(
+            legs.map(|futures| {
+                Self::initial_margin(futures).unwrap_or(0.0) + Self::margin_pnl(futures, price)
+                    - Self::maintenance_margin(futures, price)
+            })
+            .sum(),
+        )
+    }
+
+    /// Prices where the cross equity crosses zero, bracketed on a log grid and
+    /// refined by bisection
+    fn cross_wallet_roots(positions: &[Position], contract_type: ContractType) -> Vec<f64> {
+        let reference = Self::cross_legs(positions, contract_type)
+            .map(|futures| futures.entry_price)
+            .fold(0.0, f64::max);
+        if reference <= 0.0 {
+            return Vec::new();
+        }
+
+        let equity =
+            |price: f64| Self::cross_equity(positions, contract_type, price).unwrap_or(0.0);
+        let ratio = (CROSS_SCAN_MAX / CROSS_SCAN_MIN).powf(1.0 / CROSS_SCAN_POINTS as f64);
+        let grid: Vec<f64> = (0..=CROSS_SCAN_POINTS)
+            .map(|i| reference * CROSS_SCAN_MIN * ratio.powi(i as i32))
+            .collect();
+
+        grid.windows(2)
+            .filter(|pair| (equity(pair[0]) < 0.0) != (equity(pair[1]) < 0.0))
+            .map(|pair| {
+                let (mut low, mut high) = (pair[0], pair[1]);
+                let low_negative = equity(low) < 0.0;
+                for _ in 0..BISECTION_ITERATIONS {
+                    let mid = 0.5 * (low + high);
+                    if (equity(mid) < 0.0) == low_negative {
+                        low = mid;
+                    } else {
+                        high = mid;
+                    }
+                }
+                0.5 * (low + high)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leveraged(quantity: f64, leverage: f64, margin_mode: MarginMode) -> FuturesPosition {
+        FuturesPosition {
+            leverage: Some(leverage),
+            margin_mode,
+            ..FuturesPosition::new(quantity, 100.0, 1.0, None)
+        }
+    }
+
+    #[test]
+    fn test_isolated_liquidation_prices() {
+        // 10x long at 100 posts 10 of margin: 10 + (p - 100) = 0.004 p
+        let long = leveraged(1.0, 10.0, MarginMode::Isolated);
+        let price = LiquidationEngine::liquidation_price(&long).unwrap();
+        assert!((price - 90.0 / 0.996).abs() < 1e-9);
+
+        // 10x short: 10 - (p - 100) = 0.004 p
+        let short = leveraged(-1.0, 10.0, MarginMode::Isolated);
+        let price = LiquidationEngine::liquidation_price(&short).unwrap();
+        assert!((price - 110.0 / 1.004).abs() < 1e-9);
+
+        // An unleveraged leg or a fully collateralized long never liquidates
+        assert_eq!(
+            LiquidationEngine::liquidation_price(&FuturesPosition::new(1.0, 100.0, 1.0, None)),
+            None
+        );
+        assert_eq!(
+            LiquidationEngine::liquidation_price(&leveraged(1.0, 1.0, MarginMode::Isolated)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_cross_wallet_shares_margin() {
+        // A 10x long and a 10x half-size short pool 15 of margin; the short's gains
+        // keep the long alive well below its isolated liquidation price
+        let long = leveraged(1.0, 10.0, MarginMode::Cross);
+        let positions = vec![
+            Position::Futures(long.clone()),
+            Position::Futures(leveraged(-0.5, 10.0, MarginMode::Cross)),
+        ];
+        let prices = LiquidationEngine::cross_liquidation_prices(&positions);
+        assert_eq!(prices.len(), 1);
+        // 15 + 0.5 (p - 100) = 0.006 p
+        assert!((prices[0] - 35.0 / 0.494).abs() < 1e-6);
+        assert!(prices[0] < LiquidationEngine::liquidation_price(&long).unwrap());
+
+        assert_eq!(
+            LiquidationEngine::liquidated_legs(&positions, 75.0),
+            vec![false, false]
+        );
+        assert_eq!(
+            LiquidationEngine::liquidated_legs(&positions, 65.0),
+            vec![true, true]
+        );
+    }
+}
@@ -0,0 +1,318 @@
+use super::liquidation_engine::LiquidationEngine;
+use super::payoff_engine::PayoffEngine;
+use super::pricing_engine::{PricingEngine, ValuationContext, VolatilityShift};
+use crate::models::{
+    BarrierPosition, ContractType, DigitalPosition, FuturesPosition, MarginMethod, OptionPosition,
+    OptionType, Position, SpotPosition,
+};
+
+/// Short option add-on: the larger of 20% of the underlying less the
+/// out-of-the-money amount, or 10% of the underlying (of the strike for puts)
+const SHORT_OPTION_BASE_RATE: f64 = 0.20;
+const SHORT_OPTION_MIN_RATE: f64 = 0.10;
+
+/// Short spot add-on on top of the sale proceeds
+const SHORT_SPOT_RATE: f64 = 0.50;
+
+/// Share of the initial add-on that must be kept as maintenance
+const MAINTENANCE_RATIO: f64 = 0.75;
+
+/// Portfolio margin shock grid: price moves up to ±15% and relative volatility
+/// moves of ±30%
+const PRICE_SHOCK_RANGE: f64 = 0.15;
+const PRICE_SHOCK_STEPS: usize = 10;
+const VOLATILITY_SHOCKS: [f64; 3] = [-0.30, 0.0, 0.30];
+
+/// Initial portfolio margin as a multiple of the worst scenario loss
+const PORTFOLIO_INITIAL_MULTIPLIER: f64 = 1.25;
+
+/// Worst scenario found by the portfolio margin grid
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MarginScenario {
+    pub price_shock: f64,      // Relative price move (-0.15 = 15% drop)
+    pub volatility_shock: f64, // Relative volatility move (0.3 = vol up 30%)
+    pub loss: f64,             // Portfolio loss in the scenario
+}
+
+/// Margin required to hold the portfolio, in quote currency
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MarginRequirement {
+    pub method: MarginMethod,
+    pub initial_margin: f64,     // Capital required to open the portfolio
+    pub maintenance_margin: f64, // Equity that must be kept to avoid a margin call
+    pub worst_scenario: Option<MarginScenario>, // Portfolio margin only
+}
+
+/// Margin requirement engine (WASM-compatible)
+pub struct MarginEngine;
+
+impl MarginEngine {
+    /// Margin for the active positions with the chosen methodology
+    pub fn calculate_margin(
+        positions: &[Position],
+        underlying_price: f64,
+        method: MarginMethod,
+        context: &ValuationContext,
+    ) -> MarginRequirement {
+        match method {
+            MarginMethod::Standard => Self::standard_margin(positions, underlying_price, context),
+            MarginMethod::PortfolioMargin => {
+                Self::portfolio_margin(positions, underlying_price, context)
+            }
+        }
+    }
+
+    /// Sum of per-leg requirements, with no offsets between legs
+    pub fn standard_margin(
+        positions: &[Position],
+        underlying_price: f64,
+        context: &ValuationContext,
+    ) -> MarginRequirement {
+        let (initial_margin, maintenance_margin) = positions
+            .iter()
+            .filter(|pos| pos.is_active())
+            .map(|pos| match pos {
+                Position::Spot(spot) => Self::spot_margin(spot, underlying_price),
+                Position::Option(option) => Self::option_margin(option, underlying_price, context),
+                Position::Futures(futures) => Self::futures_margin(futures, underlying_price),
+                Position::Digital(digital) => {
+                    Self::digital_margin(digital, underlying_price, context)
+                }
+                Position::Barrier(barrier) => {
+                    Self::barrier_margin(barrier, underlying_price, context)
+                }
+            })
+            .fold(
+                (0.0, 0.0),
+                |(initial, maintenance), (leg_initial, leg_maintenance)| {
+                    (initial + leg_initial, maintenance + leg_maintenance)
+                },
+            );
+
+        MarginRequirement {
+            method: MarginMethod::Standard,
+            initial_margin,
+            maintenance_margin,
+            worst_scenario: None,
+        }
+    }
+
+    /// Worst loss of the whole portfolio across a price / volatility shock grid
+    pub fn portfolio_margin(
+        positions: &[Position],
+        underlying_price: f64,
+        context: &ValuationContext,
+    ) -> MarginRequirement {
+        let today = ValuationContext { days_elapsed: 0.0, ..*context };
+        let current_value =
+            PayoffEngine::calculate_portfolio_value(positions, underlying_price, &today);
+
+        let mut worst_scenario: Option<MarginScenario> = None;
+        for volatility_shock in VOLATILITY_SHOCKS {
+            let (shocked_context, shocked_positions) =
+                today.shift_volatility(positions, VolatilityShift::Relative(volatility_shock));
+
+            for step in 0..=PRICE_SHOCK_STEPS {
+                let price_shock = -PRICE_SHOCK_RANGE
+                    + 2.0 * PRICE_SHOCK_RANGE * step as f64 / PRICE_SHOCK_STEPS as f64;
+                let value = PayoffEngine::calculate_portfolio_value(
+                    &shocked_positions,
+                    underlying_price * (1.0 + price_shock),
+                    &shocked_context,
+                );
+                let loss = current_value - value;
+
+                if worst_scenario.is_none_or(|worst| loss > worst.loss) {
+                    worst_scenario = Some(MarginScenario { price_shock, volatility_shock, loss });
+                }
+            }
+        }
+
+        let maintenance_margin = worst_scenario.map_or(0.0, |worst| worst.loss.max(0.0));
+        MarginRequirement {
+            method: MarginMethod::PortfolioMargin,
+            initial_margin: maintenance_margin * PORTFOLIO_INITIAL_MULTIPLIER,
+            maintenance_margin,
+            worst_scenario,
+        }
+    }
+
+    /// Profit as a fraction of the initial margin (None when no margin is required)
+    pub fn return_on_margin(profit: f64, margin: &MarginRequirement) -> Option<f64> {
+        if margin.initial_margin > 0.0 {
+            Some(profit / margin.initial_margin)
+        } else {
+            None
+        }
+    }
+
+    // === Private helper functions ===
+
+    /// Long spot is fully paid; short spot holds the proceeds plus an add-on
+    fn spot_margin(spot: &SpotPosition, underlying_price: f64) -> (f64, f64) {
+        let value = spot.quantity.abs() * underlying_price;
+        if spot.quantity >= 0.0 {
+            (value, value)
+        } else {
+            let add_on = value * SHORT_SPOT_RATE;
+            (value + add_on, value + add_on * MAINTENANCE_RATIO)
+        }
+    }
+
+    /// Long options are fully paid; short options hold their value plus an add-on
+    fn option_margin(
+        option: &OptionPosition,
+        underlying_price: f64,
+        context: &ValuationContext,
+    ) -> (f64, f64) {
+        let time_to_expiry = context.leg_time_from_today(option.expiry);
+        let option_value = if time_to_expiry > 0.0 {
+            PricingEngine::value_option(option, underlying_price, time_to_expiry, context)
+        } else {
+            match option.option_type {
+                OptionType::Call => (underlying_price - option.strike_price).max(0.0),
+                OptionType::Put => (option.strike_price - underlying_price).max(0.0),
+            }
+        };
+
+        let units = option.units().abs();
+        if option.quantity >= 0.0 {
+            let value = units * option_value;
+            return (value, value);
+        }
+
+        let add_on =
+            Self::short_option_add_on(option.option_type, option.strike_price, underlying_price);
+        (
+            units * (option_value + add_on),
+            units * (option_value + add_on * MAINTENANCE_RATIO),
+        )
+    }
+
+    /// Barrier options follow the vanilla rules on the barrier option's own value
+    fn barrier_margin(
+        barrier: &BarrierPosition,
+        underlying_price: f64,
+        context: &ValuationContext,
+    ) -> (f64, f64) {
+        let time_to_expiry = context.leg_time_from_today(barrier.expiry);
+        let barrier_value =
+            PricingEngine::value_barrier(barrier, underlying_price, time_to_expiry, context);
+
+        let contracts = barrier.quantity.abs();
+        if barrier.quantity >= 0.0 {
+            let value = contracts * barrier_value;
+            return (value, value);
+        }
+
+        let add_on =
+            Self::short_option_add_on(barrier.option_type, barrier.strike_price, underlying_price);
+        (
+            contracts * (barrier_value + add_on),
+            contracts * (barrier_value + add_on * MAINTENANCE_RATIO),
+        )
+    }
+
+    /// Short option add-on per contract on top of the option value
+    fn short_option_add_on(
+        option_type: OptionType,
+        strike_price: f64,
+        underlying_price: f64,
+    ) -> f64 {
+        let (out_of_the_money, minimum_base) = match option_type {
+            OptionType::Call => ((strike_price - underlying_price).max(0.0), underlying_price),
+            OptionType::Put => ((underlying_price - strike_price).max(0.0), strike_price),
+        };
+        (SHORT_OPTION_BASE_RATE * underlying_price - out_of_the_money)
+            .max(SHORT_OPTION_MIN_RATE * minimum_base)
+    }
+
+    /// Long digitals are fully paid; short digitals hold the full payout
+    fn digital_margin(
+        digital: &DigitalPosition,
+        underlying_price: f64,
+        context: &ValuationContext,
+    ) -> (f64, f64) {
+        let contracts = digital.quantity.abs();
+        if digital.quantity < 0.0 {
+            let payout = contracts * digital.payout;
+            return (payout, payout);
+        }
+
+        let time_to_expiry = context.leg_time_from_today(digital.expiry);
+        let value = contracts
+            * digital.payout
+            * PricingEngine::digital_price(
+                digital.option_type,
+                underlying_price,
+                digital.strike_price,
+                time_to_expiry,
+                context.risk_free_rate,
+                digital.implied_volatility.unwrap_or(context.volatility),
+            );
+        (value, value)
+    }
+
+    /// Leveraged futures post their initial margin; unleveraged ones the full notional
+    fn futures_margin(futures: &FuturesPosition, underlying_price: f64) -> (f64, f64) {
+        let quantity = (futures.quantity * futures.contract_size).abs();
+        // Coin-margined amounts are converted to quote at the current price
+        let (notional, to_quote) = match futures.contract_type {
+            ContractType::Linear => (quantity * underlying_price, 1.0),
+            ContractType::Inverse => (quantity, underlying_price),
+        };
+
+        let initial_margin =
+            LiquidationEngine::initial_margin(futures).map_or(notional, |margin| margin * to_quote);
+        let maintenance_margin =
+            LiquidationEngine::maintenance_margin(futures, underlying_price) * to_quote;
+
+        (initial_margin, maintenance_margin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standard_short_put_margin() {
+        // Expired context: the 90 put is worth nothing with the underlying at 100
+        let context = ValuationContext::new(0.0, 0.0, 0.5, 0.0);
+        let short_put = OptionPosition::new(OptionType::Put, -2.0, 90.0, 3.0, None);
+        let positions = vec![Position::Option(short_put)];
+
+        let margin = MarginEngine::standard_margin(&positions, 100.0, &context);
+        // Per contract: max(20% * 100 - 10 OTM, 10% * 90) = 10
+        assert!((margin.initial_margin - 20.0).abs() < 1e-9);
+        assert!((margin.maintenance_margin - 15.0).abs() < 1e-9);
+        assert_eq!(margin.worst_scenario, None);
+    }
+
+    #[test]
+    fn test_portfolio_margin_nets_hedged_legs() {
+        let context = ValuationContext::default();
+        let long_spot = vec![Position::Spot(SpotPosition::new(1.0, 100.0, None))];
+
+        // A lone long loses most on the largest price drop
+        let margin = MarginEngine::portfolio_margin(&long_spot, 100.0, &context);
+        let worst = margin.worst_scenario.unwrap();
+        assert!((worst.price_shock + PRICE_SHOCK_RANGE).abs() < 1e-12);
+        assert!((margin.maintenance_margin - 15.0).abs() < 1e-9);
+
+        // Shorting the same amount of futures leaves no scenario loss, while the
+        // per-leg rules still charge both sides
+        let mut hedged = long_spot.clone();
+        hedged.push(Position::Futures(FuturesPosition::new(
+            -1.0, 100.0, 1.0, None,
+        )));
+        let portfolio = MarginEngine::portfolio_margin(&hedged, 100.0, &context);
+        let standard = MarginEngine::standard_margin(&hedged, 100.0, &context);
+        assert!(portfolio.initial_margin < 1e-9);
+        assert!(standard.initial_margin > 100.0);
+
+        assert_eq!(MarginEngine::return_on_margin(10.0, &portfolio), None);
+        let return_on_margin = MarginEngine::return_on_margin(3.0, &margin).unwrap();
+        assert!((return_on_margin - 3.0 / 18.75).abs() < 1e-9);
+    }
+}
@@ -1,11 +1,38 @@
 // Payoff Engine Module
 // Core calculation engine for financial position analysis, independent of UI
 
+pub mod greeks_engine;
+pub mod hedge_engine;
+pub mod liquidation_engine;
+pub mod margin_engine;
+pub mod optimizer_engine;
 pub mod payoff_engine;
 pub mod portfolio_engine;
+pub mod pricing_engine;
+pub mod scenario_engine;
+pub mod simulation_engine;
+pub mod strategy_engine;
+pub mod template_engine;
 pub mod validation_engine;
 
 // Re-export main interfaces
-pub use payoff_engine::{PayoffEngine, PayoffPoint};
-pub use portfolio_engine::{PortfolioEngine, PortfolioMetrics};
+pub use greeks_engine::{Greeks, GreeksEngine, GreeksPoint};
+pub use hedge_engine::{HedgeEngine, HedgeInstrument, HedgeSuggestion, HedgeTargets};
+pub use liquidation_engine::LiquidationEngine;
+pub use margin_engine::{MarginEngine, MarginRequirement};
+pub use optimizer_engine::{
+    ListedOption, OptimizationCandidate, OptimizationConstraints, OptimizationObjective,
+    OptimizationResult, OptimizerEngine,
+};
+pub use payoff_engine::{
+    BetaWeighting, PayoffBound, PayoffEngine, PayoffPoint, UnderlyingGroup, UnderlyingMarket,
+};
+pub use portfolio_engine::{PortfolioEngine, PortfolioMetrics, VarEstimate, VarMethod};
+pub use pricing_engine::{PricingEngine, ValuationContext};
+pub use scenario_engine::{ScenarioCell, ScenarioEngine, ScenarioReport};
+pub use simulation_engine::{SimulationConfig, SimulationEngine, SimulationResult};
+pub use strategy_engine::{
+    RecognizedStrategy, StrategyEngine, StrategyKind, StrategyLeg, StrategyReport,
+};
+pub use template_engine::{TemplateEngine, TemplateInputs};
 pub use validation_engine::{ValidationEngine, ValidationResult};
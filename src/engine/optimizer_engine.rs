@@ -0,0 +1,618 @@
+use super::payoff_engine::{PayoffBound, PayoffEngine};
+use super::portfolio_engine::PortfolioEngine;
+use super::pricing_engine::ValuationContext;
+use super::template_engine::{TemplateEngine, TemplateInputs};
+use crate::models::{OptionPosition, OptionType, Position, PositionTemplate};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// Relative tolerance for treating two template strikes as the same strike
+const STRIKE_TOLERANCE: f64 = 1e-9;
+
+/// Template width used to read the strike order of a strategy shape
+const SHAPE_WIDTH: f64 = 0.05;
+
+/// Hard cap on scored strike combinations, so long chains cannot stall the UI thread
+const MAX_EVALUATIONS: usize = 20_000;
+
+/// One listed option the optimizer can pick a strike from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ListedOption {
+    pub option_type: OptionType,
+    pub strike: f64,
+    pub premium: f64, // Premium per underlying unit (mid price)
+}
+
+/// What the optimizer ranks candidates by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptimizationObjective {
+    ProfitProbability, // Lognormal probability of finishing in profit
+    ExpectedValue,     // Lognormal expected payoff at expiry
+    RewardRisk,        // Max profit over max loss (bounded candidates only)
+}
+
+impl OptimizationObjective {
+    pub fn label(&self) -> &'static str {
+        match self {
+            OptimizationObjective::ProfitProbability => "Probability of Profit",
+            OptimizationObjective::ExpectedValue => "Expected Value",
+            OptimizationObjective::RewardRisk => "Reward / Risk",
+        }
+    }
+}
+
+/// Limits every candidate has to satisfy
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OptimizationConstraints {
+    pub max_loss: Option<f64>, // Largest acceptable loss as a positive amount
+    pub min_net_credit: Option<f64>, // Smallest premium received (negative allows a debit)
+    pub max_width: Option<f64>, // Largest distance between the lowest and highest strike
+    pub max_candidates: usize, // How many of the best candidates to return
+}
+
+impl Default for OptimizationConstraints {
+    fn default() -> Self {
+        Self {
+            max_loss: None,
+            min_net_credit: None,
+            max_width: None,
+            max_candidates: 5,
+        }
+    }
+}
+
+/// One strike combination and its scores
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptimizationCandidate {
+    pub positions: Vec<Position>, // Template legs on the chosen strikes
+    pub strikes: Vec<f64>,        // Chosen strike of each option leg, in leg order
+    pub net_credit: f64,          // Premium received (negative = debit paid)
+    pub max_profit: Option<PayoffBound>, // From PayoffEngine
+    pub max_loss: Option<PayoffBound>, // From PayoffEngine
+    pub profit_probability: Option<f64>, // Lognormal probability of profit
+    pub expected_value: Option<f64>, // Lognormal expected payoff
+    pub reward_risk: Option<f64>, // Max profit / |max loss| when both are bounded
+    pub score: f64,               // Value of the chosen objective
+}
+
+/// Result of a strike search
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptimizationResult {
+    pub candidates: Vec<OptimizationCandidate>, // Best first, at most max_candidates
+    pub evaluated: usize,                       // Strike combinations that were scored
+    pub feasible: usize,                        // Combinations that met the constraints
+    pub truncated: bool, // Search stopped at MAX_EVALUATIONS before covering the chain
+}
+
+/// Strike optimizer engine (WASM-compatible)
+pub struct OptimizerEngine;
+
+impl OptimizerEngine {
+    /// Search the listed strikes for the combinations of a template's option legs that
+    /// score best on the objective, subject to the constraints
+    ///
+    /// Legs keep the strike order of the template: legs on the same template strike
+    /// share a strike, higher ones need a strictly higher strike. Spot and futures legs
+    /// are entered at the current price. Combinations are scored as they are generated
+    /// into a bounded top list; branches that cannot meet the width or net credit limits
+    /// are skipped, and at most MAX_EVALUATIONS combinations are scored.
+    pub fn optimize(
+        template: &PositionTemplate,
+        chain: &[ListedOption],
+        inputs: &TemplateInputs,
+        objective: OptimizationObjective,
+        constraints: &OptimizationConstraints,
+        context: &ValuationContext,
+    ) -> Result<OptimizationResult, String> {
+        // Listed strikes replace the template's, so its width only orders the legs
+        let shape = TemplateInputs { width: SHAPE_WIDTH, ..inputs.clone() };
+        let base = TemplateEngine::instantiate(template, &shape, context)?;
+
+        let legs: Vec<OptionLeg> = base
+            .iter()
+            .enumerate()
+            .filter_map(|(index, pos)| match pos {
+                Position::Option(option) => Some(OptionLeg {
+                    index,
+                    option_type: option.option_type,
+                    template_strike: option.strike_price,
+                    units: option.units(),
+                }),
+                _ => None,
+            })
+            .collect();
+        if legs.is_empty() {
+            return Err("Template has no option legs to place".to_string());
+        }
+
+        // Visit legs from the lowest template strike up, so each choice bounds the next
+        let mut order: Vec<usize> = (0..legs.len()).collect();
+        order.sort_by(|&a, &b| {
+            legs[a]
+                .template_strike
+                .partial_cmp(&legs[b].template_strike)
+                .unwrap()
+        });
+
+        let mut listed: Vec<ListedOption> = chain
+            .iter()
+            .filter(|quote| quote.strike > 0.0 && quote.premium >= 0.0)
+            .copied()
+            .collect();
+        listed.sort_by(|a, b| a.strike.partial_cmp(&b.strike).unwrap());
+
+        // Most premium the legs from each depth on could still bring in
+        let mut best_remaining_credit = vec![0.0; order.len() + 1];
+        for depth in (0..order.len()).rev() {
+            let leg = &legs[order[depth]];
+            let best = listed
+                .iter()
+                .filter(|quote| quote.option_type == leg.option_type)
+                .map(|quote| -leg.units * quote.premium)
+                .fold(f64::NEG_INFINITY, f64::max);
+            best_remaining_credit[depth] = best_remaining_credit[depth + 1] + best;
+        }
+
+        let mut search = StrikeSearch {
+            base: &base,
+            legs: &legs,
+            order: &order,
+            listed: &listed,
+            best_remaining_credit: &best_remaining_credit,
+            underlying_price: inputs.underlying_price,
+            objective,
+            constraints,
+            context,
+            chosen: vec![None; legs.len()],
+            top: BinaryHeap::new(),
+            evaluated: 0,
+            feasible: 0,
+            truncated: false,
+        };
+        search.visit(0, 0.0);
+
+        Ok(OptimizationResult {
+            candidates: search
+                .top
+                .into_sorted_vec()
+                .into_iter()
+                .map(|ranked| ranked.0)
+                .collect(),
+            evaluated: search.evaluated,
+            feasible: search.feasible,
+            truncated: search.truncated,
+        })
+    }
+
+    // === Private helper functions ===
+
+    /// Template positions with each option leg moved onto its listed strike and premium
+    fn place_legs(base: &[Position], legs: &[OptionLeg], quotes: &[ListedOption]) -> Vec<Position> {
+        let mut positions = base.to_vec();
+        for (leg, quote) in legs.iter().zip(quotes) {
+            if let Position::Option(option) = &positions[leg.index] {
+                positions[leg.index] = Position::Option(OptionPosition {
+                    strike_price: quote.strike,
+                    premium: quote.premium,
+                    ..option.clone()
+                });
+            }
+        }
+        positions
+    }
+
+    /// Score a candidate with the payoff bounds and lognormal portfolio metrics
+    fn score(
+        positions: Vec<Position>,
+        quotes: &[ListedOption],
+        underlying_price: f64,
+        objective: OptimizationObjective,
+        context: &ValuationContext,
+    ) -> Option<OptimizationCandidate> {
+        let net_credit = -positions
+            .iter()
+            .filter_map(|pos| match pos {
+                Position::Option(option) => Some(option.units() * option.premium),
+                _ => None,
+            })
+            .sum::<f64>();
+        let max_profit = PayoffEngine::calculate_max_profit(&positions);
+        let max_loss = PayoffEngine::calculate_max_loss(&positions);
+        let profit_probability = PortfolioEngine::calculate_lognormal_profit_probability(
+            &positions,
+            underlying_price,
+            context,
+        );
+        let expected_value = PortfolioEngine::calculate_lognormal_expected_value(
+            &positions,
+            underlying_price,
+            context,
+        );
+        let reward_risk = match (max_profit, max_loss) {
+            (Some(PayoffBound::Bounded(profit)), Some(PayoffBound::Bounded(loss)))
+                if loss < 0.0 =>
+            {
+                Some(profit / -loss)
+            }
+            _ => None,
+        };
+
+        let score = match objective {
+            OptimizationObjective::ProfitProbability => profit_probability?,
+            OptimizationObjective::ExpectedValue => expected_value?,
+            OptimizationObjective::RewardRisk => reward_risk?,
+        };
+        if !score.is_finite() {
+            return None;
+        }
+
+        Some(OptimizationCandidate {
+            positions,
+            strikes: quotes.iter().map(|quote| quote.strike).collect(),
+            net_credit,
+            max_profit,
+            max_loss,
+            profit_probability,
+            expected_value,
+            reward_risk,
+            score,
+        })
+    }
+
+    fn meets_constraints(
+        candidate: &OptimizationCandidate,
+        constraints: &OptimizationConstraints,
+    ) -> bool {
+        let loss_ok = match constraints.max_loss {
+            None => true,
+            Some(limit) => match candidate.max_loss {
+                Some(PayoffBound::Bounded(loss)) => -loss <= limit,
+                _ => false,
+            },
+        };
+        let credit_ok = constraints
+            .min_net_credit
+            .is_none_or(|min_credit| candidate.net_credit >= min_credit);
+        loss_ok && credit_ok
+    }
+}
+
+/// Option leg of the template the search places on a listed strike
+struct OptionLeg {
+    index: usize, // Position index in the instantiated template
+    option_type: OptionType,
+    template_strike: f64, // Orders the legs
+    units: f64,           // Signed underlying units (sign gives long / short)
+}
+
+/// Candidate ordered by score, lowest first out of the heap
+struct Ranked(OptimizationCandidate);
+
+impl PartialEq for Ranked {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Ranked {}
+
+impl PartialOrd for Ranked {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Ranked {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed so the max-heap keeps the weakest candidate on top; scores are finite
+        other
+            .0
+            .score
+            .partial_cmp(&self.0.score)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+/// Depth-first walk over the listed strikes, scoring complete combinations into a
+/// bounded top list
+struct StrikeSearch<'a> {
+    base: &'a [Position],
+    legs: &'a [OptionLeg],
+    order: &'a [usize],
+    listed: &'a [ListedOption],
+    best_remaining_credit: &'a [f64],
+    underlying_price: f64,
+    objective: OptimizationObjective,
+    constraints: &'a OptimizationConstraints,
+    context: &'a ValuationContext,
+    chosen: Vec<Option<ListedOption>>,
+    top: BinaryHeap<Ranked>,
+    evaluated: usize,
+    feasible: usize,
+    truncated: bool,
+}
+
+impl StrikeSearch<'_> {
+    fn visit(&mut self, depth: usize, credit: f64) {
+        if self.truncated {
+            return;
+        }
+        let Some(&leg_num) = self.order.get(depth) else {
+            self.evaluate();
+            return;
+        };
+        let leg = &self.legs[leg_num];
+
+        // Bound by the strike picked for the previous leg in template order
+        let previous = depth.checked_sub(1).map(|prev| {
+            let previous_leg = self.order[prev];
+            (
+                self.legs[previous_leg].template_strike,
+                self.chosen[previous_leg].unwrap().strike,
+            )
+        });
+        let lowest_strike = depth
+            .checked_sub(1)
+            .map(|_| self.chosen[self.order[0]].unwrap().strike);
+
+        for quote in self
+            .listed
+            .iter()
+            .filter(|quote| quote.option_type == leg.option_type)
+        {
+            if let Some((previous_template, previous_strike)) = previous {
+                let same = (leg.template_strike - previous_template).abs()
+                    <= STRIKE_TOLERANCE * leg.template_strike.abs().max(1.0);
+                if quote.strike < previous_strike || (!same && quote.strike == previous_strike) {
+                    continue;
+                }
+                if same && quote.strike > previous_strike {
+                    break;
+                }
+            }
+            // Strikes ascend, so every later quote is wider still
+            if let (Some(lowest), Some(max_width)) = (lowest_strike, self.constraints.max_width) {
+                if quote.strike - lowest > max_width {
+                    break;
+                }
+            }
+            let credit = credit - leg.units * quote.premium;
+            if let Some(min_credit) = self.constraints.min_net_credit {
+                if credit + self.best_remaining_credit[depth + 1] < min_credit {
+                    continue;
+                }
+            }
+
+            self.chosen[leg_num] = Some(*quote);
+            self.visit(depth + 1, credit);
+            if self.truncated {
+                break;
+            }
+        }
+        self.chosen[leg_num] = None;
+    }
+
+    /// Score the complete combination and keep it if it ranks among the best
+    fn evaluate(&mut self) {
+        if self.evaluated >= MAX_EVALUATIONS {
+            self.truncated = true;
+            return;
+        }
+        self.evaluated += 1;
+
+        let quotes: Vec<ListedOption> = self.chosen.iter().map(|quote| quote.unwrap()).collect();
+        let positions = OptimizerEngine::place_legs(self.base, self.legs, &quotes);
+        let Some(candidate) = OptimizerEngine::score(
+            positions,
+            &quotes,
+            self.underlying_price,
+            self.objective,
+            self.context,
+        ) else {
+            return;
+        };
+        if !OptimizerEngine::meets_constraints(&candidate, self.constraints) {
+            return;
+        }
+        self.feasible += 1;
+
+        let limit = self.constraints.max_candidates;
+        if self.top.len() < limit {
+            self.top.push(Ranked(candidate));
+        } else if self
+            .top
+            .peek()
+            .is_some_and(|weakest| candidate.score > weakest.0.score)
+        {
+            self.top.pop();
+            self.top.push(Ranked(candidate));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::*;
+
+    fn template(id: &str) -> PositionTemplate {
+        TemplateEngine::builtin_templates()
+            .into_iter()
+            .find(|template| template.id == format!("builtin-{}", id))
+            .unwrap()
+    }
+
+    fn inputs() -> TemplateInputs {
+        TemplateInputs {
+            underlying_price: 100.0,
+            size: 1.0,
+            width: 0.05,
+            underlying: None,
+        }
+    }
+
+    fn chain() -> Vec<ListedOption> {
+        let calls = [
+            (90.0, 11.5),
+            (95.0, 7.5),
+            (100.0, 4.5),
+            (105.0, 2.5),
+            (110.0, 1.2),
+        ];
+        let puts = [
+            (90.0, 1.2),
+            (95.0, 2.5),
+            (100.0, 4.5),
+            (105.0, 7.5),
+            (110.0, 11.5),
+        ];
+        calls
+            .iter()
+            .map(|&(strike, premium)| ListedOption {
+                option_type: OptionType::Call,
+                strike,
+                premium,
+            })
+            .chain(puts.iter().map(|&(strike, premium)| ListedOption {
+                option_type: OptionType::Put,
+                strike,
+                premium,
+            }))
+            .collect()
+    }
+
+    #[test]
+    fn test_bull_call_spread_reward_risk_with_max_loss() {
+        let context = ValuationContext::new(30.0, 0.0, 0.5, 0.05);
+        let constraints = OptimizationConstraints {
+            max_loss: Some(4.0),
+            min_net_credit: None,
+            max_width: None,
+            max_candidates: 3,
+        };
+
+        let result = OptimizerEngine::optimize(
+            &template("bull-call-spread"),
+            &chain(),
+            &inputs(),
+            OptimizationObjective::RewardRisk,
+            &constraints,
+            &context,
+        )
+        .unwrap();
+
+        // Long strike strictly below the short strike: C(5, 2) pairs
+        assert_eq!(result.evaluated, 10);
+        assert!(result.feasible > 0 && result.candidates.len() <= 3);
+        for candidate in &result.candidates {
+            assert!(candidate.strikes[0] < candidate.strikes[1]);
+            assert!(matches!(candidate.max_loss, Some(PayoffBound::Bounded(loss)) if -loss <= 4.0));
+        }
+        assert!(result
+            .candidates
+            .windows(2)
+            .all(|pair| pair[0].score >= pair[1].score));
+
+        // 105/110: pay 1.3 for a 5 wide spread
+        let best = &result.candidates[0];
+        assert_eq!(best.strikes, vec![105.0, 110.0]);
+        assert!((best.net_credit + 1.3).abs() < 1e-9);
+        assert!((best.score - 3.7 / 1.3).abs() < 1e-9);
+
+        // Width limit: only neighbouring strikes are tried
+        let narrow = OptimizationConstraints { max_width: Some(5.0), ..constraints };
+        let result = OptimizerEngine::optimize(
+            &template("bull-call-spread"),
+            &chain(),
+            &inputs(),
+            OptimizationObjective::RewardRisk,
+            &narrow,
+            &context,
+        )
+        .unwrap();
+        assert_eq!(result.evaluated, 4);
+        assert!(!result.truncated);
+
+        // A long chain stops at the evaluation cap instead of scoring every butterfly
+        let long_chain: Vec<ListedOption> = (0..60)
+            .map(|i| ListedOption {
+                option_type: OptionType::Call,
+                strike: 70.0 + i as f64,
+                premium: (30.0 - i as f64 * 0.5).max(0.1),
+            })
+            .collect();
+        let result = OptimizerEngine::optimize(
+            &template("long-call-butterfly"),
+            &long_chain,
+            &inputs(),
+            OptimizationObjective::ExpectedValue,
+            &OptimizationConstraints::default(),
+            &context,
+        )
+        .unwrap();
+        assert!(result.truncated);
+        assert_eq!(result.evaluated, MAX_EVALUATIONS);
+        assert_eq!(result.candidates.len(), 5);
+    }
+
+    #[test]
+    fn test_iron_condor_net_credit_and_strike_order() {
+        let context = ValuationContext::new(30.0, 0.0, 0.5, 0.05);
+        let constraints = OptimizationConstraints {
+            max_loss: None,
+            min_net_credit: Some(2.0),
+            max_width: None,
+            max_candidates: 10,
+        };
+
+        let result = OptimizerEngine::optimize(
+            &template("short-iron-condor"),
+            &chain(),
+            &inputs(),
+            OptimizationObjective::ProfitProbability,
+            &constraints,
+            &context,
+        )
+        .unwrap();
+
+        // Four strictly increasing strikes out of five
+        assert_eq!(result.evaluated, 5);
+        for candidate in &result.candidates {
+            assert!(candidate.net_credit >= 2.0);
+            assert!(candidate.strikes.windows(2).all(|pair| pair[0] < pair[1]));
+            assert!(candidate
+                .profit_probability
+                .is_some_and(|p| (0.0..=1.0).contains(&p)));
+        }
+        assert!(!result.candidates.is_empty());
+
+        // Branches that can no longer reach the credit are not scored
+        let rich = OptimizationConstraints { min_net_credit: Some(4.0), ..constraints };
+        let result = OptimizerEngine::optimize(
+            &template("short-iron-condor"),
+            &chain(),
+            &inputs(),
+            OptimizationObjective::ProfitProbability,
+            &rich,
+            &context,
+        )
+        .unwrap();
+        assert_eq!((result.evaluated, result.feasible), (2, 2));
+
+        // A straddle needs both types listed at one strike
+        let straddle = OptimizerEngine::optimize(
+            &template("long-straddle"),
+            &chain(),
+            &inputs(),
+            OptimizationObjective::ExpectedValue,
+            &OptimizationConstraints::default(),
+            &context,
+        )
+        .unwrap();
+        assert_eq!(straddle.evaluated, 5);
+        assert!(straddle
+            .candidates
+            .iter()
+            .all(|candidate| candidate.strikes[0] == candidate.strikes[1]));
+    }
+}
@@ -1,4 +1,9 @@
-use crate::models::{FuturesPosition, OptionPosition, OptionType, Position, SpotPosition};
+use super::liquidation_engine::LiquidationEngine;
+use super::pricing_engine::{PricingEngine, ValuationContext};
+use crate::models::{
+    BarrierPath, BarrierPosition, CalculationSettings, ContractType, DigitalPosition,
+    FuturesPosition, MarginMode, OptionPosition, OptionType, Position, SpotPosition, TradingFees,
+};
 
 /// A single point on the payoff diagram
 #[derive(Debug, Clone, PartialEq)]
@@ -7,6 +12,258 @@ pub struct PayoffPoint {
     pub payoff: f64,
 }
 
+/// Tolerance used when deciding whether a payoff or slope is exactly zero
+const ZERO_TOLERANCE: f64 = 1e-9;
+
+/// Perpetual funding settles every 8 hours
+const FUNDING_INTERVALS_PER_DAY: f64 = 3.0;
+
+/// One piece of an expiry payoff: `payoff = slope * price + intercept + inverse / price`
+///
+/// `inverse` is only non-zero for inverse futures whose P&L is reported in coin.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PayoffSegment {
+    pub start: f64, // Inclusive lower price bound
+    pub end: f64,   // Upper price bound (infinity for the last segment)
+    pub slope: f64,
+    pub intercept: f64,
+    pub inverse: f64,
+}
+
+impl PayoffSegment {
+    pub fn value_at(&self, price: f64) -> f64 {
+        let linear = self.slope * price + self.intercept;
+        if self.inverse == 0.0 {
+            linear
+        } else {
+            linear + self.inverse / price
+        }
+    }
+
+    /// Prices in `[lower, upper]` where the payoff crosses zero
+    pub fn roots(&self, lower: f64, upper: f64) -> Vec<f64> {
+        // Multiplying by price > 0: slope * p^2 + intercept * p + inverse = 0
+        let (a, b, c) = (self.slope, self.intercept, self.inverse);
+        let candidates = if c.abs() <= ZERO_TOLERANCE {
+            if a.abs() > ZERO_TOLERANCE {
+                vec![-b / a]
+            } else {
+                Vec::new()
+            }
+        } else if a.abs() <= ZERO_TOLERANCE {
+            if b.abs() > ZERO_TOLERANCE {
+                vec![-c / b]
+            } else {
+                Vec::new()
+            }
+        } else {
+            let discriminant = b * b - 4.0 * a * c;
+            if discriminant < 0.0 {
+                Vec::new()
+            } else {
+                let sqrt = discriminant.sqrt();
+                vec![(-b - sqrt) / (2.0 * a), (-b + sqrt) / (2.0 * a)]
+            }
+        };
+
+        candidates
+            .into_iter()
+            .filter(|root| *root >= lower && *root <= upper && (c == 0.0 || *root > 0.0))
+            .collect()
+    }
+
+    /// Sub-intervals of `[lower, upper]` on which the payoff is positive
+    pub fn positive_intervals(&self, lower: f64, upper: f64) -> Vec<(f64, f64)> {
+        if upper <= lower {
+            return Vec::new();
+        }
+
+        let mut edges = vec![lower];
+        edges.extend(self.roots(lower, upper));
+        edges.push(upper);
+        edges.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        edges
+            .windows(2)
+            .filter(|pair| pair[1] > pair[0])
+            .filter(|pair| {
+                // Test the sign strictly inside each piece
+                let probe = if pair[1].is_finite() {
+                    0.5 * (pair[0] + pair[1])
+                } else {
+                    pair[0].max(1.0) * 2.0
+                };
+                self.value_at(probe) > 0.0
+            })
+            .map(|pair| (pair[0], pair[1]))
+            .collect()
+    }
+}
+
+/// Exact expiry payoff as linear segments between kinks (strikes)
+#[derive(Debug, Clone, PartialEq)]
+pub struct PiecewisePayoff {
+    pub segments: Vec<PayoffSegment>,
+}
+
+impl PiecewisePayoff {
+    /// Slope of the payoff as price goes to infinity
+    pub fn asymptotic_slope(&self) -> f64 {
+        self.segments
+            .last()
+            .map(|segment| segment.slope)
+            .unwrap_or(0.0)
+    }
+
+    /// Coefficient of 1/price, which dominates as price goes to zero
+    pub fn inverse_coefficient(&self) -> f64 {
+        self.segments
+            .first()
+            .map(|segment| segment.inverse)
+            .unwrap_or(0.0)
+    }
+
+    /// Payoffs at zero, on both sides of every kink and at interior turning
+    /// points, where a bounded payoff reaches its extremes
+    pub fn extreme_values(&self) -> Vec<f64> {
+        self.segments
+            .iter()
+            .flat_map(|segment| {
+                let mut values = Vec::new();
+                if segment.start > 0.0 || segment.inverse == 0.0 {
+                    values.push(segment.value_at(segment.start));
+                }
+                if segment.end.is_finite() {
+                    values.push(segment.value_at(segment.end));
+                } else if segment.slope.abs() <= ZERO_TOLERANCE {
+                    // Flat tail: the payoff approaches its intercept
+                    values.push(segment.intercept);
+                }
+
+                // slope - inverse / p^2 = 0
+                let turning_point = (segment.inverse / segment.slope).sqrt();
+                if turning_point.is_finite()
+                    && turning_point > segment.start
+                    && turning_point < segment.end
+                {
+                    values.push(segment.value_at(turning_point));
+                }
+                values
+            })
+            .collect()
+    }
+}
+
+/// Extreme payoff of a portfolio: a finite amount or unbounded
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PayoffBound {
+    Bounded(f64),
+    Unbounded,
+}
+
+/// Positions that share one underlying
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnderlyingGroup {
+    pub underlying: Option<String>, // None = the portfolio's primary underlying
+    pub positions: Vec<Position>,
+}
+
+/// Current price and beta of one underlying in a beta-weighted view
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnderlyingMarket {
+    pub underlying: Option<String>, // None = the portfolio's primary underlying
+    pub price: f64,
+    pub beta: f64, // Return sensitivity to the primary underlying
+}
+
+/// Moves every underlying with a reference underlying through their betas, so
+/// positions on different underlyings can share one price axis
+#[derive(Debug, Clone, PartialEq)]
+pub struct BetaWeighting {
+    pub reference: Option<String>, // Underlying on the price axis
+    pub markets: Vec<UnderlyingMarket>,
+}
+
+impl BetaWeighting {
+    /// Markets from the calculation settings, with the reference underlying at `reference_price`
+    ///
+    /// The primary underlying has a beta of 1 and is only priced when its
+    /// current price is set or it is the reference.
+    pub fn from_settings(
+        settings: &CalculationSettings,
+        reference: Option<&str>,
+        reference_price: f64,
+    ) -> Self {
+        let primary = settings
+            .underlying_price
+            .filter(|price| *price > 0.0)
+            .map(|price| UnderlyingMarket { underlying: None, price, beta: 1.0 });
+        let quoted = settings.underlying_quotes.iter().filter_map(|quote| {
+            quote
+                .price
+                .filter(|price| *price > 0.0)
+                .map(|price| UnderlyingMarket {
+                    underlying: Some(quote.symbol.clone()),
+                    price,
+                    beta: quote.beta,
+                })
+        });
+        let reference_beta = reference
+            .and_then(|symbol| settings.underlying_quote(symbol))
+            .map_or(1.0, |quote| quote.beta);
+
+        let mut markets: Vec<UnderlyingMarket> = primary
+            .into_iter()
+            .chain(quoted)
+            .filter(|market| market.underlying.as_deref() != reference)
+            .collect();
+        markets.push(UnderlyingMarket {
+            underlying: reference.map(str::to_string),
+            price: reference_price,
+            beta: reference_beta,
+        });
+
+        Self { reference: reference.map(str::to_string), markets }
+    }
+
+    /// Market of an underlying, if it is priced
+    pub fn market(&self, underlying: Option<&str>) -> Option<&UnderlyingMarket> {
+        self.markets
+            .iter()
+            .find(|market| market.underlying.as_deref() == underlying)
+    }
+
+    /// Change in an underlying's price per unit change of the reference price
+    pub fn price_sensitivity(&self, underlying: Option<&str>) -> Option<f64> {
+        let reference = self.market(self.reference.as_deref())?;
+        let market = self.market(underlying)?;
+        if reference.price <= 0.0 || reference.beta.abs() <= ZERO_TOLERANCE {
+            return None;
+        }
+
+        Some(market.price * market.beta / (reference.beta * reference.price))
+    }
+
+    /// Price of an underlying implied by the reference trading at `reference_price`,
+    /// floored at zero
+    pub fn implied_price(&self, underlying: Option<&str>, reference_price: f64) -> Option<f64> {
+        let reference = self.market(self.reference.as_deref())?;
+        let market = self.market(underlying)?;
+        let sensitivity = self.price_sensitivity(underlying)?;
+
+        Some((market.price + sensitivity * (reference_price - reference.price)).max(0.0))
+    }
+
+    /// Underlyings of the positions that have no price and are left out of the view
+    pub fn unpriced_underlyings(&self, positions: &[Position]) -> Vec<Option<String>> {
+        PayoffEngine::group_by_underlying(positions)
+            .into_iter()
+            .map(|group| group.underlying)
+            .filter(|underlying| self.implied_price(underlying.as_deref(), 0.0).is_none())
+            .collect()
+    }
+}
+
 /// Core payoff calculation engine (WASM-compatible)
 pub struct PayoffEngine;
 
@@ -17,15 +274,25 @@ impl PayoffEngine {
             Position::Spot(spot) => Self::calculate_spot_payoff(spot, underlying_price),
             Position::Option(option) => Self::calculate_option_payoff(option, underlying_price),
             Position::Futures(futures) => Self::calculate_futures_payoff(futures, underlying_price),
+            Position::Digital(digital) => Self::calculate_digital_payoff(digital, underlying_price),
+            Position::Barrier(barrier) => Self::calculate_barrier_payoff(barrier, underlying_price),
         }
     }
 
     /// Calculate payoff for multiple positions (portfolio)
     pub fn calculate_portfolio_payoff(positions: &[Position], underlying_price: f64) -> f64 {
+        // Leveraged futures stop at liquidation, losing the margin posted for them
+        let liquidated = LiquidationEngine::liquidated_legs(positions, underlying_price);
         positions
             .iter()
-            .filter(|pos| pos.is_active()) // Only include active positions
-            .map(|pos| Self::calculate_single_payoff(pos, underlying_price))
+            .zip(liquidated)
+            .filter(|(pos, _)| pos.is_active()) // Only include active positions
+            .map(|(pos, liquidated)| match pos {
+                Position::Futures(futures) if liquidated => {
+                    LiquidationEngine::liquidation_payoff(futures, underlying_price)
+                }
+                _ => Self::calculate_single_payoff(pos, underlying_price),
+            })
             .sum()
     }
 
@@ -41,112 +308,605 @@ impl PayoffEngine {
 
         while current_price <= price_end {
             let payoff = Self::calculate_portfolio_payoff(positions, current_price);
-            points.push(PayoffPoint { price: current_price, payoff });
+            // Coin-settled inverse futures diverge at zero
+            if payoff.is_finite() {
+                points.push(PayoffPoint { price: current_price, payoff });
+            }
             current_price += step_size;
         }
 
         points
     }
 
-    /// Find breakeven points for the portfolio
-    pub fn find_breakeven_points(
+    /// Calculate P&L for a single position valued before expiry (T+n)
+    pub fn calculate_single_value(
+        position: &Position,
+        underlying_price: f64,
+        context: &ValuationContext,
+    ) -> f64 {
+        match position {
+            Position::Option(option) => {
+                Self::calculate_option_value(option, underlying_price, context)
+            }
+            Position::Digital(digital) => {
+                Self::calculate_digital_value(digital, underlying_price, context)
+            }
+            Position::Barrier(barrier) => {
+                Self::calculate_barrier_value(barrier, underlying_price, context)
+            }
+            // Dated futures trade at the forward implied by the basis until they expire
+            Position::Futures(futures) if futures.expiry.is_some() => {
+                let time_to_expiry = context.leg_time_to_expiry(futures.expiry);
+                let forward = context.forward_price(underlying_price, time_to_expiry);
+                Self::calculate_futures_payoff(futures, forward)
+            }
+            // Spot and perpetuals are worth the same before and at expiry
+            _ => Self::calculate_single_payoff(position, underlying_price),
+        }
+    }
+
+    /// Expected funding of the active perpetuals at a price (positive = received),
+    /// already included in the payoff
+    pub fn calculate_expected_funding(positions: &[Position], underlying_price: f64) -> f64 {
+        positions
+            .iter()
+            .filter(|pos| pos.is_active())
+            .map(|pos| match pos {
+                Position::Futures(futures) => {
+                    Self::calculate_futures_funding(futures, underlying_price)
+                }
+                _ => 0.0,
+            })
+            .sum()
+    }
+
+    /// Fees and slippage of the active positions held to expiry (entry plus
+    /// projected exit or exercise), already deducted from the payoff
+    pub fn calculate_trading_costs(positions: &[Position], underlying_price: f64) -> f64 {
+        positions
+            .iter()
+            .filter(|pos| pos.is_active())
+            .map(|pos| Self::calculate_position_costs(pos, underlying_price))
+            .sum()
+    }
+
+    /// Entry and projected exit costs of a position held to expiry
+    pub fn calculate_position_costs(position: &Position, underlying_price: f64) -> f64 {
+        match position {
+            Position::Spot(spot) => Self::spot_costs(spot, underlying_price),
+            Position::Futures(futures) => Self::futures_costs(futures, underlying_price),
+            Position::Option(option) => Self::option_expiry_costs(option, underlying_price),
+            // Cash-settled without an exercise fee
+            Position::Digital(digital) => {
+                let fees = digital.fees.unwrap_or_default();
+                digital.quantity.abs()
+                    * Self::option_entry_cost(&fees, digital.strike_price, digital.premium)
+            }
+            Position::Barrier(barrier) => {
+                let fees = barrier.fees.unwrap_or_default();
+                barrier.quantity.abs()
+                    * Self::option_entry_cost(&fees, barrier.strike_price, barrier.premium)
+            }
+        }
+    }
+
+    /// Cumulative funding rate over the holding horizon, zero for dated futures
+    pub fn funding_carry(futures: &FuturesPosition) -> f64 {
+        match (
+            futures.expiry,
+            futures.funding_rate,
+            futures.funding_horizon_days,
+        ) {
+            (None, Some(rate), Some(days)) => rate * days.max(0.0) * FUNDING_INTERVALS_PER_DAY,
+            _ => 0.0,
+        }
+    }
+
+    /// Calculate pre-expiry P&L for multiple positions (portfolio)
+    pub fn calculate_portfolio_value(
+        positions: &[Position],
+        underlying_price: f64,
+        context: &ValuationContext,
+    ) -> f64 {
+        let liquidated = LiquidationEngine::liquidated_legs(positions, underlying_price);
+        positions
+            .iter()
+            .zip(liquidated)
+            .filter(|(pos, _)| pos.is_active())
+            .map(|(pos, liquidated)| match pos {
+                Position::Futures(futures) if liquidated => {
+                    LiquidationEngine::liquidation_payoff(futures, underlying_price)
+                }
+                _ => Self::calculate_single_value(pos, underlying_price, context),
+            })
+            .sum()
+    }
+
+    /// Generate the T+n value curve across a price range
+    pub fn generate_value_curve(
         positions: &[Position],
         price_start: f64,
         price_end: f64,
         step_size: f64,
-    ) -> Vec<f64> {
-        let active_positions: Vec<&Position> =
-            positions.iter().filter(|pos| pos.is_active()).collect();
-        if active_positions.is_empty() {
-            return Vec::new();
-        }
-
-        let mut breakeven_points = Vec::new();
-        let mut prev_pnl = Self::calculate_portfolio_payoff(positions, price_start);
-        let mut current_price = price_start + step_size;
+        context: &ValuationContext,
+    ) -> Vec<PayoffPoint> {
+        let mut points = Vec::new();
+        let mut current_price = price_start;
 
         while current_price <= price_end {
-            let current_pnl = Self::calculate_portfolio_payoff(positions, current_price);
-
-            // Check if we crossed zero (sign change)
-            if (prev_pnl <= 0.0 && current_pnl >= 0.0) || (prev_pnl >= 0.0 && current_pnl <= 0.0) {
-                // Use linear interpolation to find more precise breakeven point
-                let breakeven = Self::interpolate_zero_crossing(
-                    current_price - step_size,
-                    prev_pnl,
-                    current_price,
-                    current_pnl,
-                );
-                breakeven_points.push(breakeven);
+            let payoff = Self::calculate_portfolio_value(positions, current_price, context);
+            if payoff.is_finite() {
+                points.push(PayoffPoint { price: current_price, payoff });
             }
-
-            prev_pnl = current_pnl;
             current_price += step_size;
         }
 
-        breakeven_points
+        points
+    }
+
+    /// Split positions by underlying, in order of first appearance
+    pub fn group_by_underlying(positions: &[Position]) -> Vec<UnderlyingGroup> {
+        let mut groups: Vec<UnderlyingGroup> = Vec::new();
+        for position in positions {
+            let underlying = position.underlying();
+            match groups
+                .iter_mut()
+                .find(|group| group.underlying.as_deref() == underlying)
+            {
+                Some(group) => group.positions.push(position.clone()),
+                None => groups.push(UnderlyingGroup {
+                    underlying: underlying.map(str::to_string),
+                    positions: vec![position.clone()],
+                }),
+            }
+        }
+        groups
+    }
+
+    /// Whether the active positions sit on more than one underlying, so that no single
+    /// price axis values them all
+    pub fn spans_several_underlyings(positions: &[Position]) -> bool {
+        let active: Vec<Position> = positions
+            .iter()
+            .filter(|pos| pos.is_active())
+            .cloned()
+            .collect();
+        Self::group_by_underlying(&active).len() > 1
+    }
+
+    /// Expiry payoff across underlyings, each moved from the reference price by its beta
+    pub fn calculate_beta_weighted_payoff(
+        positions: &[Position],
+        reference_price: f64,
+        weighting: &BetaWeighting,
+    ) -> f64 {
+        let groups = Self::group_by_underlying(positions);
+        Self::beta_weighted_total(&groups, reference_price, weighting, None)
+    }
+
+    /// Pre-expiry P&L across underlyings, each moved from the reference price by its beta
+    pub fn calculate_beta_weighted_value(
+        positions: &[Position],
+        reference_price: f64,
+        weighting: &BetaWeighting,
+        context: &ValuationContext,
+    ) -> f64 {
+        let groups = Self::group_by_underlying(positions);
+        Self::beta_weighted_total(&groups, reference_price, weighting, Some(context))
     }
 
-    /// Calculate maximum profit for a portfolio (if bounded)
-    pub fn calculate_max_profit(
+    /// Generate the beta-weighted expiry payoff (or T+n value, given a context)
+    /// across reference prices
+    pub fn generate_beta_weighted_curve(
         positions: &[Position],
         price_start: f64,
         price_end: f64,
         step_size: f64,
-    ) -> Option<f64> {
-        let active_positions: Vec<&Position> =
-            positions.iter().filter(|pos| pos.is_active()).collect();
-        if active_positions.is_empty() {
-            return None;
+        weighting: &BetaWeighting,
+        context: Option<&ValuationContext>,
+    ) -> Vec<PayoffPoint> {
+        let groups = Self::group_by_underlying(positions);
+        let mut points = Vec::new();
+        let mut current_price = price_start;
+
+        while current_price <= price_end {
+            let payoff = Self::beta_weighted_total(&groups, current_price, weighting, context);
+            if payoff.is_finite() {
+                points.push(PayoffPoint { price: current_price, payoff });
+            }
+            current_price += step_size;
         }
 
-        let points = Self::generate_payoff_curve(positions, price_start, price_end, step_size);
-        let max_profit = points
-            .iter()
-            .map(|p| p.payoff)
-            .max_by(|a, b| a.partial_cmp(b).unwrap());
+        points
+    }
+
+    /// Prices where a sampled curve crosses zero, interpolated between samples
+    pub fn find_curve_crossings(points: &[PayoffPoint]) -> Vec<f64> {
+        points
+            .windows(2)
+            .filter_map(|pair| {
+                let (left, right) = (&pair[0], &pair[1]);
+                if left.payoff == 0.0 {
+                    Some(left.price)
+                } else if left.payoff * right.payoff < 0.0 {
+                    let weight = left.payoff / (left.payoff - right.payoff);
+                    Some(left.price + weight * (right.price - left.price))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Whether any active option still has time value at the valuation date
+    pub fn has_live_options(positions: &[Position], context: &ValuationContext) -> bool {
+        positions.iter().any(|pos| match pos {
+            Position::Option(option) => {
+                option.active && context.leg_time_to_expiry(option.expiry) > 0.0
+            }
+            Position::Digital(digital) => {
+                digital.active && context.leg_time_to_expiry(digital.expiry) > 0.0
+            }
+            Position::Barrier(barrier) => {
+                barrier.active && context.leg_time_to_expiry(barrier.expiry) > 0.0
+            }
+            _ => false,
+        })
+    }
 
-        #[cfg(debug_assertions)]
-        if let Some(max) = max_profit {
-            web_sys::console::log_1(&format!("Max Profit calculated: ${:.2}", max).into());
+    /// Prices at which a position's expiry payoff can change slope or jump
+    pub fn payoff_kinks(position: &Position) -> Vec<f64> {
+        match position {
+            // A capped exercise fee switches from the rate to the cap in the money
+            Position::Option(option) => std::iter::once(option.strike_price)
+                .chain(Self::exercise_fee_cap_price(option))
+                .collect(),
+            Position::Digital(digital) => vec![digital.strike_price],
+            // Under the monotone path assumption the payoff also jumps at the barrier
+            Position::Barrier(barrier) => match barrier.path {
+                BarrierPath::Monotone => vec![barrier.strike_price, barrier.barrier_level],
+                BarrierPath::Touched => vec![barrier.strike_price],
+            },
+            // Isolated leveraged futures jump to a fixed loss at liquidation
+            Position::Futures(futures) if futures.margin_mode == MarginMode::Isolated => {
+                LiquidationEngine::liquidation_price(futures)
+                    .into_iter()
+                    .collect()
+            }
+            // Spot and other futures payoffs are a single straight line; cross-margin
+            // liquidations depend on the whole wallet
+            Position::Spot(_) | Position::Futures(_) => Vec::new(),
         }
+    }
+
+    /// Build the exact piecewise-linear expiry payoff of the active positions
+    pub fn build_piecewise_payoff(positions: &[Position]) -> PiecewisePayoff {
+        let mut kinks: Vec<f64> = positions
+            .iter()
+            .filter(|pos| pos.is_active())
+            .flat_map(Self::payoff_kinks)
+            .chain(LiquidationEngine::cross_liquidation_prices(positions))
+            .filter(|price| price.is_finite() && *price > 0.0)
+            .collect();
+        kinks.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        kinks.dedup();
+
+        // Segment boundaries: [0, k1], [k1, k2], ..., [kn, +inf)
+        let mut boundaries = vec![0.0];
+        boundaries.extend(kinks);
+
+        let segments = boundaries
+            .iter()
+            .enumerate()
+            .map(|(i, &start)| {
+                let end = boundaries.get(i + 1).copied().unwrap_or(f64::INFINITY);
 
-        max_profit
+                // Sample strictly inside the segment so jumps at the boundaries don't leak in
+                let (x1, x2) = if end.is_finite() {
+                    (
+                        start + (end - start) / 3.0,
+                        start + 2.0 * (end - start) / 3.0,
+                    )
+                } else {
+                    let scale = start.max(1.0);
+                    (start + scale, start + 2.0 * scale)
+                };
+                let inverse = Self::inverse_coefficient(positions, x1);
+                let y1 = Self::calculate_portfolio_payoff(positions, x1) - inverse / x1;
+                let y2 = Self::calculate_portfolio_payoff(positions, x2) - inverse / x2;
+                let slope = (y2 - y1) / (x2 - x1);
+
+                PayoffSegment { start, end, slope, intercept: y1 - slope * x1, inverse }
+            })
+            .collect();
+
+        PiecewisePayoff { segments }
     }
 
-    /// Calculate maximum loss for a portfolio (if bounded)
-    pub fn calculate_max_loss(
+    /// Find exact breakeven points for the portfolio within a price range
+    pub fn find_breakeven_points(
         positions: &[Position],
         price_start: f64,
         price_end: f64,
-        step_size: f64,
-    ) -> Option<f64> {
+    ) -> Vec<f64> {
         let active_positions: Vec<&Position> =
             positions.iter().filter(|pos| pos.is_active()).collect();
         if active_positions.is_empty() {
-            return None;
+            return Vec::new();
         }
 
-        let points = Self::generate_payoff_curve(positions, price_start, price_end, step_size);
-        let max_loss = points
-            .iter()
-            .map(|p| p.payoff)
-            .min_by(|a, b| a.partial_cmp(b).unwrap());
+        let payoff = Self::build_piecewise_payoff(positions);
+        let mut breakeven_points = Vec::new();
+
+        for (i, segment) in payoff.segments.iter().enumerate() {
+            let lower = segment.start.max(price_start);
+            let upper = segment.end.min(price_end);
+            if lower > upper {
+                continue;
+            }
+
+            breakeven_points.extend(segment.roots(lower, upper));
+            if segment.slope.abs() <= ZERO_TOLERANCE
+                && segment.inverse.abs() <= ZERO_TOLERANCE
+                && segment.intercept.abs() <= ZERO_TOLERANCE
+            {
+                // Flat at zero: report where the flat stretch begins
+                breakeven_points.push(lower);
+            }
+
+            // A jump across zero at the segment boundary also separates profit from loss
+            if let Some(previous) = i.checked_sub(1).map(|j| &payoff.segments[j]) {
+                let left = previous.value_at(segment.start);
+                let right = segment.value_at(segment.start);
+                if segment.start >= price_start && segment.start <= price_end && left * right < 0.0
+                {
+                    breakeven_points.push(segment.start);
+                }
+            }
+        }
 
-        #[cfg(debug_assertions)]
-        if let Some(min) = max_loss {
-            web_sys::console::log_1(&format!("Max Loss calculated: ${:.2}", min).into());
+        breakeven_points.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        breakeven_points.dedup_by(|a, b| (*a - *b).abs() <= ZERO_TOLERANCE * a.abs().max(1.0));
+        breakeven_points
+    }
+
+    /// Calculate maximum profit of the portfolio over all prices at expiry
+    pub fn calculate_max_profit(positions: &[Position]) -> Option<PayoffBound> {
+        let payoff = Self::active_piecewise_payoff(positions)?;
+
+        // Rising payoff as price goes to infinity (or short coin P&L as price goes
+        // to zero) means profit has no cap
+        if payoff.asymptotic_slope() > ZERO_TOLERANCE
+            || payoff.inverse_coefficient() > ZERO_TOLERANCE
+        {
+            return Some(PayoffBound::Unbounded);
         }
 
-        max_loss
+        let max = payoff
+            .extreme_values()
+            .into_iter()
+            .fold(f64::NEG_INFINITY, f64::max);
+        Some(PayoffBound::Bounded(max))
+    }
+
+    /// Calculate maximum loss of the portfolio over all prices at expiry
+    pub fn calculate_max_loss(positions: &[Position]) -> Option<PayoffBound> {
+        let payoff = Self::active_piecewise_payoff(positions)?;
+
+        // Falling payoff as price goes to infinity means loss has no floor; on the
+        // downside the worst case is the value at zero, unbounded only for coin P&L
+        if payoff.asymptotic_slope() < -ZERO_TOLERANCE
+            || payoff.inverse_coefficient() < -ZERO_TOLERANCE
+        {
+            return Some(PayoffBound::Unbounded);
+        }
+
+        let min = payoff
+            .extreme_values()
+            .into_iter()
+            .fold(f64::INFINITY, f64::min);
+        Some(PayoffBound::Bounded(min))
     }
 
     // === Private helper functions ===
 
+    /// Sum of each group's payoff (or value) at the price implied by the reference price;
+    /// groups without a price are left out
+    fn beta_weighted_total(
+        groups: &[UnderlyingGroup],
+        reference_price: f64,
+        weighting: &BetaWeighting,
+        context: Option<&ValuationContext>,
+    ) -> f64 {
+        groups
+            .iter()
+            .filter_map(|group| {
+                let price =
+                    weighting.implied_price(group.underlying.as_deref(), reference_price)?;
+                Some(match context {
+                    Some(context) => {
+                        Self::calculate_portfolio_value(&group.positions, price, context)
+                    }
+                    None => Self::calculate_portfolio_payoff(&group.positions, price),
+                })
+            })
+            .sum()
+    }
+
     /// Calculate spot position payoff
     fn calculate_spot_payoff(spot: &SpotPosition, underlying_price: f64) -> f64 {
-        // P&L = Quantity * (Current Price - Entry Price)
+        // P&L = Quantity * (Current Price - Entry Price) - fees
         spot.quantity * (underlying_price - spot.entry_price)
+            - Self::spot_costs(spot, underlying_price)
+    }
+
+    /// Fees and slippage on buying at entry and selling at the current price
+    fn spot_costs(spot: &SpotPosition, underlying_price: f64) -> f64 {
+        let fees = spot.fees.unwrap_or_default();
+        spot.quantity.abs()
+            * ((fees.entry_rate + fees.slippage) * spot.entry_price
+                + (fees.exit_rate + fees.slippage) * underlying_price)
+    }
+
+    /// Fees and slippage on the entry trade and a closing trade at the current price,
+    /// in the P&L currency
+    fn futures_costs(futures: &FuturesPosition, underlying_price: f64) -> f64 {
+        let fees = futures.fees.unwrap_or_default();
+        let entry = fees.entry_rate + fees.slippage;
+        let exit = fees.exit_rate + fees.slippage;
+        let notional = futures.quantity.abs() * futures.contract_size;
+        match futures.contract_type {
+            ContractType::Linear => {
+                notional * (entry * futures.entry_price + exit * underlying_price)
+            }
+            // Inverse fees are charged in coin on the contract value
+            ContractType::Inverse if futures.pnl_in_base => {
+                notional * (entry / futures.entry_price + exit / underlying_price)
+            }
+            ContractType::Inverse => {
+                notional * (entry * underlying_price / futures.entry_price + exit)
+            }
+        }
+    }
+
+    /// Fee on one option trade: a rate of the underlying, capped at a fraction of the option price
+    fn option_fee(fees: &TradingFees, rate: f64, underlying_price: f64, option_price: f64) -> f64 {
+        let fee = rate * underlying_price;
+        match fees.premium_cap {
+            Some(cap) => fee.min(cap * option_price),
+            None => fee,
+        }
+    }
+
+    /// Entry cost per contract of an option-like leg; the strike stands in for
+    /// the underlying at entry
+    fn option_entry_cost(fees: &TradingFees, strike_price: f64, premium: f64) -> f64 {
+        Self::option_fee(fees, fees.entry_rate, strike_price, premium) + fees.slippage * premium
+    }
+
+    /// Cost per contract of closing an option-like leg at `option_price` before expiry
+    fn option_exit_cost(fees: &TradingFees, underlying_price: f64, option_price: f64) -> f64 {
+        Self::option_fee(fees, fees.exit_rate, underlying_price, option_price)
+            + fees.slippage * option_price
+    }
+
+    /// Entry cost plus the exercise fee of an option finishing in the money
+    fn option_expiry_costs(option: &OptionPosition, underlying_price: f64) -> f64 {
+        let fees = option.fees.unwrap_or_default();
+        let intrinsic_value = match option.option_type {
+            OptionType::Call => (underlying_price - option.strike_price).max(0.0),
+            OptionType::Put => (option.strike_price - underlying_price).max(0.0),
+        };
+        let exercise = if intrinsic_value > 0.0 {
+            Self::option_fee(&fees, fees.exercise_rate, underlying_price, intrinsic_value)
+        } else {
+            0.0
+        };
+
+        option.units().abs()
+            * (Self::option_entry_cost(&fees, option.strike_price, option.premium) + exercise)
+    }
+
+    /// Price where a capped exercise fee reaches its cap
+    fn exercise_fee_cap_price(option: &OptionPosition) -> Option<f64> {
+        let fees = option.fees?;
+        let cap = fees.premium_cap?;
+        let rate = fees.exercise_rate;
+        if rate <= 0.0 {
+            return None;
+        }
+
+        // rate * price = cap * intrinsic value
+        let price = match option.option_type {
+            OptionType::Call => cap * option.strike_price / (cap - rate),
+            OptionType::Put => cap * option.strike_price / (cap + rate),
+        };
+        (price.is_finite() && price > 0.0).then_some(price)
+    }
+
+    /// Calculate digital position payoff: the payout if it finishes in the money
+    fn calculate_digital_payoff(digital: &DigitalPosition, underlying_price: f64) -> f64 {
+        let in_the_money = match digital.option_type {
+            OptionType::Call => underlying_price > digital.strike_price,
+            OptionType::Put => underlying_price < digital.strike_price,
+        };
+        let payout = if in_the_money { digital.payout } else { 0.0 };
+        let fees = digital.fees.unwrap_or_default();
+
+        digital.quantity * (payout - digital.premium)
+            - digital.quantity.abs()
+                * Self::option_entry_cost(&fees, digital.strike_price, digital.premium)
+    }
+
+    /// Calculate digital position P&L with Black-Scholes before expiry
+    fn calculate_digital_value(
+        digital: &DigitalPosition,
+        underlying_price: f64,
+        context: &ValuationContext,
+    ) -> f64 {
+        let time_to_expiry = context.leg_time_to_expiry(digital.expiry);
+        if time_to_expiry <= 0.0 {
+            return Self::calculate_digital_payoff(digital, underlying_price);
+        }
+
+        let probability_value = PricingEngine::digital_price(
+            digital.option_type,
+            underlying_price,
+            digital.strike_price,
+            time_to_expiry,
+            context.risk_free_rate,
+            digital.implied_volatility.unwrap_or(context.volatility),
+        );
+
+        let digital_value = digital.payout * probability_value;
+        let fees = digital.fees.unwrap_or_default();
+
+        digital.quantity * (digital_value - digital.premium)
+            - digital.quantity.abs()
+                * (Self::option_entry_cost(&fees, digital.strike_price, digital.premium)
+                    + Self::option_exit_cost(&fees, underlying_price, digital_value))
+    }
+
+    /// Calculate barrier position payoff: the vanilla payoff if the option is alive
+    /// at expiry, the rebate otherwise
+    fn calculate_barrier_payoff(barrier: &BarrierPosition, underlying_price: f64) -> f64 {
+        let intrinsic_value = match barrier.option_type {
+            OptionType::Call => (underlying_price - barrier.strike_price).max(0.0),
+            OptionType::Put => (barrier.strike_price - underlying_price).max(0.0),
+        };
+        let alive = PricingEngine::barrier_touched(barrier, underlying_price)
+            == barrier.barrier_type.is_knock_in();
+        let value = if alive {
+            intrinsic_value
+        } else {
+            barrier.rebate
+        };
+        let fees = barrier.fees.unwrap_or_default();
+
+        barrier.quantity * (value - barrier.premium)
+            - barrier.quantity.abs()
+                * Self::option_entry_cost(&fees, barrier.strike_price, barrier.premium)
+    }
+
+    /// Calculate barrier position P&L before expiry
+    fn calculate_barrier_value(
+        barrier: &BarrierPosition,
+        underlying_price: f64,
+        context: &ValuationContext,
+    ) -> f64 {
+        let time_to_expiry = context.leg_time_to_expiry(barrier.expiry);
+        if time_to_expiry <= 0.0 {
+            return Self::calculate_barrier_payoff(barrier, underlying_price);
+        }
+
+        let barrier_value =
+            PricingEngine::value_barrier(barrier, underlying_price, time_to_expiry, context);
+        let fees = barrier.fees.unwrap_or_default();
+
+        barrier.quantity * (barrier_value - barrier.premium)
+            - barrier.quantity.abs()
+                * (Self::option_entry_cost(&fees, barrier.strike_price, barrier.premium)
+                    + Self::option_exit_cost(&fees, underlying_price, barrier_value))
     }
 
     /// Calculate option position payoff
@@ -156,38 +916,123 @@ impl PayoffEngine {
             OptionType::Put => (option.strike_price - underlying_price).max(0.0),
         };
 
-        // Calculate payoff per contract (always from long perspective first)
-        let long_payoff_per_contract = intrinsic_value - option.premium;
+        // Calculate payoff per underlying unit (always from long perspective first)
+        let long_payoff_per_unit = intrinsic_value - option.premium;
 
-        // Apply quantity (negative quantity automatically makes it short)
-        let total_payoff = option.quantity * long_payoff_per_contract;
+        // Apply quantity times the contract multiplier (negative quantity automatically
+        // makes it short), net of fees
+        let total_payoff = option.units() * long_payoff_per_unit
+            - Self::option_expiry_costs(option, underlying_price);
 
         // Debug logging for troubleshooting
-        #[cfg(debug_assertions)]
+        #[cfg(all(debug_assertions, target_arch = "wasm32"))]
         if underlying_price == 200.0 || underlying_price == 0.0 || underlying_price == 250.0 {
             web_sys::console::log_1(&format!(
-                "Option Debug - Underlying: ${:.2}, Intrinsic: ${:.2}, Long P&L: ${:.2}, Quantity: {}, Total: ${:.2}",
-                underlying_price, intrinsic_value, long_payoff_per_contract, option.quantity, total_payoff
+                "Option Debug - Underlying: ${:.2}, Intrinsic: ${:.2}, Long P&L: ${:.2}, Units: {}, Total: ${:.2}",
+                underlying_price, intrinsic_value, long_payoff_per_unit, option.units(), total_payoff
             ).into());
         }
 
         total_payoff
     }
 
-    /// Calculate futures position payoff
+    /// Calculate option position P&L with its pricing model before expiry
+    fn calculate_option_value(
+        option: &OptionPosition,
+        underlying_price: f64,
+        context: &ValuationContext,
+    ) -> f64 {
+        // Legs that have expired by the valuation date settle at intrinsic
+        let time_to_expiry = context.leg_time_to_expiry(option.expiry);
+        if time_to_expiry <= 0.0 {
+            return Self::calculate_option_payoff(option, underlying_price);
+        }
+
+        let theoretical_value =
+            PricingEngine::value_option(option, underlying_price, time_to_expiry, context);
+
+        let fees = option.fees.unwrap_or_default();
+
+        option.units() * (theoretical_value - option.premium)
+            - option.units().abs()
+                * (Self::option_entry_cost(&fees, option.strike_price, option.premium)
+                    + Self::option_exit_cost(&fees, underlying_price, theoretical_value))
+    }
+
+    /// Calculate futures position payoff, including expected funding on perpetuals
     fn calculate_futures_payoff(futures: &FuturesPosition, underlying_price: f64) -> f64 {
-        // P&L = Quantity * Contract Size * (Current Price - Entry Price)
-        futures.quantity * futures.contract_size * (underlying_price - futures.entry_price)
+        Self::calculate_futures_price_pnl(futures, underlying_price)
+            + Self::calculate_futures_funding(futures, underlying_price)
+            - Self::futures_costs(futures, underlying_price)
     }
 
-    /// Linear interpolation to find where P&L crosses zero
-    fn interpolate_zero_crossing(x1: f64, y1: f64, x2: f64, y2: f64) -> f64 {
-        if (y2 - y1).abs() < f64::EPSILON {
-            return x1; // Avoid division by zero
+    /// Calculate futures P&L from the price move alone
+    fn calculate_futures_price_pnl(futures: &FuturesPosition, underlying_price: f64) -> f64 {
+        let notional = futures.quantity * futures.contract_size;
+        match futures.contract_type {
+            // P&L = Quantity * Contract Size * (Current Price - Entry Price)
+            ContractType::Linear => notional * (underlying_price - futures.entry_price),
+            // Coin P&L = Quantity * Contract Size * (1 / Entry Price - 1 / Current Price)
+            ContractType::Inverse if futures.pnl_in_base => {
+                notional * (1.0 / futures.entry_price - 1.0 / underlying_price)
+            }
+            // Converted back to quote at the current price, which makes it linear again
+            ContractType::Inverse => notional * (underlying_price / futures.entry_price - 1.0),
+        }
+    }
+
+    /// Funding over the holding horizon; longs pay when the rate is positive
+    fn calculate_futures_funding(futures: &FuturesPosition, underlying_price: f64) -> f64 {
+        let carry = Self::funding_carry(futures);
+        if carry == 0.0 {
+            return 0.0;
         }
 
-        // Linear interpolation: x = x1 + (0 - y1) * (x2 - x1) / (y2 - y1)
-        x1 + (0.0 - y1) * (x2 - x1) / (y2 - y1)
+        // Funding is charged on the position value in its settlement currency
+        let position_value = futures.quantity
+            * match futures.contract_type {
+                ContractType::Linear => futures.contract_size * underlying_price,
+                ContractType::Inverse if futures.pnl_in_base => {
+                    futures.contract_size / underlying_price
+                }
+                ContractType::Inverse => futures.contract_size,
+            };
+        -position_value * carry
+    }
+
+    /// Coefficient of 1/price in the payoff around a price: inverse futures
+    /// reported in coin add `-quantity * size / price`, and their exit fees
+    /// `-|quantity| * size * rate / price`, until they are liquidated
+    fn inverse_coefficient(positions: &[Position], underlying_price: f64) -> f64 {
+        let liquidated = LiquidationEngine::liquidated_legs(positions, underlying_price);
+        positions
+            .iter()
+            .zip(liquidated)
+            .map(|(pos, liquidated)| match pos {
+                Position::Futures(futures)
+                    if futures.active
+                        && !liquidated
+                        && futures.contract_type == ContractType::Inverse
+                        && futures.pnl_in_base =>
+                {
+                    let fees = futures.fees.unwrap_or_default();
+                    -futures.quantity * futures.contract_size * (1.0 + Self::funding_carry(futures))
+                        - futures.quantity.abs()
+                            * futures.contract_size
+                            * (fees.exit_rate + fees.slippage)
+                }
+                _ => 0.0,
+            })
+            .sum()
+    }
+
+    /// Piecewise payoff of the active positions, or None if nothing is active
+    fn active_piecewise_payoff(positions: &[Position]) -> Option<PiecewisePayoff> {
+        if !positions.iter().any(|pos| pos.is_active()) {
+            return None;
+        }
+
+        Some(Self::build_piecewise_payoff(positions))
     }
 }
 
@@ -249,4 +1094,427 @@ mod tests {
         assert_eq!(curve[4].price, 60.0);
         assert_eq!(curve[4].payoff, 1000.0); // (60-50) * 100 = 1000
     }
+
+    #[test]
+    fn test_option_value_before_expiry() {
+        let call = OptionPosition::new(OptionType::Call, 1.0, 100.0, 5.0, None);
+        let positions = vec![Position::Option(call)];
+
+        // With time remaining the call is worth more than its expiry payoff
+        let context = ValuationContext::new(30.0, 0.0, 0.5, 0.05);
+        let value = PayoffEngine::calculate_portfolio_value(&positions, 100.0, &context);
+        let payoff = PayoffEngine::calculate_portfolio_payoff(&positions, 100.0);
+        assert!(value > payoff);
+
+        // At expiry the value curve collapses onto the payoff curve
+        let expired = ValuationContext::new(30.0, 30.0, 0.5, 0.05);
+        let value_curve =
+            PayoffEngine::generate_value_curve(&positions, 80.0, 120.0, 10.0, &expired);
+        let payoff_curve = PayoffEngine::generate_payoff_curve(&positions, 80.0, 120.0, 10.0);
+        assert_eq!(value_curve, payoff_curve);
+    }
+
+    #[test]
+    fn test_calendar_spread_at_valuation_date() {
+        let today = chrono::DateTime::parse_from_rfc3339("2025-01-01T08:00:00Z")
+            .unwrap()
+            .to_utc();
+        let near = OptionPosition {
+            expiry: Some(today + chrono::Duration::days(7)),
+            ..OptionPosition::new(OptionType::Call, -1.0, 100.0, 2.0, None)
+        };
+        let far = OptionPosition {
+            expiry: Some(today + chrono::Duration::days(37)),
+            ..OptionPosition::new(OptionType::Call, 1.0, 100.0, 5.0, None)
+        };
+        let positions = vec![Position::Option(near), Position::Option(far)];
+
+        // At the near expiry the short leg settles at intrinsic, the long leg keeps 30 days
+        let context = ValuationContext {
+            reference_date: today,
+            ..ValuationContext::new(30.0, 7.0, 0.5, 0.0)
+        };
+        assert!(PayoffEngine::has_live_options(&positions, &context));
+
+        let far_value = PricingEngine::black_scholes_price(
+            OptionType::Call,
+            110.0,
+            100.0,
+            30.0 / 365.0,
+            0.0,
+            0.5,
+        );
+        let expected = -(10.0 - 2.0) + (far_value - 5.0);
+        let value = PayoffEngine::calculate_portfolio_value(&positions, 110.0, &context);
+        assert!((value - expected).abs() < 1e-9);
+
+        let after_both = ValuationContext { days_elapsed: 40.0, ..context };
+        assert!(!PayoffEngine::has_live_options(&positions, &after_both));
+    }
+
+    #[test]
+    fn test_breakeven_points_are_exact() {
+        // Long straddle at 100 costing 10 in total breaks even at exactly 90 and 110
+        let call = OptionPosition::new(OptionType::Call, 1.0, 100.0, 4.0, None);
+        let put = OptionPosition::new(OptionType::Put, 1.0, 100.0, 6.0, None);
+        let positions = vec![Position::Option(call), Position::Option(put)];
+
+        let breakevens = PayoffEngine::find_breakeven_points(&positions, 0.0, 300.0);
+        assert_eq!(breakevens.len(), 2);
+        assert!((breakevens[0] - 90.0).abs() < 1e-9);
+        assert!((breakevens[1] - 110.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_breakeven_on_range_boundary_counted_once() {
+        let spot = SpotPosition::new(100.0, 50.0, None);
+        let positions = vec![Position::Spot(spot)];
+
+        assert_eq!(
+            PayoffEngine::find_breakeven_points(&positions, 40.0, 60.0),
+            vec![50.0]
+        );
+        assert_eq!(
+            PayoffEngine::find_breakeven_points(&positions, 50.0, 60.0),
+            vec![50.0]
+        );
+    }
+
+    #[test]
+    fn test_max_profit_and_loss_are_exact() {
+        // Bull call spread 100/110 for a net debit of 4
+        let long_call = OptionPosition::new(OptionType::Call, 1.0, 100.0, 6.0, None);
+        let short_call = OptionPosition::new(OptionType::Call, -1.0, 110.0, 2.0, None);
+        let positions = vec![Position::Option(long_call), Position::Option(short_call)];
+
+        let max_profit = PayoffEngine::calculate_max_profit(&positions);
+        let max_loss = PayoffEngine::calculate_max_loss(&positions);
+        assert!(matches!(max_profit, Some(PayoffBound::Bounded(p)) if (p - 6.0).abs() < 1e-9));
+        assert!(matches!(max_loss, Some(PayoffBound::Bounded(l)) if (l + 4.0).abs() < 1e-9));
+
+        // A naked short call has no floor on losses
+        let naked = vec![Position::Option(OptionPosition::new(
+            OptionType::Call,
+            -1.0,
+            100.0,
+            5.0,
+            None,
+        ))];
+        assert_eq!(
+            PayoffEngine::calculate_max_loss(&naked),
+            Some(PayoffBound::Unbounded)
+        );
+        assert_eq!(
+            PayoffEngine::calculate_max_profit(&naked),
+            Some(PayoffBound::Bounded(5.0))
+        );
+    }
+
+    #[test]
+    fn test_inverse_futures_payoff() {
+        // Long 1 inverse contract worth 100 USD, entered at 100
+        let mut futures = FuturesPosition::new(1.0, 100.0, 100.0, None);
+        futures.contract_type = ContractType::Inverse;
+        let quote = vec![Position::Futures(futures.clone())];
+        futures.pnl_in_base = true;
+        let coin = vec![Position::Futures(futures)];
+
+        // Quote P&L is linear, coin P&L is convex in 1/price
+        assert!((PayoffEngine::calculate_portfolio_payoff(&quote, 200.0) - 100.0).abs() < 1e-9);
+        assert!((PayoffEngine::calculate_portfolio_payoff(&coin, 200.0) - 0.5).abs() < 1e-9);
+        assert!((PayoffEngine::calculate_portfolio_payoff(&coin, 50.0) + 1.0).abs() < 1e-9);
+
+        let breakevens = PayoffEngine::find_breakeven_points(&coin, 0.0, 300.0);
+        assert_eq!(breakevens.len(), 1);
+        assert!((breakevens[0] - 100.0).abs() < 1e-9);
+
+        // Coin gains are capped at the contract value in coin, losses grow without limit
+        assert_eq!(
+            PayoffEngine::calculate_max_loss(&coin),
+            Some(PayoffBound::Unbounded)
+        );
+        assert!(matches!(
+            PayoffEngine::calculate_max_profit(&coin),
+            Some(PayoffBound::Bounded(p)) if (p - 1.0).abs() < 1e-9
+        ));
+        assert!(PayoffEngine::generate_payoff_curve(&coin, 0.0, 100.0, 10.0)
+            .iter()
+            .all(|point| point.payoff.is_finite() && point.price > 0.0));
+    }
+
+    #[test]
+    fn test_digital_payoff_steps_at_strike() {
+        // Long 2 digital calls struck at 100 paying 10 for a premium of 4
+        let call = DigitalPosition::new(OptionType::Call, 2.0, 100.0, 10.0, 4.0, None);
+        let positions = vec![Position::Digital(call.clone())];
+
+        assert!((PayoffEngine::calculate_portfolio_payoff(&positions, 90.0) + 8.0).abs() < 1e-9);
+        assert!((PayoffEngine::calculate_portfolio_payoff(&positions, 100.0) + 8.0).abs() < 1e-9);
+        assert!((PayoffEngine::calculate_portfolio_payoff(&positions, 110.0) - 12.0).abs() < 1e-9);
+
+        // Both sides of the step are flat, so profit and loss are capped
+        assert!(matches!(
+            PayoffEngine::calculate_max_profit(&positions),
+            Some(PayoffBound::Bounded(p)) if (p - 12.0).abs() < 1e-9
+        ));
+        assert!(matches!(
+            PayoffEngine::calculate_max_loss(&positions),
+            Some(PayoffBound::Bounded(l)) if (l + 8.0).abs() < 1e-9
+        ));
+
+        // The matching put pays on the other side of the strike
+        let put = DigitalPosition::new(OptionType::Put, 2.0, 100.0, 10.0, 4.0, None);
+        let strangle = vec![Position::Digital(call), Position::Digital(put)];
+        assert!((PayoffEngine::calculate_portfolio_payoff(&strangle, 90.0) - 4.0).abs() < 1e-9);
+        assert!((PayoffEngine::calculate_portfolio_payoff(&strangle, 110.0) - 4.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_barrier_payoff_under_path_assumption() {
+        // Long an up-and-out call struck at 100, knocked out at 120 with a rebate of 1
+        let barrier = BarrierPosition {
+            rebate: 1.0,
+            ..BarrierPosition::new(
+                OptionType::Call,
+                BarrierType::UpAndOut,
+                1.0,
+                100.0,
+                120.0,
+                3.0,
+                None,
+            )
+        };
+        let positions = vec![Position::Barrier(barrier.clone())];
+
+        assert!((PayoffEngine::calculate_portfolio_payoff(&positions, 90.0) + 3.0).abs() < 1e-9);
+        assert!((PayoffEngine::calculate_portfolio_payoff(&positions, 110.0) - 7.0).abs() < 1e-9);
+        assert!((PayoffEngine::calculate_portfolio_payoff(&positions, 130.0) + 2.0).abs() < 1e-9);
+
+        // Profit peaks just below the barrier; the knock-out caps the upside
+        assert!(matches!(
+            PayoffEngine::calculate_max_profit(&positions),
+            Some(PayoffBound::Bounded(p)) if (p - 17.0).abs() < 1e-9
+        ));
+        let breakevens = PayoffEngine::find_breakeven_points(&positions, 0.0, 200.0);
+        assert_eq!(breakevens.len(), 2);
+        assert!((breakevens[0] - 103.0).abs() < 1e-9);
+        assert!((breakevens[1] - 120.0).abs() < 1e-9);
+
+        // Once touched, the option is dead at every expiry price
+        let touched = vec![Position::Barrier(BarrierPosition {
+            path: BarrierPath::Touched,
+            ..barrier
+        })];
+        assert!((PayoffEngine::calculate_portfolio_payoff(&touched, 110.0) + 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_perpetual_funding_carry() {
+        // Long 2 perpetuals at 100 paying 0.01% every 8h for 10 days: 0.3% of value
+        let perpetual = FuturesPosition {
+            funding_rate: Some(0.0001),
+            funding_horizon_days: Some(10.0),
+            ..FuturesPosition::new(2.0, 100.0, 1.0, None)
+        };
+        let positions = vec![Position::Futures(perpetual.clone())];
+
+        let funding = PayoffEngine::calculate_expected_funding(&positions, 100.0);
+        assert!((funding + 0.6).abs() < 1e-9);
+        assert!(
+            (PayoffEngine::calculate_portfolio_payoff(&positions, 100.0) - funding).abs() < 1e-9
+        );
+
+        // Break-even moves up to cover the carry
+        let breakevens = PayoffEngine::find_breakeven_points(&positions, 0.0, 200.0);
+        assert!((breakevens[0] - 100.0 / 0.997).abs() < 1e-9);
+
+        // Dated futures never pay funding
+        let dated = FuturesPosition { expiry: Some(chrono::Utc::now()), ..perpetual };
+        assert_eq!(PayoffEngine::funding_carry(&dated), 0.0);
+    }
+
+    #[test]
+    fn test_payoff_clipped_at_liquidation() {
+        // 10x isolated long at 100 liquidates near 90.36 and loses its 10 of margin
+        let futures = FuturesPosition {
+            leverage: Some(10.0),
+            margin_mode: MarginMode::Isolated,
+            ..FuturesPosition::new(1.0, 100.0, 1.0, None)
+        };
+        let positions = vec![Position::Futures(futures)];
+
+        assert!((PayoffEngine::calculate_portfolio_payoff(&positions, 95.0) + 5.0).abs() < 1e-9);
+        assert!((PayoffEngine::calculate_portfolio_payoff(&positions, 50.0) + 10.0).abs() < 1e-9);
+        assert!(matches!(
+            PayoffEngine::calculate_max_loss(&positions),
+            Some(PayoffBound::Bounded(loss)) if (loss + 10.0).abs() < 1e-9
+        ));
+    }
+
+    #[test]
+    fn test_group_by_underlying() {
+        let mut sol = Position::Spot(SpotPosition::new(10.0, 20.0, None));
+        sol.set_underlying(Some(" solusdt ".to_string()));
+        let positions = vec![
+            Position::Spot(SpotPosition::new(1.0, 100.0, None)),
+            sol.clone(),
+            Position::Option(OptionPosition::new(OptionType::Call, 1.0, 100.0, 5.0, None)),
+        ];
+
+        let groups = PayoffEngine::group_by_underlying(&positions);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].underlying, None);
+        assert_eq!(groups[0].positions.len(), 2);
+        assert_eq!(groups[1].underlying.as_deref(), Some("SOLUSDT"));
+        assert_eq!(groups[1].positions, vec![sol]);
+    }
+
+    #[test]
+    fn test_beta_weighted_payoff() {
+        // 1 primary unit at 100 and 10 SOL at 20, with SOL moving twice as much
+        let mut sol = Position::Spot(SpotPosition::new(10.0, 20.0, None));
+        sol.set_underlying(Some("SOLUSDT".to_string()));
+        let mut eth = Position::Spot(SpotPosition::new(1.0, 3000.0, None));
+        eth.set_underlying(Some("ETHUSDT".to_string()));
+        let positions = vec![
+            Position::Spot(SpotPosition::new(1.0, 100.0, None)),
+            sol,
+            eth,
+        ];
+        let settings = CalculationSettings {
+            underlying_price: Some(100.0),
+            underlying_quotes: vec![UnderlyingQuote {
+                symbol: "SOLUSDT".to_string(),
+                price: Some(20.0),
+                beta: 2.0,
+            }],
+            ..CalculationSettings::default()
+        };
+
+        // +10% on the primary moves SOL +20% to 24; unpriced ETH is left out
+        let weighting = BetaWeighting::from_settings(&settings, None, 100.0);
+        let payoff = PayoffEngine::calculate_beta_weighted_payoff(&positions, 110.0, &weighting);
+        assert!((payoff - 50.0).abs() < 1e-9);
+        assert_eq!(
+            weighting.unpriced_underlyings(&positions),
+            vec![Some("ETHUSDT".to_string())]
+        );
+
+        // Drawn against SOL, +10% on SOL moves the primary +5%
+        let weighting = BetaWeighting::from_settings(&settings, Some("SOLUSDT"), 20.0);
+        let payoff = PayoffEngine::calculate_beta_weighted_payoff(&positions, 22.0, &weighting);
+        assert!((payoff - 25.0).abs() < 1e-9);
+
+        let curve = PayoffEngine::generate_beta_weighted_curve(
+            &positions, 10.0, 30.0, 1.0, &weighting, None,
+        );
+        let crossings = PayoffEngine::find_curve_crossings(&curve);
+        assert_eq!(crossings.len(), 1);
+        assert!((crossings[0] - 20.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_fees_shift_breakeven_and_max_loss() {
+        // Binance-style option fees: 0.03% of the underlying at entry, 0.015% on
+        // exercise, both capped at 10% of the option price
+        let fees = TradingFees {
+            entry_rate: 0.0003,
+            exercise_rate: 0.00015,
+            premium_cap: Some(0.1),
+            ..TradingFees::default()
+        };
+        let call = OptionPosition {
+            fees: Some(fees),
+            ..OptionPosition::new(OptionType::Call, 1.0, 100.0, 5.0, None)
+        };
+        let positions = vec![Position::Option(call)];
+
+        assert!(matches!(
+            PayoffEngine::calculate_max_loss(&positions),
+            Some(PayoffBound::Bounded(loss)) if (loss + 5.03).abs() < 1e-9
+        ));
+        let breakevens = PayoffEngine::find_breakeven_points(&positions, 0.0, 200.0);
+        assert_eq!(breakevens.len(), 1);
+        assert!((breakevens[0] - 105.03 / (1.0 - 0.00015)).abs() < 1e-9);
+
+        // Just in the money the exercise fee is held at 10% of the intrinsic value
+        let payoff = PayoffEngine::calculate_portfolio_payoff(&positions, 100.1);
+        assert!((payoff - (0.1 - 5.03 - 0.01)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_option_multiplier_scales_contracts() {
+        let fees = TradingFees {
+            entry_rate: 0.0003,
+            exercise_rate: 0.00015,
+            premium_cap: Some(0.1),
+            ..TradingFees::default()
+        };
+        // One contract on 100 units is the same exposure as 100 single-unit contracts
+        let contract = Position::Option(OptionPosition {
+            multiplier: Some(100.0),
+            fees: Some(fees),
+            ..OptionPosition::new(OptionType::Call, 1.0, 100.0, 5.0, None)
+        });
+        let units = Position::Option(OptionPosition {
+            fees: Some(fees),
+            ..OptionPosition::new(OptionType::Call, 100.0, 100.0, 5.0, None)
+        });
+
+        let context = ValuationContext::new(30.0, 10.0, 0.5, 0.05);
+        for price in [50.0, 100.0, 105.0, 150.0] {
+            let expected = PayoffEngine::calculate_single_payoff(&units, price);
+            assert!(
+                (PayoffEngine::calculate_single_payoff(&contract, price) - expected).abs() < 1e-9
+            );
+            let expected = PayoffEngine::calculate_single_value(&units, price, &context);
+            assert!(
+                (PayoffEngine::calculate_single_value(&contract, price, &context) - expected).abs()
+                    < 1e-9
+            );
+        }
+        assert!(matches!(
+            PayoffEngine::calculate_max_loss(&[contract]),
+            Some(PayoffBound::Bounded(loss)) if (loss + 503.0).abs() < 1e-9
+        ));
+    }
+
+    #[test]
+    fn test_fee_schedule_applies_to_positions_without_fees() {
+        let schedule = FeeSchedule { enabled: true, ..FeeSchedule::default() };
+        let spot = Position::Spot(SpotPosition::new(1.0, 100.0, None));
+        let own = Position::Spot(SpotPosition {
+            fees: Some(TradingFees::default()),
+            ..SpotPosition::new(1.0, 100.0, None)
+        });
+        let positions = schedule.apply(&[spot, own]);
+
+        // 0.1% on the way in and out: 100.1 + 0.1% of the exit price
+        let breakevens = PayoffEngine::find_breakeven_points(&positions[..1], 0.0, 200.0);
+        assert!((breakevens[0] - 100.1 / 0.999).abs() < 1e-9);
+        assert_eq!(
+            PayoffEngine::calculate_trading_costs(&positions[1..], 120.0),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_inverse_exit_fees_in_coin() {
+        let futures = FuturesPosition {
+            contract_type: ContractType::Inverse,
+            pnl_in_base: true,
+            fees: Some(TradingFees {
+                entry_rate: 0.0005,
+                exit_rate: 0.0005,
+                ..TradingFees::default()
+            }),
+            ..FuturesPosition::new(1.0, 100.0, 100.0, None)
+        };
+        let positions = vec![Position::Futures(futures)];
+
+        let breakevens = PayoffEngine::find_breakeven_points(&positions, 1.0, 200.0);
+        assert_eq!(breakevens.len(), 1);
+        assert!(breakevens[0] > 100.0);
+        assert!(PayoffEngine::calculate_portfolio_payoff(&positions, breakevens[0]).abs() < 1e-9);
+    }
 }
@@ -1,15 +1,53 @@
-use super::payoff_engine::{PayoffEngine, PayoffPoint};
-use crate::models::Position;
+use super::greeks_engine::{Greeks, GreeksEngine, GreeksPoint};
+use super::liquidation_engine::LiquidationEngine;
+use super::margin_engine::{MarginEngine, MarginRequirement};
+use super::payoff_engine::{BetaWeighting, PayoffBound, PayoffEngine, PayoffPoint};
+use super::pricing_engine::{PricingEngine, ValuationContext};
+use super::simulation_engine::{
+    percentile_of_sorted, SimulationConfig, SimulationEngine, SimulationResult,
+};
+use crate::models::{CalculationSettings, Position, PriceDistribution, VarSettings};
+
+const DAYS_PER_YEAR: f64 = 365.0;
+
+/// Fewest historical return scenarios a historical VaR is reported from
+const MIN_HISTORY_SCENARIOS: usize = 20;
 
 /// Portfolio analysis metrics
 #[derive(Debug, Clone, PartialEq)]
 pub struct PortfolioMetrics {
     pub total_positions: usize,
     pub breakeven_points: Vec<f64>,
-    pub max_profit: Option<f64>,
-    pub max_loss: Option<f64>,
+    pub max_profit: Option<PayoffBound>,
+    pub max_loss: Option<PayoffBound>,
     pub profit_probability: Option<f64>,
     pub expected_value: Option<f64>,
+    pub expected_funding: f64, // Perpetual funding at the current price, part of the payoff
+    pub liquidation_prices: Vec<f64>, // Where leveraged futures get liquidated
+    pub margin: MarginRequirement, // Capital required to hold the portfolio
+    pub return_on_margin: Option<f64>, // Max profit / initial margin (None when unbounded)
+    pub current_price: f64,    // Underlying price the Greeks are evaluated at
+    pub greeks: Greeks,        // Portfolio Greeks at the current price
+    pub greeks_curve: Vec<GreeksPoint>, // Portfolio Greeks across the price range
+    pub simulation: Option<SimulationResult>, // Monte Carlo P&L distribution (None when off)
+    pub value_at_risk: Vec<VarEstimate>, // VaR / ES per method, confidence level and horizon
+}
+
+/// How a Value-at-Risk estimate was produced
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VarMethod {
+    Parametric, // Delta-normal, from the portfolio delta and a volatility
+    Historical, // Full revaluation over historical price moves
+}
+
+/// Value at Risk and Expected Shortfall at one confidence level and horizon
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VarEstimate {
+    pub method: VarMethod,
+    pub confidence: f64,         // 0.99 = 99%
+    pub horizon_days: f64,       // Holding period
+    pub value_at_risk: f64,      // Loss not exceeded at the confidence level (positive = loss)
+    pub expected_shortfall: f64, // Average loss beyond the VaR (CVaR)
 }
 
 /// Portfolio analysis engine (WASM-compatible)
@@ -17,34 +55,154 @@ pub struct PortfolioEngine;
 
 impl PortfolioEngine {
     /// Analyze portfolio and return comprehensive metrics
+    ///
+    /// Every metric is read off one price axis, so positions on several underlyings
+    /// are rejected; analyze each `PayoffEngine::group_by_underlying` group instead.
     pub fn analyze_portfolio(
         positions: &[Position],
         price_start: f64,
         price_end: f64,
         step_size: f64,
-    ) -> PortfolioMetrics {
+        settings: &CalculationSettings,
+    ) -> Result<PortfolioMetrics, String> {
+        if PayoffEngine::spans_several_underlyings(positions) {
+            return Err(
+                "Positions span several underlyings - analyze each underlying separately"
+                    .to_string(),
+            );
+        }
+
+        let context = ValuationContext::from_settings(settings);
+        let current_price = Self::resolve_current_price(settings, price_start, price_end);
+
+        // Net of fees: positions without their own take the portfolio fee schedule
+        let positions = &settings.fee_schedule.apply(positions)[..];
+
         let breakeven_points =
-            PayoffEngine::find_breakeven_points(positions, price_start, price_end, step_size);
+            PayoffEngine::find_breakeven_points(positions, price_start, price_end);
 
-        let max_profit =
-            PayoffEngine::calculate_max_profit(positions, price_start, price_end, step_size);
+        let max_profit = PayoffEngine::calculate_max_profit(positions);
 
-        let max_loss =
-            PayoffEngine::calculate_max_loss(positions, price_start, price_end, step_size);
+        let max_loss = PayoffEngine::calculate_max_loss(positions);
 
         let payoff_curve =
             PayoffEngine::generate_payoff_curve(positions, price_start, price_end, step_size);
 
-        let profit_probability = Self::calculate_profit_probability(&payoff_curve);
-        let expected_value = Self::calculate_expected_value(&payoff_curve);
+        let (profit_probability, expected_value) = Self::calculate_probability_metrics(
+            positions,
+            &payoff_curve,
+            current_price,
+            settings.price_distribution,
+            &context,
+        );
+
+        let expected_funding = PayoffEngine::calculate_expected_funding(positions, current_price);
+
+        let greeks = GreeksEngine::calculate_portfolio_greeks(positions, current_price, &context);
+        let greeks_curve = GreeksEngine::generate_greeks_curve(
+            positions,
+            price_start,
+            price_end,
+            step_size,
+            &context,
+        );
+
+        let margin = MarginEngine::calculate_margin(
+            positions,
+            current_price,
+            settings.margin_method,
+            &context,
+        );
+        let return_on_margin = match max_profit {
+            Some(PayoffBound::Bounded(profit)) => MarginEngine::return_on_margin(profit, &margin),
+            _ => None,
+        };
 
-        PortfolioMetrics {
+        let simulation = SimulationEngine::simulate_portfolio(
+            positions,
+            current_price,
+            &SimulationConfig::from_settings(settings),
+            &context,
+        );
+
+        let value_at_risk = Self::calculate_value_at_risk(
+            positions,
+            current_price,
+            &settings.var_settings,
+            &context,
+            None,
+        );
+
+        Ok(PortfolioMetrics {
             total_positions: positions.len(),
             breakeven_points,
             max_profit,
             max_loss,
             profit_probability,
             expected_value,
+            expected_funding,
+            liquidation_prices: LiquidationEngine::portfolio_liquidation_prices(positions),
+            margin,
+            return_on_margin,
+            current_price,
+            greeks,
+            greeks_curve,
+            simulation,
+            value_at_risk,
+        })
+    }
+
+    /// Current underlying price from settings, falling back to the range midpoint
+    pub fn resolve_current_price(
+        settings: &CalculationSettings,
+        price_start: f64,
+        price_end: f64,
+    ) -> f64 {
+        settings
+            .underlying_price
+            .filter(|price| *price > 0.0)
+            .unwrap_or((price_start + price_end) / 2.0)
+    }
+
+    /// Current price of an underlying (None = primary) from settings, falling back
+    /// to the range midpoint
+    pub fn resolve_underlying_price(
+        settings: &CalculationSettings,
+        underlying: Option<&str>,
+        price_start: f64,
+        price_end: f64,
+    ) -> f64 {
+        match underlying {
+            None => Self::resolve_current_price(settings, price_start, price_end),
+            Some(symbol) => settings
+                .underlying_quote(symbol)
+                .and_then(|quote| quote.price)
+                .filter(|price| *price > 0.0)
+                .unwrap_or((price_start + price_end) / 2.0),
+        }
+    }
+
+    /// Probability of profit and expected value at expiry under the chosen price distribution
+    /// (None for positions on several underlyings)
+    pub fn calculate_probability_metrics(
+        positions: &[Position],
+        payoff_curve: &[PayoffPoint],
+        current_price: f64,
+        distribution: PriceDistribution,
+        context: &ValuationContext,
+    ) -> (Option<f64>, Option<f64>) {
+        if PayoffEngine::spans_several_underlyings(positions) {
+            return (None, None);
+        }
+        match distribution {
+            PriceDistribution::Uniform => (
+                Self::calculate_profit_probability(payoff_curve),
+                Self::calculate_expected_value(payoff_curve),
+            ),
+            PriceDistribution::Lognormal => (
+                Self::calculate_lognormal_profit_probability(positions, current_price, context),
+                Self::calculate_lognormal_expected_value(positions, current_price, context),
+            ),
         }
     }
 
@@ -73,35 +231,251 @@ impl PortfolioEngine {
         Some(total_payoff / payoff_curve.len() as f64)
     }
 
-    /// Check if portfolio has unlimited profit potential
+    /// Probability that the portfolio finishes in profit at expiry, with the
+    /// underlying lognormal around the current price (None across several underlyings)
+    pub fn calculate_lognormal_profit_probability(
+        positions: &[Position],
+        current_price: f64,
+        context: &ValuationContext,
+    ) -> Option<f64> {
+        if !positions.iter().any(|pos| pos.is_active())
+            || PayoffEngine::spans_several_underlyings(positions)
+        {
+            return None;
+        }
+
+        let payoff = PayoffEngine::build_piecewise_payoff(positions);
+        let Some(distribution) = LognormalPrice::new(current_price, context) else {
+            let forward = Self::forward_price(current_price, context);
+            let in_profit = PayoffEngine::calculate_portfolio_payoff(positions, forward) > 0.0;
+            return Some(if in_profit { 1.0 } else { 0.0 });
+        };
+
+        let probability = payoff
+            .segments
+            .iter()
+            .flat_map(|segment| segment.positive_intervals(segment.start, segment.end))
+            .map(|(lower, upper)| distribution.probability_between(lower, upper))
+            .sum::<f64>();
+
+        Some(probability.clamp(0.0, 1.0))
+    }
+
+    /// Expected portfolio payoff at expiry, with the underlying lognormal around
+    /// the current price (None across several underlyings)
+    pub fn calculate_lognormal_expected_value(
+        positions: &[Position],
+        current_price: f64,
+        context: &ValuationContext,
+    ) -> Option<f64> {
+        if !positions.iter().any(|pos| pos.is_active())
+            || PayoffEngine::spans_several_underlyings(positions)
+        {
+            return None;
+        }
+
+        let payoff = PayoffEngine::build_piecewise_payoff(positions);
+        let Some(distribution) = LognormalPrice::new(current_price, context) else {
+            let forward = Self::forward_price(current_price, context);
+            return Some(PayoffEngine::calculate_portfolio_payoff(positions, forward));
+        };
+
+        let expected_value = payoff
+            .segments
+            .iter()
+            .map(|segment| {
+                segment.slope * distribution.partial_moment(segment.start, segment.end, 1.0)
+                    + segment.intercept
+                        * distribution.probability_between(segment.start, segment.end)
+                    + segment.inverse
+                        * distribution.partial_moment(segment.start, segment.end, -1.0)
+            })
+            .sum();
+
+        Some(expected_value)
+    }
+
+    /// Check if portfolio has unlimited profit potential (payoff rises without bound)
     pub fn has_unlimited_profit(positions: &[Position]) -> bool {
-        // Simple heuristic: check if any position has unlimited upside
-        positions.iter().any(|pos| match pos {
-            crate::models::Position::Spot(spot) => spot.quantity > 0.0,
-            crate::models::Position::Option(option) => {
-                matches!(option.option_type, crate::models::OptionType::Call)
-                    && option.quantity > 0.0
-            }
-            crate::models::Position::Futures(futures) => futures.quantity > 0.0,
-        })
+        matches!(
+            PayoffEngine::calculate_max_profit(positions),
+            Some(PayoffBound::Unbounded)
+        )
     }
 
-    /// Check if portfolio has unlimited loss potential
+    /// Check if portfolio has unlimited loss potential (payoff falls without bound)
     pub fn has_unlimited_loss(positions: &[Position]) -> bool {
-        // Simple heuristic: check if any position has unlimited downside
-        positions.iter().any(|pos| match pos {
-            crate::models::Position::Spot(spot) => spot.quantity > 0.0, // Long spot has unlimited downside to 0
-            crate::models::Position::Option(option) => {
-                // Short calls or long puts can have significant losses
-                (matches!(option.option_type, crate::models::OptionType::Call)
-                    && option.quantity < 0.0)
-                    || (matches!(option.option_type, crate::models::OptionType::Put)
-                        && option.quantity > 0.0)
+        matches!(
+            PayoffEngine::calculate_max_loss(positions),
+            Some(PayoffBound::Unbounded)
+        )
+    }
+
+    /// Parametric and (given enough price history) historical VaR / ES at every
+    /// configured confidence level and horizon
+    pub fn calculate_value_at_risk(
+        positions: &[Position],
+        current_price: f64,
+        settings: &VarSettings,
+        context: &ValuationContext,
+        weighting: Option<&BetaWeighting>,
+    ) -> Vec<VarEstimate> {
+        // Without a beta weighting one price cannot move several underlyings
+        let unweighted_mix =
+            weighting.is_none() && PayoffEngine::spans_several_underlyings(positions);
+        if !positions.iter().any(|pos| pos.is_active()) || current_price <= 0.0 || unweighted_mix {
+            return Vec::new();
+        }
+
+        // The price history, when given, also replaces the volatility assumption
+        let volatility =
+            Self::historical_volatility(&settings.price_history).unwrap_or(context.volatility);
+
+        let mut estimates = Vec::new();
+        for &horizon_days in &settings.horizons_days {
+            for &confidence in &settings.confidence_levels {
+                if !(0.0..1.0).contains(&confidence) || horizon_days <= 0.0 {
+                    continue;
+                }
+                estimates.push(Self::calculate_parametric_var(
+                    positions,
+                    current_price,
+                    confidence,
+                    horizon_days,
+                    volatility,
+                    context,
+                    weighting,
+                ));
+                estimates.extend(Self::calculate_historical_var(
+                    positions,
+                    current_price,
+                    confidence,
+                    horizon_days,
+                    &settings.price_history,
+                    context,
+                    weighting,
+                ));
             }
-            crate::models::Position::Futures(_futures) => true, // Futures always have unlimited risk
+        }
+        estimates
+    }
+
+    /// Delta-normal VaR / ES: the portfolio moves with its delta on a normal
+    /// return with the given annualized volatility
+    pub fn calculate_parametric_var(
+        positions: &[Position],
+        current_price: f64,
+        confidence: f64,
+        horizon_days: f64,
+        volatility: f64,
+        context: &ValuationContext,
+        weighting: Option<&BetaWeighting>,
+    ) -> VarEstimate {
+        let today = ValuationContext { days_elapsed: 0.0, ..*context };
+        let greeks = match weighting {
+            Some(weighting) => GreeksEngine::calculate_beta_weighted_greeks(
+                positions,
+                current_price,
+                weighting,
+                &today,
+            ),
+            None => GreeksEngine::calculate_portfolio_greeks(positions, current_price, &today),
+        };
+
+        let pnl_std = (greeks.delta * current_price).abs()
+            * volatility.max(0.0)
+            * (horizon_days / DAYS_PER_YEAR).sqrt();
+        let z = PricingEngine::norm_inv(confidence);
+
+        VarEstimate {
+            method: VarMethod::Parametric,
+            confidence,
+            horizon_days,
+            value_at_risk: z * pnl_std,
+            expected_shortfall: pnl_std * PricingEngine::norm_pdf(z) / (1.0 - confidence),
+        }
+    }
+
+    /// Historical VaR / ES: the portfolio is revalued at the horizon after every
+    /// overlapping horizon-day return of the closes (None with too little history)
+    pub fn calculate_historical_var(
+        positions: &[Position],
+        current_price: f64,
+        confidence: f64,
+        horizon_days: f64,
+        closes: &[f64],
+        context: &ValuationContext,
+        weighting: Option<&BetaWeighting>,
+    ) -> Option<VarEstimate> {
+        let returns = Self::horizon_returns(closes, horizon_days.round().max(1.0) as usize);
+        if returns.len() < MIN_HISTORY_SCENARIOS {
+            return None;
+        }
+
+        let today = ValuationContext { days_elapsed: 0.0, ..*context };
+        let horizon = ValuationContext { days_elapsed: horizon_days, ..*context };
+        let value = |price: f64, context: &ValuationContext| match weighting {
+            Some(weighting) => {
+                PayoffEngine::calculate_beta_weighted_value(positions, price, weighting, context)
+            }
+            None => PayoffEngine::calculate_portfolio_value(positions, price, context),
+        };
+        let current_value = value(current_price, &today);
+
+        let mut pnl: Vec<f64> = returns
+            .iter()
+            .map(|log_return| value(current_price * log_return.exp(), &horizon) - current_value)
+            .filter(|pnl| pnl.is_finite())
+            .collect();
+        if pnl.len() < MIN_HISTORY_SCENARIOS {
+            return None;
+        }
+        pnl.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let cutoff = percentile_of_sorted(&pnl, 1.0 - confidence);
+        let tail: Vec<f64> = pnl.iter().copied().filter(|pnl| *pnl <= cutoff).collect();
+        let tail_mean = tail.iter().sum::<f64>() / tail.len() as f64;
+
+        Some(VarEstimate {
+            method: VarMethod::Historical,
+            confidence,
+            horizon_days,
+            value_at_risk: -cutoff,
+            expected_shortfall: -tail_mean,
         })
     }
 
+    /// Annualized volatility of daily log returns (None with fewer than two returns)
+    pub fn historical_volatility(closes: &[f64]) -> Option<f64> {
+        let returns = Self::horizon_returns(closes, 1);
+        if returns.len() < 2 {
+            return None;
+        }
+
+        let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+        let variance =
+            returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (returns.len() - 1) as f64;
+        Some((variance * DAYS_PER_YEAR).sqrt())
+    }
+
+    // === Private helper functions ===
+
+    /// Overlapping log returns over `days` closes
+    fn horizon_returns(closes: &[f64], days: usize) -> Vec<f64> {
+        if days == 0 || closes.len() <= days || closes.iter().any(|close| *close <= 0.0) {
+            return Vec::new();
+        }
+        closes
+            .windows(days + 1)
+            .map(|window| (window[days] / window[0]).ln())
+            .collect()
+    }
+
+    /// Risk-neutral forward of the underlying at expiry
+    fn forward_price(current_price: f64, context: &ValuationContext) -> f64 {
+        current_price * (context.risk_free_rate * context.days_to_expiry / DAYS_PER_YEAR).exp()
+    }
+
     /// Get portfolio risk classification
     pub fn get_risk_level(positions: &[Position]) -> RiskLevel {
         let has_unlimited_profit = Self::has_unlimited_profit(positions);
@@ -116,6 +490,60 @@ impl PortfolioEngine {
     }
 }
 
+/// Lognormal distribution of the underlying price at expiry (risk-neutral drift)
+struct LognormalPrice {
+    mean_log: f64, // Mean of ln(price)
+    std_log: f64,  // Standard deviation of ln(price)
+}
+
+impl LognormalPrice {
+    /// None when the distribution collapses to a single price (no vol or no time)
+    fn new(current_price: f64, context: &ValuationContext) -> Option<Self> {
+        // Horizon runs from today to expiry, where the payoff is realised
+        let horizon = context.days_to_expiry.max(0.0) / DAYS_PER_YEAR;
+        let volatility = context.volatility;
+        if current_price <= 0.0 || volatility <= 0.0 || horizon <= 0.0 {
+            return None;
+        }
+
+        Some(Self {
+            mean_log: current_price.ln()
+                + (context.risk_free_rate - 0.5 * volatility * volatility) * horizon,
+            std_log: volatility * horizon.sqrt(),
+        })
+    }
+
+    /// P(price < x), with the shape shifted by `shift` standard deviations squared
+    fn cdf(&self, price: f64, shift: f64) -> f64 {
+        if price <= 0.0 {
+            0.0
+        } else if price.is_infinite() {
+            1.0
+        } else {
+            PricingEngine::norm_cdf((price.ln() - self.mean_log - shift) / self.std_log)
+        }
+    }
+
+    /// P(lower < price < upper)
+    fn probability_between(&self, lower: f64, upper: f64) -> f64 {
+        if upper <= lower {
+            return 0.0;
+        }
+        self.cdf(upper, 0.0) - self.cdf(lower, 0.0)
+    }
+
+    /// E[price^power; lower < price < upper]
+    fn partial_moment(&self, lower: f64, upper: f64, power: f64) -> f64 {
+        if upper <= lower {
+            return 0.0;
+        }
+        let variance = self.std_log * self.std_log;
+        let moment = (power * self.mean_log + 0.5 * power * power * variance).exp();
+        let shift = power * variance;
+        moment * (self.cdf(upper, shift) - self.cdf(lower, shift))
+    }
+}
+
 /// Portfolio risk classification
 #[derive(Debug, Clone, PartialEq)]
 pub enum RiskLevel {
@@ -135,21 +563,180 @@ mod tests {
         let position = Position::Spot(spot);
         let positions = vec![position];
 
-        let metrics = PortfolioEngine::analyze_portfolio(&positions, 40.0, 60.0, 5.0);
+        let settings = CalculationSettings::default();
+        let metrics =
+            PortfolioEngine::analyze_portfolio(&positions, 40.0, 60.0, 5.0, &settings).unwrap();
 
         assert_eq!(metrics.total_positions, 1);
         assert!(metrics.profit_probability.is_some());
         assert!(metrics.expected_value.is_some());
+        assert_eq!(metrics.current_price, 50.0);
+        assert_eq!(metrics.greeks.delta, 100.0);
+        assert_eq!(metrics.greeks_curve.len(), 5);
+        assert_eq!(
+            metrics.simulation.unwrap().pnl.len(),
+            settings.simulation_paths
+        );
+        // Parametric VaR at each default confidence and horizon, no history loaded
+        assert_eq!(metrics.value_at_risk.len(), 4);
+    }
+
+    #[test]
+    fn test_mixed_underlyings_are_not_valued_on_one_axis() {
+        let mut sol = Position::Spot(SpotPosition::new(10.0, 20.0, None));
+        sol.set_underlying(Some("SOLUSDT".to_string()));
+        let btc = Position::Option(OptionPosition::new(OptionType::Call, 1.0, 100.0, 5.0, None));
+        let positions = vec![sol.clone(), btc.clone()];
+        let settings = CalculationSettings { underlying_price: Some(100.0), ..Default::default() };
+        let context = ValuationContext::from_settings(&settings);
+
+        assert!(
+            PortfolioEngine::analyze_portfolio(&positions, 0.0, 200.0, 5.0, &settings).is_err()
+        );
+        assert!(PortfolioEngine::calculate_lognormal_profit_probability(
+            &positions, 100.0, &context
+        )
+        .is_none());
+        assert!(SimulationEngine::simulate_portfolio(
+            &positions,
+            100.0,
+            &SimulationConfig::from_settings(&settings),
+            &context
+        )
+        .is_none());
+
+        // An inactive leg on the other underlying does not mix the axis
+        sol.set_active(false);
+        let positions = vec![sol, btc];
+        assert!(PortfolioEngine::analyze_portfolio(&positions, 0.0, 200.0, 5.0, &settings).is_ok());
     }
 
     #[test]
     fn test_risk_level_classification() {
-        // Long spot position - high risk (unlimited downside to 0)
-        let spot = SpotPosition::new(100.0, 50.0, None);
-        let position = Position::Spot(spot);
-        let positions = vec![position];
+        // Long spot - unlimited upside, loss capped at the entry value (price can't go below 0)
+        let spot = Position::Spot(SpotPosition::new(100.0, 50.0, None));
+        assert_eq!(
+            PortfolioEngine::get_risk_level(std::slice::from_ref(&spot)),
+            RiskLevel::Medium
+        );
+
+        // Covered call - the short call caps the upside
+        let short_call = Position::Option(OptionPosition::new(
+            OptionType::Call,
+            -100.0,
+            60.0,
+            2.0,
+            None,
+        ));
+        assert_eq!(
+            PortfolioEngine::get_risk_level(&[spot, short_call.clone()]),
+            RiskLevel::Low
+        );
+
+        // Naked short call - unlimited loss
+        assert_eq!(
+            PortfolioEngine::get_risk_level(&[short_call]),
+            RiskLevel::High
+        );
+
+        // Long and short futures of equal size hedge each other out
+        let long = Position::Futures(FuturesPosition::new(1.0, 100.0, 1.0, None));
+        let short = Position::Futures(FuturesPosition::new(-1.0, 105.0, 1.0, None));
+        assert_eq!(
+            PortfolioEngine::get_risk_level(&[long, short]),
+            RiskLevel::Low
+        );
+    }
+
+    #[test]
+    fn test_lognormal_probability_ignores_chart_range() {
+        let call = OptionPosition::new(OptionType::Call, 1.0, 100.0, 5.0, None);
+        let positions = vec![Position::Option(call)];
+        let settings = CalculationSettings {
+            underlying_price: Some(100.0),
+            volatility: 0.5,
+            risk_free_rate: 0.05,
+            days_to_expiry: 73.0,
+            ..CalculationSettings::default()
+        };
+
+        let narrow =
+            PortfolioEngine::analyze_portfolio(&positions, 90.0, 110.0, 1.0, &settings).unwrap();
+        let wide =
+            PortfolioEngine::analyze_portfolio(&positions, 0.0, 500.0, 10.0, &settings).unwrap();
+        assert_eq!(narrow.profit_probability, wide.profit_probability);
+        assert_eq!(narrow.expected_value, wide.expected_value);
+
+        // Long call is profitable above K + premium: P = N(d2) struck at 105
+        let (_, d2) = PricingEngine::d1_d2(100.0, 105.0, 0.2, 0.05, 0.5);
+        let pop = narrow.profit_probability.unwrap();
+        assert!((pop - PricingEngine::norm_cdf(d2)).abs() < 1e-9);
+
+        // Undiscounted expected payoff = forward Black-Scholes value - premium
+        let bs = PricingEngine::black_scholes_price(OptionType::Call, 100.0, 100.0, 0.2, 0.05, 0.5);
+        let forward_value = bs * (0.05_f64 * 0.2).exp() - 5.0;
+        assert!((narrow.expected_value.unwrap() - forward_value).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_parametric_var_of_spot() {
+        let spot = Position::Spot(SpotPosition::new(10.0, 100.0, None));
+        let context = ValuationContext::new(30.0, 0.0, 0.5, 0.0);
+
+        let estimate = PortfolioEngine::calculate_parametric_var(
+            &[spot],
+            100.0,
+            0.99,
+            1.0,
+            0.5,
+            &context,
+            None,
+        );
+        let pnl_std = 10.0 * 100.0 * 0.5 * (1.0_f64 / 365.0).sqrt();
+        assert!((PricingEngine::norm_inv(0.99) - 2.326347874).abs() < 1e-8);
+        assert!((estimate.value_at_risk - 2.326347874 * pnl_std).abs() < 1e-6);
+        assert!(estimate.expected_shortfall > estimate.value_at_risk);
+    }
+
+    #[test]
+    fn test_historical_var_from_closes() {
+        // Daily log returns from -14.5% to +14.5% in 1% steps
+        let returns: Vec<f64> = (0..30).map(|i| (i as f64 - 14.5) / 100.0).collect();
+        let mut closes = vec![100.0];
+        for r in &returns {
+            closes.push(closes.last().unwrap() * f64::exp(*r));
+        }
+        let spot = Position::Spot(SpotPosition::new(1.0, 100.0, None));
+        let context = ValuationContext::new(30.0, 0.0, 0.5, 0.0);
+
+        let estimate = PortfolioEngine::calculate_historical_var(
+            std::slice::from_ref(&spot),
+            100.0,
+            0.95,
+            1.0,
+            &closes,
+            &context,
+            None,
+        )
+        .unwrap();
+        assert_eq!(estimate.method, VarMethod::Historical);
+
+        // 5th percentile sits between the second and third worst days
+        let loss = |r: f64| 100.0 * (1.0 - f64::exp(r));
+        assert!(estimate.value_at_risk < loss(-0.135) && estimate.value_at_risk > loss(-0.125));
+        let expected_shortfall = (loss(-0.145) + loss(-0.135)) / 2.0;
+        assert!((estimate.expected_shortfall - expected_shortfall).abs() < 1e-9);
 
-        let risk = PortfolioEngine::get_risk_level(&positions);
-        assert_eq!(risk, RiskLevel::High);
+        // Too little history for a 15-day horizon
+        assert!(PortfolioEngine::calculate_historical_var(
+            &[spot],
+            100.0,
+            0.95,
+            15.0,
+            &closes,
+            &context,
+            None
+        )
+        .is_none());
     }
 }
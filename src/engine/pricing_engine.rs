@@ -0,0 +1,1114 @@
+use super::simulation_engine::SimulationRng;
+use crate::models::{
+    BarrierPath, BarrierPosition, BarrierValuation, CalculationSettings, DigitalPosition,
+    ExerciseStyle, OptionPosition, OptionType, Position, PricingModel,
+};
+use chrono::{DateTime, Duration, Utc};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+const DAYS_PER_YEAR: f64 = 365.0;
+const MILLIS_PER_DAY: f64 = 86_400_000.0;
+
+/// Search bounds and tolerance for the implied volatility solver
+const MIN_VOLATILITY: f64 = 1e-4;
+const MAX_VOLATILITY: f64 = 10.0;
+const IV_PRICE_TOLERANCE: f64 = 1e-8;
+const IV_MAX_ITERATIONS: usize = 100;
+
+/// Floor for shifted volatilities, so large negative shifts stay priceable
+const MIN_SHIFTED_VOLATILITY: f64 = 0.01;
+
+/// Time steps used by the binomial tree for American options
+pub const BINOMIAL_STEPS: usize = 200;
+
+/// Simulated paths for daily-monitored barriers; the fixed seed reuses the same draws
+/// at every price so value curves stay smooth
+const BARRIER_PATHS: usize = 2_000;
+const BARRIER_SEED: u64 = 0x5EED;
+
+/// Simulated path sets kept before the barrier cache starts over
+const BARRIER_CACHE_ENTRIES: usize = 64;
+
+/// One simulated barrier path, relative to its starting price
+#[derive(Debug, Clone, Copy)]
+struct BarrierPathSummary {
+    growth: f64, // Terminal price / starting price
+    high: f64,   // Highest daily close, as a log return from the start
+    low: f64,    // Lowest daily close, as a log return from the start
+}
+
+/// Simulated barrier paths per (time to expiry, rate, volatility)
+#[derive(Default)]
+struct BarrierPathCache {
+    paths: HashMap<(u64, u64, u64), Rc<Vec<BarrierPathSummary>>>,
+    simulated: usize, // Path sets simulated so far
+}
+
+thread_local! {
+    // A path set serves every spot price, so chart points, Greek bumps and simulated
+    // prices at one horizon share a single simulation
+    static BARRIER_PATH_CACHE: RefCell<BarrierPathCache> = RefCell::new(BarrierPathCache::default());
+}
+
+/// Move applied to every volatility in a stress scenario
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VolatilityShift {
+    Absolute(f64), // Added to the volatility (0.05 = +5 vol points)
+    Relative(f64), // Proportional change (0.3 = 30% higher)
+}
+
+impl VolatilityShift {
+    /// Shifted volatility, floored so it stays priceable
+    pub fn apply(self, volatility: f64) -> f64 {
+        let shifted = match self {
+            VolatilityShift::Absolute(shift) => volatility + shift,
+            VolatilityShift::Relative(shift) => volatility * (1.0 + shift),
+        };
+        shifted.max(MIN_SHIFTED_VOLATILITY)
+    }
+}
+
+/// Market assumptions used to value positions before expiry
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ValuationContext {
+    pub days_to_expiry: f64,           // Calendar days from today until expiry
+    pub days_elapsed: f64,             // Days from today at which positions are valued (T+n)
+    pub volatility: f64,               // Fallback annualized IV for legs without their own
+    pub risk_free_rate: f64,           // Annualized continuously-compounded rate
+    pub reference_date: DateTime<Utc>, // "Today" - dated legs are measured from here
+    pub pricing_model: PricingModel,   // Model for option legs without their own
+    pub futures_basis: f64,            // Annualized basis: forward = spot * e^(basis * T)
+}
+
+impl ValuationContext {
+    pub fn new(
+        days_to_expiry: f64,
+        days_elapsed: f64,
+        volatility: f64,
+        risk_free_rate: f64,
+    ) -> Self {
+        Self {
+            days_to_expiry,
+            days_elapsed,
+            volatility,
+            risk_free_rate,
+            reference_date: Utc::now(),
+            pricing_model: PricingModel::BlackScholes,
+            futures_basis: 0.0,
+        }
+    }
+
+    /// Build a context from the persisted portfolio calculation settings
+    pub fn from_settings(settings: &CalculationSettings) -> Self {
+        Self {
+            pricing_model: settings.default_pricing_model,
+            futures_basis: settings.futures_basis,
+            ..Self::new(
+                settings.days_to_expiry,
+                settings.days_elapsed,
+                settings.volatility,
+                settings.risk_free_rate,
+            )
+        }
+    }
+
+    /// This context and a copy of the positions with the fallback volatility and every
+    /// leg's own volatility moved by the shift
+    pub fn shift_volatility(
+        &self,
+        positions: &[Position],
+        shift: VolatilityShift,
+    ) -> (Self, Vec<Position>) {
+        let apply = |volatility: f64| shift.apply(volatility);
+        let shifted_positions = positions
+            .iter()
+            .map(|pos| match pos {
+                Position::Option(option) => Position::Option(OptionPosition {
+                    implied_volatility: option.implied_volatility.map(apply),
+                    ..option.clone()
+                }),
+                Position::Digital(digital) => Position::Digital(DigitalPosition {
+                    implied_volatility: digital.implied_volatility.map(apply),
+                    ..digital.clone()
+                }),
+                Position::Barrier(barrier) => Position::Barrier(BarrierPosition {
+                    implied_volatility: barrier.implied_volatility.map(apply),
+                    ..barrier.clone()
+                }),
+                _ => pos.clone(),
+            })
+            .collect();
+        let context = Self { volatility: shift.apply(self.volatility), ..*self };
+        (context, shifted_positions)
+    }
+
+    /// Forward (futures) price for a spot price and time to expiry in years
+    pub fn forward_price(&self, spot: f64, time_to_expiry: f64) -> f64 {
+        spot * (self.futures_basis * time_to_expiry).exp()
+    }
+
+    /// Remaining time to expiry at the valuation point, in years
+    pub fn time_to_expiry(&self) -> f64 {
+        ((self.days_to_expiry - self.days_elapsed) / DAYS_PER_YEAR).max(0.0)
+    }
+
+    /// Calendar date at which positions are valued (today + days elapsed)
+    pub fn valuation_date(&self) -> DateTime<Utc> {
+        self.reference_date + Duration::milliseconds((self.days_elapsed * MILLIS_PER_DAY) as i64)
+    }
+
+    /// Remaining time to a leg's own expiry at the valuation point, in years
+    ///
+    /// Undated legs (None) use the portfolio-level days to expiry.
+    pub fn leg_time_to_expiry(&self, expiry: Option<DateTime<Utc>>) -> f64 {
+        match expiry {
+            Some(expiry) => years_between(self.valuation_date(), expiry),
+            None => self.time_to_expiry(),
+        }
+    }
+
+    /// Time from today to a leg's expiry, in years (used to price today's premium)
+    pub fn leg_time_from_today(&self, expiry: Option<DateTime<Utc>>) -> f64 {
+        match expiry {
+            Some(expiry) => years_between(self.reference_date, expiry),
+            None => (self.days_to_expiry / DAYS_PER_YEAR).max(0.0),
+        }
+    }
+}
+
+impl Default for ValuationContext {
+    fn default() -> Self {
+        Self::from_settings(&CalculationSettings::default())
+    }
+}
+
+/// Binomial tree price with the sensitivities read off its first nodes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BinomialValuation {
+    pub price: f64,
+    pub delta: f64,
+    pub gamma: f64,
+    pub theta: f64, // Per year
+}
+
+/// Years from one date to another, floored at zero
+fn years_between(from: DateTime<Utc>, to: DateTime<Utc>) -> f64 {
+    ((to - from).num_milliseconds() as f64 / MILLIS_PER_DAY / DAYS_PER_YEAR).max(0.0)
+}
+
+/// Option pricing models (WASM-compatible)
+pub struct PricingEngine;
+
+impl PricingEngine {
+    /// Black-Scholes price of a European option on a non-dividend-paying underlying
+    pub fn black_scholes_price(
+        option_type: OptionType,
+        spot: f64,
+        strike: f64,
+        time_to_expiry: f64,
+        risk_free_rate: f64,
+        volatility: f64,
+    ) -> f64 {
+        let discount = (-risk_free_rate * time_to_expiry).exp();
+
+        // Degenerate inputs collapse to the discounted intrinsic value
+        if time_to_expiry <= 0.0 || volatility <= 0.0 || spot <= 0.0 || strike <= 0.0 {
+            let forward_intrinsic = match option_type {
+                OptionType::Call => (spot - strike * discount).max(0.0),
+                OptionType::Put => (strike * discount - spot).max(0.0),
+            };
+            return forward_intrinsic;
+        }
+
+        let (d1, d2) = Self::d1_d2(spot, strike, time_to_expiry, risk_free_rate, volatility);
+
+        match option_type {
+            OptionType::Call => spot * Self::norm_cdf(d1) - strike * discount * Self::norm_cdf(d2),
+            OptionType::Put => strike * discount * Self::norm_cdf(-d2) - spot * Self::norm_cdf(-d1),
+        }
+    }
+
+    /// Black-Scholes price of a cash-or-nothing digital paying 1 in the money
+    pub fn digital_price(
+        option_type: OptionType,
+        spot: f64,
+        strike: f64,
+        time_to_expiry: f64,
+        risk_free_rate: f64,
+        volatility: f64,
+    ) -> f64 {
+        let discount = (-risk_free_rate * time_to_expiry).exp();
+
+        // Degenerate inputs: the payout is certain once the forward is past the strike
+        if time_to_expiry <= 0.0 || volatility <= 0.0 || spot <= 0.0 || strike <= 0.0 {
+            let in_the_money = match option_type {
+                OptionType::Call => spot > strike * discount,
+                OptionType::Put => spot < strike * discount,
+            };
+            return if in_the_money { discount } else { 0.0 };
+        }
+
+        let (_, d2) = Self::d1_d2(spot, strike, time_to_expiry, risk_free_rate, volatility);
+
+        match option_type {
+            OptionType::Call => discount * Self::norm_cdf(d2),
+            OptionType::Put => discount * Self::norm_cdf(-d2),
+        }
+    }
+
+    /// Value one unit of a barrier option leg with its valuation method
+    pub fn value_barrier(
+        barrier: &BarrierPosition,
+        spot: f64,
+        time_to_expiry: f64,
+        context: &ValuationContext,
+    ) -> f64 {
+        let volatility = barrier.implied_volatility.unwrap_or(context.volatility);
+        let rate = context.risk_free_rate;
+
+        match barrier.valuation {
+            BarrierValuation::ClosedForm => {
+                Self::barrier_price(barrier, spot, time_to_expiry, rate, volatility)
+            }
+            BarrierValuation::MonteCarlo => {
+                Self::barrier_price_monte_carlo(barrier, spot, time_to_expiry, rate, volatility)
+            }
+        }
+    }
+
+    /// Whether a barrier counts as touched with the underlying at a price
+    pub fn barrier_touched(barrier: &BarrierPosition, price: f64) -> bool {
+        barrier.path == BarrierPath::Touched || Self::beyond_barrier(barrier, price)
+    }
+
+    /// Closed-form price of a continuously monitored barrier option (Reiner-Rubinstein),
+    /// with the rebate paid at expiry
+    ///
+    /// Once touched, a knock-in is a vanilla option and a knock-out is worth the rebate.
+    pub fn barrier_price(
+        barrier: &BarrierPosition,
+        spot: f64,
+        time_to_expiry: f64,
+        risk_free_rate: f64,
+        volatility: f64,
+    ) -> f64 {
+        let discount = (-risk_free_rate * time_to_expiry).exp();
+        let rebate = barrier.rebate * discount;
+        let vanilla = Self::black_scholes_price(
+            barrier.option_type,
+            spot,
+            barrier.strike_price,
+            time_to_expiry,
+            risk_free_rate,
+            volatility,
+        );
+        let knock_in = barrier.barrier_type.is_knock_in();
+
+        // No diffusion left: the price drifts straight to the forward
+        let touched = if time_to_expiry <= 0.0 || volatility <= 0.0 || spot <= 0.0 {
+            Self::barrier_touched(barrier, spot / discount)
+        } else if Self::barrier_touched(barrier, spot) {
+            true
+        } else {
+            let (knock_out, survival) =
+                Self::knock_out_price(barrier, spot, time_to_expiry, risk_free_rate, volatility);
+            return if knock_in {
+                vanilla - knock_out + rebate * survival
+            } else {
+                knock_out + rebate * (1.0 - survival)
+            };
+        };
+
+        if touched == knock_in {
+            vanilla
+        } else {
+            rebate
+        }
+    }
+
+    /// Monte Carlo price of a barrier option monitored at each daily close
+    pub fn barrier_price_monte_carlo(
+        barrier: &BarrierPosition,
+        spot: f64,
+        time_to_expiry: f64,
+        risk_free_rate: f64,
+        volatility: f64,
+    ) -> f64 {
+        // Nothing path-dependent left to simulate
+        if time_to_expiry <= 0.0
+            || volatility <= 0.0
+            || spot <= 0.0
+            || Self::barrier_touched(barrier, spot)
+        {
+            return Self::barrier_price(barrier, spot, time_to_expiry, risk_free_rate, volatility);
+        }
+
+        let paths = Self::barrier_paths(time_to_expiry, risk_free_rate, volatility);
+        let log_barrier = (barrier.barrier_level / spot).ln();
+        let knock_in = barrier.barrier_type.is_knock_in();
+
+        let total: f64 = paths
+            .iter()
+            .map(|path| {
+                let touched = if barrier.barrier_type.is_up() {
+                    path.high >= log_barrier
+                } else {
+                    path.low <= log_barrier
+                };
+
+                let price = spot * path.growth;
+                if touched == knock_in {
+                    match barrier.option_type {
+                        OptionType::Call => (price - barrier.strike_price).max(0.0),
+                        OptionType::Put => (barrier.strike_price - price).max(0.0),
+                    }
+                } else {
+                    barrier.rebate
+                }
+            })
+            .sum();
+
+        (-risk_free_rate * time_to_expiry).exp() * total / BARRIER_PATHS as f64
+    }
+
+    /// Daily-close paths for a barrier, simulated once per horizon, rate and volatility
+    fn barrier_paths(
+        time_to_expiry: f64,
+        risk_free_rate: f64,
+        volatility: f64,
+    ) -> Rc<Vec<BarrierPathSummary>> {
+        let key = (
+            time_to_expiry.to_bits(),
+            risk_free_rate.to_bits(),
+            volatility.to_bits(),
+        );
+        if let Some(paths) =
+            BARRIER_PATH_CACHE.with(|cache| cache.borrow().paths.get(&key).cloned())
+        {
+            return paths;
+        }
+
+        let steps = ((time_to_expiry * DAYS_PER_YEAR).ceil() as usize).max(1);
+        let dt = time_to_expiry / steps as f64;
+        let step_drift = (risk_free_rate - 0.5 * volatility * volatility) * dt;
+        let step_diffusion = volatility * dt.sqrt();
+
+        let mut rng = SimulationRng::new(BARRIER_SEED);
+        let paths: Rc<Vec<BarrierPathSummary>> = Rc::new(
+            (0..BARRIER_PATHS)
+                .map(|_| {
+                    let mut log_return = 0.0;
+                    let (mut high, mut low) = (f64::NEG_INFINITY, f64::INFINITY);
+                    for _ in 0..steps {
+                        log_return += step_drift + step_diffusion * rng.next_normal();
+                        high = high.max(log_return);
+                        low = low.min(log_return);
+                    }
+                    BarrierPathSummary { growth: log_return.exp(), high, low }
+                })
+                .collect(),
+        );
+
+        BARRIER_PATH_CACHE.with(|cache| {
+            let mut cache = cache.borrow_mut();
+            if cache.paths.len() >= BARRIER_CACHE_ENTRIES {
+                cache.paths.clear();
+            }
+            cache.paths.insert(key, paths.clone());
+            cache.simulated += 1;
+        });
+        paths
+    }
+
+    /// Value one unit of an option leg with its pricing model and exercise style
+    ///
+    /// Black-76 legs are priced off the forward implied by the context's futures
+    /// basis; Black-Scholes legs off spot.
+    pub fn value_option(
+        option: &OptionPosition,
+        spot: f64,
+        time_to_expiry: f64,
+        context: &ValuationContext,
+    ) -> f64 {
+        let volatility = option.implied_volatility.unwrap_or(context.volatility);
+        let rate = context.risk_free_rate;
+        let model = option.pricing_model.unwrap_or(context.pricing_model);
+
+        // Black-76 is Black-Scholes on the forward with zero cost of carry
+        let (underlying, carry) = match model {
+            PricingModel::BlackScholes => (spot, rate),
+            PricingModel::Black76 => (context.forward_price(spot, time_to_expiry), 0.0),
+        };
+
+        match option.exercise_style {
+            ExerciseStyle::European => match model {
+                PricingModel::BlackScholes => Self::black_scholes_price(
+                    option.option_type,
+                    spot,
+                    option.strike_price,
+                    time_to_expiry,
+                    rate,
+                    volatility,
+                ),
+                PricingModel::Black76 => Self::black76_price(
+                    option.option_type,
+                    underlying,
+                    option.strike_price,
+                    time_to_expiry,
+                    rate,
+                    volatility,
+                ),
+            },
+            ExerciseStyle::American => {
+                Self::binomial_valuation(
+                    option.option_type,
+                    ExerciseStyle::American,
+                    underlying,
+                    option.strike_price,
+                    time_to_expiry,
+                    rate,
+                    carry,
+                    volatility,
+                    BINOMIAL_STEPS,
+                )
+                .price
+            }
+        }
+    }
+
+    /// Black-76 price of a European option on a forward / futures price
+    pub fn black76_price(
+        option_type: OptionType,
+        forward: f64,
+        strike: f64,
+        time_to_expiry: f64,
+        risk_free_rate: f64,
+        volatility: f64,
+    ) -> f64 {
+        // Black-76 equals Black-Scholes on the discounted forward
+        let discounted_forward = forward * (-risk_free_rate * time_to_expiry).exp();
+        Self::black_scholes_price(
+            option_type,
+            discounted_forward,
+            strike,
+            time_to_expiry,
+            risk_free_rate,
+            volatility,
+        )
+    }
+
+    /// Cox-Ross-Rubinstein binomial valuation, with early exercise for American options
+    ///
+    /// `cost_of_carry` is the underlying's drift: the risk-free rate for spot,
+    /// zero for a futures price.
+    #[allow(clippy::too_many_arguments)]
+    pub fn binomial_valuation(
+        option_type: OptionType,
+        exercise_style: ExerciseStyle,
+        spot: f64,
+        strike: f64,
+        time_to_expiry: f64,
+        risk_free_rate: f64,
+        cost_of_carry: f64,
+        volatility: f64,
+        steps: usize,
+    ) -> BinomialValuation {
+        let intrinsic = |price: f64| match option_type {
+            OptionType::Call => (price - strike).max(0.0),
+            OptionType::Put => (strike - price).max(0.0),
+        };
+
+        // Degenerate trees collapse to immediate exercise / discounted intrinsic
+        if time_to_expiry <= 0.0 || volatility <= 0.0 || spot <= 0.0 || steps < 2 {
+            let carried_spot = spot * ((cost_of_carry - risk_free_rate) * time_to_expiry).exp();
+            let european = Self::black_scholes_price(
+                option_type,
+                carried_spot,
+                strike,
+                time_to_expiry,
+                risk_free_rate,
+                volatility,
+            );
+            let price = match exercise_style {
+                ExerciseStyle::American => intrinsic(spot).max(european),
+                ExerciseStyle::European => european,
+            };
+            return BinomialValuation { price, delta: 0.0, gamma: 0.0, theta: 0.0 };
+        }
+
+        let dt = time_to_expiry / steps as f64;
+        let up = (volatility * dt.sqrt()).exp();
+        let down = 1.0 / up;
+        let discount = (-risk_free_rate * dt).exp();
+        let up_probability = ((cost_of_carry * dt).exp() - down) / (up - down);
+        let node_price =
+            |step: usize, ups: usize| spot * up.powi(ups as i32) * down.powi((step - ups) as i32);
+
+        // Payoffs at expiry, indexed by number of up moves
+        let mut values: Vec<f64> = (0..=steps)
+            .map(|ups| intrinsic(node_price(steps, ups)))
+            .collect();
+        let mut second_step = [0.0; 3];
+        let mut first_step = [0.0; 2];
+
+        for step in (0..steps).rev() {
+            for ups in 0..=step {
+                let continuation = discount
+                    * (up_probability * values[ups + 1] + (1.0 - up_probability) * values[ups]);
+                values[ups] = match exercise_style {
+                    ExerciseStyle::American => continuation.max(intrinsic(node_price(step, ups))),
+                    ExerciseStyle::European => continuation,
+                };
+            }
+
+            match step {
+                2 => second_step.copy_from_slice(&values[..3]),
+                1 => first_step.copy_from_slice(&values[..2]),
+                _ => {}
+            }
+        }
+
+        let (up_price, down_price) = (node_price(1, 1), node_price(1, 0));
+        let (uu_price, dd_price) = (node_price(2, 2), node_price(2, 0));
+
+        let delta = (first_step[1] - first_step[0]) / (up_price - down_price);
+        let upper_delta = (second_step[2] - second_step[1]) / (uu_price - spot);
+        let lower_delta = (second_step[1] - second_step[0]) / (spot - dd_price);
+        let gamma = (upper_delta - lower_delta) / (0.5 * (uu_price - dd_price));
+        // The middle node two steps ahead sits at today's spot
+        let theta = (second_step[1] - values[0]) / (2.0 * dt);
+
+        BinomialValuation { price: values[0], delta, gamma, theta }
+    }
+
+    /// Implied volatility that reprices an option to `option_price`
+    ///
+    /// Newton-Raphson on vega, falling back to bisection when a step leaves the
+    /// bracket. Returns None when the price is outside the no-arbitrage bounds.
+    pub fn implied_volatility(
+        option_type: OptionType,
+        option_price: f64,
+        spot: f64,
+        strike: f64,
+        time_to_expiry: f64,
+        risk_free_rate: f64,
+    ) -> Option<f64> {
+        if time_to_expiry <= 0.0 || spot <= 0.0 || strike <= 0.0 || option_price <= 0.0 {
+            return None;
+        }
+
+        let price_at = |volatility: f64| {
+            Self::black_scholes_price(
+                option_type,
+                spot,
+                strike,
+                time_to_expiry,
+                risk_free_rate,
+                volatility,
+            )
+        };
+
+        let mut low = MIN_VOLATILITY;
+        let mut high = MAX_VOLATILITY;
+        if option_price < price_at(low) - IV_PRICE_TOLERANCE
+            || option_price > price_at(high) + IV_PRICE_TOLERANCE
+        {
+            return None;
+        }
+
+        // Brenner-Subrahmanyam approximation as the starting guess
+        let mut volatility = ((2.0 * std::f64::consts::PI / time_to_expiry).sqrt() * option_price
+            / spot)
+            .clamp(low, high);
+
+        for _ in 0..IV_MAX_ITERATIONS {
+            let difference = price_at(volatility) - option_price;
+            if difference.abs() < IV_PRICE_TOLERANCE {
+                return Some(volatility);
+            }
+
+            // Price is increasing in volatility, so the sign keeps the bracket valid
+            if difference > 0.0 {
+                high = volatility;
+            } else {
+                low = volatility;
+            }
+
+            let (d1, _) = Self::d1_d2(spot, strike, time_to_expiry, risk_free_rate, volatility);
+            let vega = spot * Self::norm_pdf(d1) * time_to_expiry.sqrt();
+            let newton = volatility - difference / vega;
+
+            volatility = if vega > 0.0 && newton > low && newton < high {
+                newton
+            } else {
+                0.5 * (low + high)
+            };
+        }
+
+        Some(volatility)
+    }
+
+    /// Implied volatility of an option position's entered premium at today's price
+    pub fn implied_volatility_from_premium(
+        option: &OptionPosition,
+        underlying_price: f64,
+        context: &ValuationContext,
+    ) -> Option<f64> {
+        Self::implied_volatility(
+            option.option_type,
+            option.premium,
+            underlying_price,
+            option.strike_price,
+            context.leg_time_from_today(option.expiry),
+            context.risk_free_rate,
+        )
+    }
+
+    /// Black-Scholes d1 and d2 terms
+    pub fn d1_d2(
+        spot: f64,
+        strike: f64,
+        time_to_expiry: f64,
+        risk_free_rate: f64,
+        volatility: f64,
+    ) -> (f64, f64) {
+        let vol_sqrt_t = volatility * time_to_expiry.sqrt();
+        let d1 = ((spot / strike).ln()
+            + (risk_free_rate + 0.5 * volatility * volatility) * time_to_expiry)
+            / vol_sqrt_t;
+        (d1, d1 - vol_sqrt_t)
+    }
+
+    /// Standard normal cumulative distribution function
+    pub fn norm_cdf(x: f64) -> f64 {
+        0.5 * Self::erfc(-x / std::f64::consts::SQRT_2)
+    }
+
+    /// Standard normal probability density function
+    pub fn norm_pdf(x: f64) -> f64 {
+        (-0.5 * x * x).exp() / (2.0 * std::f64::consts::PI).sqrt()
+    }
+
+    /// Inverse standard normal CDF (Acklam's rational approximation, ~1e-9 relative error)
+    pub fn norm_inv(p: f64) -> f64 {
+        if p <= 0.0 {
+            return f64::NEG_INFINITY;
+        }
+        if p >= 1.0 {
+            return f64::INFINITY;
+        }
+
+        const A: [f64; 6] = [
+            -3.969683028665376e1,
+            2.209460984245205e2,
+            -2.759285104469687e2,
+            1.38357751867269e2,
+            -3.066479806614716e1,
+            2.506628277459239,
+        ];
+        const B: [f64; 5] = [
+            -5.447609879822406e1,
+            1.615858368580409e2,
+            -1.556989798598866e2,
+            6.680131188771972e1,
+            -1.328068155288572e1,
+        ];
+        const C: [f64; 6] = [
+            -7.784894002430293e-3,
+            -3.223964580411365e-1,
+            -2.400758277161838,
+            -2.549732539343734,
+            4.374664141464968,
+            2.938163982698783,
+        ];
+        const D: [f64; 4] = [
+            7.784695709041462e-3,
+            3.224671290700398e-1,
+            2.445134137142996,
+            3.754408661907416,
+        ];
+        const P_LOW: f64 = 0.02425;
+
+        let tail = |q: f64| {
+            (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+                / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+        };
+        if p < P_LOW {
+            tail((-2.0 * p.ln()).sqrt())
+        } else if p > 1.0 - P_LOW {
+            -tail((-2.0 * (1.0 - p).ln()).sqrt())
+        } else {
+            let q = p - 0.5;
+            let r = q * q;
+            (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+                / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+        }
+    }
+
+    // === Private helper functions ===
+
+    /// Whether a price is at or past the barrier
+    fn beyond_barrier(barrier: &BarrierPosition, price: f64) -> bool {
+        if barrier.barrier_type.is_up() {
+            price >= barrier.barrier_level
+        } else {
+            price <= barrier.barrier_level
+        }
+    }
+
+    /// Knock-out price without rebate and the probability of never touching the
+    /// barrier, for a spot on the live side of it (Haug's A-D terms)
+    fn knock_out_price(
+        barrier: &BarrierPosition,
+        spot: f64,
+        time_to_expiry: f64,
+        risk_free_rate: f64,
+        volatility: f64,
+    ) -> (f64, f64) {
+        let (strike, level) = (barrier.strike_price, barrier.barrier_level);
+        let phi = match barrier.option_type {
+            OptionType::Call => 1.0,
+            OptionType::Put => -1.0,
+        };
+        let eta = if barrier.barrier_type.is_up() {
+            -1.0
+        } else {
+            1.0
+        };
+
+        let vol_sqrt_t = volatility * time_to_expiry.sqrt();
+        let mu = (risk_free_rate - 0.5 * volatility * volatility) / (volatility * volatility);
+        let discount = (-risk_free_rate * time_to_expiry).exp();
+        let ratio = level / spot;
+
+        let x1 = (spot / strike).ln() / vol_sqrt_t + (1.0 + mu) * vol_sqrt_t;
+        let x2 = (spot / level).ln() / vol_sqrt_t + (1.0 + mu) * vol_sqrt_t;
+        let y1 = (level * level / (spot * strike)).ln() / vol_sqrt_t + (1.0 + mu) * vol_sqrt_t;
+        let y2 = ratio.ln() / vol_sqrt_t + (1.0 + mu) * vol_sqrt_t;
+
+        let n = Self::norm_cdf;
+        let a = phi * spot * n(phi * x1) - phi * strike * discount * n(phi * (x1 - vol_sqrt_t));
+        let b = phi * spot * n(phi * x2) - phi * strike * discount * n(phi * (x2 - vol_sqrt_t));
+        let c = phi * spot * ratio.powf(2.0 * (mu + 1.0)) * n(eta * y1)
+            - phi * strike * discount * ratio.powf(2.0 * mu) * n(eta * (y1 - vol_sqrt_t));
+        let d = phi * spot * ratio.powf(2.0 * (mu + 1.0)) * n(eta * y2)
+            - phi * strike * discount * ratio.powf(2.0 * mu) * n(eta * (y2 - vol_sqrt_t));
+
+        let strike_above = strike > level;
+        let knock_out = match (barrier.option_type, barrier.barrier_type.is_up()) {
+            (OptionType::Call, false) if strike_above => a - c,
+            (OptionType::Call, false) => b - d,
+            (OptionType::Call, true) if strike_above => 0.0,
+            (OptionType::Call, true) => a - b + c - d,
+            (OptionType::Put, false) if strike_above => a - b + c - d,
+            (OptionType::Put, false) => 0.0,
+            (OptionType::Put, true) if strike_above => b - d,
+            (OptionType::Put, true) => a - c,
+        };
+        let survival =
+            n(eta * (x2 - vol_sqrt_t)) - ratio.powf(2.0 * mu) * n(eta * (y2 - vol_sqrt_t));
+
+        (knock_out.max(0.0), survival.clamp(0.0, 1.0))
+    }
+
+    /// Complementary error function (Chebyshev fit, fractional error < 1.2e-7)
+    fn erfc(x: f64) -> f64 {
+        let z = x.abs();
+        let t = 1.0 / (1.0 + 0.5 * z);
+        let poly = -z * z - 1.26551223
+            + t * (1.00002368
+                + t * (0.37409196
+                    + t * (0.09678418
+                        + t * (-0.18628806
+                            + t * (0.27886807
+                                + t * (-1.13520398
+                                    + t * (1.48851587 + t * (-0.82215223 + t * 0.17087277))))))));
+        let result = t * poly.exp();
+
+        if x >= 0.0 {
+            result
+        } else {
+            2.0 - result
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::BarrierType;
+
+    #[test]
+    fn test_norm_cdf() {
+        assert!((PricingEngine::norm_cdf(0.0) - 0.5).abs() < 1e-7);
+        assert!((PricingEngine::norm_cdf(1.96) - 0.975002).abs() < 1e-6);
+        assert!((PricingEngine::norm_cdf(-1.0) - 0.158655).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_black_scholes_reference_values() {
+        // Hull example: S=42, K=40, r=10%, sigma=20%, T=0.5
+        let call = PricingEngine::black_scholes_price(OptionType::Call, 42.0, 40.0, 0.5, 0.1, 0.2);
+        let put = PricingEngine::black_scholes_price(OptionType::Put, 42.0, 40.0, 0.5, 0.1, 0.2);
+
+        assert!((call - 4.76).abs() < 0.01);
+        assert!((put - 0.81).abs() < 0.01);
+
+        // Put-call parity: C - P = S - K * e^(-rT)
+        let parity = 42.0 - 40.0 * (-0.1_f64 * 0.5).exp();
+        assert!((call - put - parity).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_valuation_context_time_to_expiry() {
+        let context = ValuationContext::new(30.0, 10.0, 0.5, 0.05);
+        assert!((context.time_to_expiry() - 20.0 / 365.0).abs() < 1e-12);
+
+        let expired = ValuationContext::new(30.0, 45.0, 0.5, 0.05);
+        assert_eq!(expired.time_to_expiry(), 0.0);
+
+        // Dated legs run off their own expiry from the valuation date
+        let today = DateTime::parse_from_rfc3339("2025-01-01T08:00:00Z")
+            .unwrap()
+            .to_utc();
+        let dated = ValuationContext { reference_date: today, ..context };
+        let near = Some(today + Duration::days(7));
+        let far = Some(today + Duration::days(40));
+        assert_eq!(dated.leg_time_to_expiry(near), 0.0);
+        assert!((dated.leg_time_to_expiry(far) - 30.0 / 365.0).abs() < 1e-12);
+        assert!((dated.leg_time_from_today(far) - 40.0 / 365.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_implied_volatility_round_trip() {
+        for (option_type, strike, volatility) in [
+            (OptionType::Call, 100.0, 0.25),
+            (OptionType::Put, 80.0, 0.9),
+            (OptionType::Call, 150.0, 1.5),
+        ] {
+            let price = PricingEngine::black_scholes_price(
+                option_type,
+                100.0,
+                strike,
+                0.25,
+                0.03,
+                volatility,
+            );
+            let solved =
+                PricingEngine::implied_volatility(option_type, price, 100.0, strike, 0.25, 0.03)
+                    .unwrap();
+            assert!((solved - volatility).abs() < 1e-6);
+        }
+
+        // Below intrinsic value there is no volatility that fits
+        assert!(
+            PricingEngine::implied_volatility(OptionType::Call, 5.0, 120.0, 100.0, 0.25, 0.0)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_binomial_american_early_exercise() {
+        let (s, k, t, r, v) = (80.0, 100.0, 1.0, 0.08, 0.3);
+        let european = PricingEngine::black_scholes_price(OptionType::Put, s, k, t, r, v);
+        let tree = |style| {
+            PricingEngine::binomial_valuation(
+                OptionType::Put,
+                style,
+                s,
+                k,
+                t,
+                r,
+                r,
+                v,
+                BINOMIAL_STEPS,
+            )
+        };
+
+        // European tree converges to Black-Scholes
+        assert!((tree(ExerciseStyle::European).price - european).abs() < 0.02);
+
+        // Deep ITM American put is worth at least intrinsic and more than the European
+        let american = tree(ExerciseStyle::American);
+        assert!(american.price >= k - s);
+        assert!(american.price > european + 0.5);
+        assert!(american.delta < -0.8 && american.gamma >= 0.0);
+
+        // Without dividends early exercise of a call is never optimal
+        let call = |style| {
+            PricingEngine::binomial_valuation(
+                OptionType::Call,
+                style,
+                s,
+                k,
+                t,
+                r,
+                r,
+                v,
+                BINOMIAL_STEPS,
+            )
+            .price
+        };
+        assert!((call(ExerciseStyle::American) - call(ExerciseStyle::European)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_black76_on_forward() {
+        let context = ValuationContext {
+            futures_basis: 0.10,
+            pricing_model: PricingModel::Black76,
+            ..ValuationContext::new(73.0, 0.0, 0.6, 0.04)
+        };
+        let t = context.time_to_expiry();
+        let forward = context.forward_price(100.0, t);
+
+        // Put-call parity on the forward: C - P = e^(-rT) (F - K)
+        let call = PricingEngine::black76_price(OptionType::Call, forward, 105.0, t, 0.04, 0.6);
+        let put = PricingEngine::black76_price(OptionType::Put, forward, 105.0, t, 0.04, 0.6);
+        assert!((call - put - (-0.04 * t).exp() * (forward - 105.0)).abs() < 1e-9);
+
+        // A leg on the portfolio default model picks up the basis; with basis = r it is Black-Scholes
+        let option = OptionPosition::new(OptionType::Call, 1.0, 105.0, 0.0, None);
+        assert!((PricingEngine::value_option(&option, 100.0, t, &context) - call).abs() < 1e-9);
+
+        let carry_equals_rate = ValuationContext { futures_basis: 0.04, ..context };
+        let black_scholes =
+            PricingEngine::black_scholes_price(OptionType::Call, 100.0, 105.0, t, 0.04, 0.6);
+        let black76 = PricingEngine::value_option(&option, 100.0, t, &carry_equals_rate);
+        assert!((black76 - black_scholes).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_digital_price() {
+        let (t, r, vol) = (0.5, 0.03, 0.4);
+        let call = PricingEngine::digital_price(OptionType::Call, 100.0, 110.0, t, r, vol);
+        let put = PricingEngine::digital_price(OptionType::Put, 100.0, 110.0, t, r, vol);
+
+        // One of the two always pays: call + put = e^(-rT)
+        assert!((call + put - (-r * t).exp()).abs() < 1e-12);
+
+        // A digital call is the limit of a tight call spread: -dC/dK
+        let h = 0.05;
+        let spread =
+            (PricingEngine::black_scholes_price(OptionType::Call, 100.0, 110.0 - h, t, r, vol)
+                - PricingEngine::black_scholes_price(
+                    OptionType::Call,
+                    100.0,
+                    110.0 + h,
+                    t,
+                    r,
+                    vol,
+                ))
+                / (2.0 * h);
+        assert!((call - spread).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_barrier_in_out_parity() {
+        let (spot, t, r, vol) = (100.0, 0.5, 0.05, 0.3);
+        let cases = [
+            (OptionType::Call, BarrierType::DownAndOut, 100.0, 90.0),
+            (OptionType::Call, BarrierType::DownAndOut, 85.0, 90.0),
+            (OptionType::Call, BarrierType::UpAndOut, 100.0, 120.0),
+            (OptionType::Put, BarrierType::DownAndOut, 100.0, 90.0),
+            (OptionType::Put, BarrierType::UpAndOut, 100.0, 120.0),
+            (OptionType::Put, BarrierType::UpAndOut, 115.0, 110.0),
+        ];
+
+        for (option_type, knock_out_type, strike, level) in cases {
+            let knock_in_type = match knock_out_type {
+                BarrierType::UpAndOut => BarrierType::UpAndIn,
+                _ => BarrierType::DownAndIn,
+            };
+            let leg = |barrier_type| BarrierPosition {
+                rebate: 2.0,
+                ..BarrierPosition::new(option_type, barrier_type, 1.0, strike, level, 0.0, None)
+            };
+            let knock_out = PricingEngine::barrier_price(&leg(knock_out_type), spot, t, r, vol);
+            let knock_in = PricingEngine::barrier_price(&leg(knock_in_type), spot, t, r, vol);
+            let vanilla = PricingEngine::black_scholes_price(option_type, spot, strike, t, r, vol);
+
+            // One of the pair is alive at expiry, the other pays the rebate
+            assert!((knock_out + knock_in - vanilla - 2.0 * (-r * t).exp()).abs() < 1e-9);
+            assert!(knock_out >= 0.0 && knock_in >= 0.0);
+        }
+
+        // A touched knock-out is worth the discounted rebate
+        let touched = BarrierPosition {
+            path: BarrierPath::Touched,
+            rebate: 2.0,
+            ..BarrierPosition::new(
+                OptionType::Call,
+                BarrierType::UpAndOut,
+                1.0,
+                100.0,
+                120.0,
+                0.0,
+                None,
+            )
+        };
+        let value = PricingEngine::barrier_price(&touched, spot, t, r, vol);
+        assert!((value - 2.0 * (-r * t).exp()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_barrier_monte_carlo_matches_closed_form() {
+        let (spot, t, r, vol) = (100.0, 0.25, 0.05, 0.3);
+        let barrier = BarrierPosition::new(
+            OptionType::Call,
+            BarrierType::DownAndOut,
+            1.0,
+            100.0,
+            90.0,
+            0.0,
+            None,
+        );
+        let simulated = PricingEngine::barrier_price_monte_carlo(&barrier, spot, t, r, vol);
+
+        // Daily monitoring equals a continuous barrier shifted away from spot
+        // (Broadie-Glasserman-Kou)
+        let dt = t / (t * DAYS_PER_YEAR).ceil();
+        let shifted = BarrierPosition {
+            barrier_level: 90.0 * (-0.5826 * vol * dt.sqrt()).exp(),
+            ..barrier.clone()
+        };
+        let closed_form = PricingEngine::barrier_price(&shifted, spot, t, r, vol);
+        assert!((simulated - closed_form).abs() < 0.4);
+
+        // The same seed reproduces the same paths
+        assert_eq!(
+            simulated,
+            PricingEngine::barrier_price_monte_carlo(&barrier, spot, t, r, vol)
+        );
+    }
+
+    #[test]
+    fn test_barrier_monte_carlo_simulates_once_per_horizon() {
+        let (t, r, vol) = (0.5, 0.03, 0.45);
+        let barrier = BarrierPosition::new(
+            OptionType::Put,
+            BarrierType::UpAndOut,
+            1.0,
+            100.0,
+            120.0,
+            1.0,
+            None,
+        );
+        let simulated = || BARRIER_PATH_CACHE.with(|cache| cache.borrow().simulated);
+        let before = simulated();
+
+        // A full chart of prices reuses one path set instead of 2,000 fresh paths each
+        let prices: Vec<f64> = (0..200)
+            .map(|step| 60.0 + step as f64 * 0.3)
+            .map(|spot| PricingEngine::barrier_price_monte_carlo(&barrier, spot, t, r, vol))
+            .collect();
+        assert_eq!(simulated() - before, 1);
+        assert!(prices.iter().all(|price| price.is_finite() && *price >= 0.0));
+
+        // Another volatility is another horizon of paths
+        PricingEngine::barrier_price_monte_carlo(&barrier, 100.0, t, r, 0.5);
+        assert_eq!(simulated() - before, 2);
+    }
+}
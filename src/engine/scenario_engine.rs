@@ -0,0 +1,183 @@
+use super::payoff_engine::{BetaWeighting, PayoffEngine};
+use super::pricing_engine::{ValuationContext, VolatilityShift};
+use crate::models::{Position, ScenarioGrid};
+
+/// Portfolio P&L in one scenario of the grid
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScenarioCell {
+    pub price_move: f64,       // Relative underlying move (-0.1 = 10% drop)
+    pub volatility_shift: f64, // Absolute IV shift (0.05 = +5 vol points)
+    pub days_forward: f64,     // Days from today
+    pub underlying_price: f64, // Underlying price in the scenario
+    pub pnl: f64,              // Change in portfolio value from today's mark
+}
+
+/// Scenario grid results, laid out day by day, then volatility shift, then price move
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScenarioReport {
+    pub base_price: f64,             // Underlying price the moves are applied to
+    pub grid: ScenarioGrid,          // Axes the cells were evaluated on
+    pub cells: Vec<ScenarioCell>,    // One cell per grid combination
+    pub worst: Option<ScenarioCell>, // Scenario with the lowest P&L
+}
+
+impl ScenarioReport {
+    /// Cell at the given day, volatility shift and price move indices
+    pub fn cell(&self, day: usize, volatility: usize, price: usize) -> Option<&ScenarioCell> {
+        let prices = self.grid.price_moves.len();
+        let volatilities = self.grid.volatility_shifts.len();
+        if day >= self.grid.days_forward.len() || volatility >= volatilities || price >= prices {
+            return None;
+        }
+        self.cells
+            .get((day * volatilities + volatility) * prices + price)
+    }
+
+    /// Largest absolute P&L in the grid (scales the heat map)
+    pub fn max_abs_pnl(&self) -> f64 {
+        self.cells
+            .iter()
+            .map(|cell| cell.pnl.abs())
+            .fold(0.0, f64::max)
+    }
+}
+
+/// Scenario analysis engine (WASM-compatible)
+pub struct ScenarioEngine;
+
+impl ScenarioEngine {
+    /// Revalue the active positions at every price move, volatility shift and day of
+    /// the grid, relative to their value today at the current price
+    ///
+    /// With a beta weighting every underlying moves by its beta times the price move.
+    pub fn run(
+        positions: &[Position],
+        underlying_price: f64,
+        grid: &ScenarioGrid,
+        context: &ValuationContext,
+        weighting: Option<&BetaWeighting>,
+    ) -> ScenarioReport {
+        let today = ValuationContext { days_elapsed: 0.0, ..*context };
+        let current_value = Self::portfolio_value(positions, underlying_price, &today, weighting);
+
+        let mut cells = Vec::new();
+        for &days_forward in &grid.days_forward {
+            for &volatility_shift in &grid.volatility_shifts {
+                let (shifted_context, shifted_positions) =
+                    today.shift_volatility(positions, VolatilityShift::Absolute(volatility_shift));
+                let shifted_context =
+                    ValuationContext { days_elapsed: days_forward.max(0.0), ..shifted_context };
+
+                for &price_move in &grid.price_moves {
+                    let price = underlying_price * (1.0 + price_move);
+                    let value = Self::portfolio_value(
+                        &shifted_positions,
+                        price,
+                        &shifted_context,
+                        weighting,
+                    );
+                    cells.push(ScenarioCell {
+                        price_move,
+                        volatility_shift,
+                        days_forward,
+                        underlying_price: price,
+                        pnl: value - current_value,
+                    });
+                }
+            }
+        }
+
+        let worst = cells
+            .iter()
+            .filter(|cell| cell.pnl.is_finite())
+            .min_by(|a, b| a.pnl.partial_cmp(&b.pnl).unwrap())
+            .copied();
+
+        ScenarioReport {
+            base_price: underlying_price,
+            grid: grid.clone(),
+            cells,
+            worst,
+        }
+    }
+
+    // === Private helper functions ===
+
+    /// Portfolio value at a price, beta-weighted across underlyings when given a weighting
+    fn portfolio_value(
+        positions: &[Position],
+        underlying_price: f64,
+        context: &ValuationContext,
+        weighting: Option<&BetaWeighting>,
+    ) -> f64 {
+        match weighting {
+            Some(weighting) => PayoffEngine::calculate_beta_weighted_value(
+                positions,
+                underlying_price,
+                weighting,
+                context,
+            ),
+            None => PayoffEngine::calculate_portfolio_value(positions, underlying_price, context),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::*;
+
+    #[test]
+    fn test_scenario_grid_layout_and_worst_cell() {
+        let call = OptionPosition::new(OptionType::Call, 1.0, 100.0, 5.0, None);
+        let positions = vec![Position::Option(call)];
+        let grid = ScenarioGrid {
+            price_moves: vec![-0.1, 0.0, 0.1],
+            volatility_shifts: vec![-0.1, 0.0, 0.1],
+            days_forward: vec![0.0, 7.0],
+        };
+        let context = ValuationContext::new(30.0, 0.0, 0.5, 0.05);
+
+        let report = ScenarioEngine::run(&positions, 100.0, &grid, &context, None);
+        assert_eq!(report.cells.len(), 18);
+
+        // No move, no shift, today: nothing changes
+        let base = report.cell(0, 1, 1).unwrap();
+        assert_eq!(
+            (base.price_move, base.volatility_shift, base.days_forward),
+            (0.0, 0.0, 0.0)
+        );
+        assert!(base.pnl.abs() < 1e-9);
+
+        // A long call loses most on a drop with lower vol, a week later
+        let worst = report.worst.unwrap();
+        assert_eq!(
+            (worst.price_move, worst.volatility_shift, worst.days_forward),
+            (-0.1, -0.1, 7.0)
+        );
+        assert!(report
+            .cell(1, 0, 0)
+            .is_some_and(|cell| cell.pnl == worst.pnl));
+        assert!(report.cell(2, 0, 0).is_none());
+    }
+
+    #[test]
+    fn test_volatility_shift_applies_to_own_iv() {
+        // A leg with its own IV moves like one on the portfolio volatility
+        let fallback = OptionPosition::new(OptionType::Put, 1.0, 100.0, 5.0, None);
+        let own = OptionPosition { implied_volatility: Some(0.5), ..fallback.clone() };
+        let grid = ScenarioGrid {
+            price_moves: vec![0.0],
+            volatility_shifts: vec![0.1],
+            days_forward: vec![0.0],
+        };
+        let context = ValuationContext::new(30.0, 0.0, 0.5, 0.05);
+
+        let shifted_fallback =
+            ScenarioEngine::run(&[Position::Option(fallback)], 100.0, &grid, &context, None);
+        let shifted_own =
+            ScenarioEngine::run(&[Position::Option(own)], 100.0, &grid, &context, None);
+        assert!(shifted_own.cells[0].pnl > 0.0);
+        assert!((shifted_own.cells[0].pnl - shifted_fallback.cells[0].pnl).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,332 @@
+use super::payoff_engine::PayoffEngine;
+use super::pricing_engine::ValuationContext;
+use crate::models::{CalculationSettings, Position};
+
+const DAYS_PER_YEAR: f64 = 365.0;
+
+/// VaR / CVaR confidence when no VaR confidence level is configured
+const DEFAULT_CONFIDENCE: f64 = 0.95;
+
+/// Percentiles reported with every simulation (as fractions)
+const REPORTED_PERCENTILES: [f64; 7] = [0.01, 0.05, 0.25, 0.50, 0.75, 0.95, 0.99];
+
+/// Monte Carlo simulation parameters
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimulationConfig {
+    pub num_paths: usize,     // Number of simulated price paths
+    pub num_steps: usize,     // Time steps per path
+    pub horizon_days: f64,    // Days from today at which the portfolio is valued
+    pub drift: f64,           // Annualized drift of the underlying
+    pub volatility: f64,      // Annualized diffusion volatility
+    pub jump_intensity: f64,  // Expected jumps per year (0 = pure GBM)
+    pub jump_mean: f64,       // Mean of the log jump size
+    pub jump_volatility: f64, // Standard deviation of the log jump size
+    pub confidence: f64,      // VaR / CVaR confidence level (0.95 = 95%)
+    pub seed: u64,            // RNG seed, same seed gives the same paths
+}
+
+impl SimulationConfig {
+    /// Build a config from the persisted portfolio calculation settings
+    ///
+    /// VaR / CVaR use the strictest configured VaR confidence level, so the simulated
+    /// tail is read at the same level as the desk's limits.
+    pub fn from_settings(settings: &CalculationSettings) -> Self {
+        let strictest = settings
+            .var_settings
+            .confidence_levels
+            .iter()
+            .copied()
+            .filter(|confidence| (0.0..1.0).contains(confidence))
+            .fold(f64::NAN, f64::max);
+        let confidence = if strictest.is_nan() {
+            DEFAULT_CONFIDENCE
+        } else {
+            strictest
+        };
+
+        Self {
+            num_paths: settings.simulation_paths,
+            num_steps: 50,
+            horizon_days: settings
+                .simulation_horizon_days
+                .unwrap_or(settings.days_to_expiry),
+            drift: settings.risk_free_rate,
+            volatility: settings.volatility,
+            jump_intensity: settings.jump_intensity,
+            jump_mean: settings.jump_mean,
+            jump_volatility: settings.jump_volatility,
+            confidence,
+            seed: settings.simulation_seed,
+        }
+    }
+}
+
+/// Simulated P&L distribution at the horizon
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulationResult {
+    pub pnl: Vec<f64>,                  // Simulated portfolio P&L, sorted ascending
+    pub mean: f64,                      // Average P&L
+    pub std_dev: f64,                   // Standard deviation of P&L
+    pub percentiles: Vec<(f64, f64)>,   // (percentile, P&L) pairs
+    pub confidence: f64,                // Confidence level used for VaR / CVaR
+    pub value_at_risk: f64,             // Loss not exceeded with `confidence` probability
+    pub conditional_value_at_risk: f64, // Average loss beyond VaR (expected shortfall)
+}
+
+impl SimulationResult {
+    /// P&L at a percentile (0.05 = 5th percentile), linearly interpolated
+    pub fn percentile(&self, fraction: f64) -> f64 {
+        percentile_of_sorted(&self.pnl, fraction)
+    }
+}
+
+/// Monte Carlo simulation engine (WASM-compatible, single-threaded)
+pub struct SimulationEngine;
+
+impl SimulationEngine {
+    /// Simulate the underlying and report the portfolio's change in value from
+    /// today's mark at the current price to its value at the horizon
+    ///
+    /// One simulated price drives every leg, so positions on several underlyings
+    /// give None.
+    pub fn simulate_portfolio(
+        positions: &[Position],
+        current_price: f64,
+        config: &SimulationConfig,
+        context: &ValuationContext,
+    ) -> Option<SimulationResult> {
+        if config.num_paths == 0
+            || current_price <= 0.0
+            || !positions.iter().any(|p| p.is_active())
+            || PayoffEngine::spans_several_underlyings(positions)
+        {
+            return None;
+        }
+
+        // Value every leg at the horizon (collapses to the expiry payoff at or past expiry)
+        let today = ValuationContext { days_elapsed: 0.0, ..*context };
+        let horizon_context = ValuationContext { days_elapsed: config.horizon_days, ..today };
+        let current_value =
+            PayoffEngine::calculate_portfolio_value(positions, current_price, &today);
+        let mut rng = SimulationRng::new(config.seed);
+
+        let mut pnl: Vec<f64> = (0..config.num_paths)
+            .map(|_| {
+                let price = Self::simulate_terminal_price(current_price, config, &mut rng);
+                PayoffEngine::calculate_portfolio_value(positions, price, &horizon_context)
+                    - current_value
+            })
+            .collect();
+        pnl.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let count = pnl.len() as f64;
+        let mean = pnl.iter().sum::<f64>() / count;
+        let variance = pnl.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / count;
+
+        let tail_fraction = 1.0 - config.confidence;
+        let value_at_risk = -percentile_of_sorted(&pnl, tail_fraction);
+        let tail_count = ((count * tail_fraction).ceil() as usize).clamp(1, pnl.len());
+        let conditional_value_at_risk = -pnl[..tail_count].iter().sum::<f64>() / tail_count as f64;
+
+        let percentiles = REPORTED_PERCENTILES
+            .iter()
+            .map(|&fraction| (fraction, percentile_of_sorted(&pnl, fraction)))
+            .collect();
+
+        Some(SimulationResult {
+            pnl,
+            mean,
+            std_dev: variance.sqrt(),
+            percentiles,
+            confidence: config.confidence,
+            value_at_risk,
+            conditional_value_at_risk,
+        })
+    }
+
+    // === Private helper functions ===
+
+    /// Draw one terminal price: GBM with compensated log-normal (Merton) jumps
+    fn simulate_terminal_price(
+        current_price: f64,
+        config: &SimulationConfig,
+        rng: &mut SimulationRng,
+    ) -> f64 {
+        let horizon = config.horizon_days.max(0.0) / DAYS_PER_YEAR;
+        let steps = config.num_steps.max(1);
+        let dt = horizon / steps as f64;
+
+        // Jump compensator keeps the expected return equal to the drift
+        let jump_compensator = config.jump_intensity
+            * ((config.jump_mean + 0.5 * config.jump_volatility.powi(2)).exp() - 1.0);
+        let step_drift = (config.drift - 0.5 * config.volatility.powi(2) - jump_compensator) * dt;
+        let step_diffusion = config.volatility * dt.sqrt();
+
+        let mut log_price = current_price.ln();
+        for _ in 0..steps {
+            log_price += step_drift + step_diffusion * rng.next_normal();
+
+            if config.jump_intensity > 0.0 {
+                for _ in 0..rng.next_poisson(config.jump_intensity * dt) {
+                    log_price += config.jump_mean + config.jump_volatility * rng.next_normal();
+                }
+            }
+        }
+
+        log_price.exp()
+    }
+}
+
+/// Linearly interpolated percentile of an ascending slice
+pub(crate) fn percentile_of_sorted(sorted: &[f64], fraction: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+
+    let rank = fraction.clamp(0.0, 1.0) * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+}
+
+/// Small deterministic RNG (SplitMix64) so simulations are reproducible without extra crates
+pub(crate) struct SimulationRng {
+    state: u64,
+}
+
+impl SimulationRng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform sample in (0, 1)
+    fn next_uniform(&mut self) -> f64 {
+        ((self.next_u64() >> 11) as f64 + 0.5) / (1u64 << 53) as f64
+    }
+
+    /// Standard normal sample (Box-Muller)
+    pub(crate) fn next_normal(&mut self) -> f64 {
+        let u1 = self.next_uniform();
+        let u2 = self.next_uniform();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
+
+    /// Poisson sample (Knuth), intended for small per-step intensities
+    fn next_poisson(&mut self, lambda: f64) -> u32 {
+        let threshold = (-lambda).exp();
+        let mut count = 0;
+        let mut product = self.next_uniform();
+        while product > threshold {
+            count += 1;
+            product *= self.next_uniform();
+        }
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::*;
+
+    fn config(num_paths: usize, volatility: f64, seed: u64) -> SimulationConfig {
+        SimulationConfig {
+            num_paths,
+            num_steps: 10,
+            horizon_days: 30.0,
+            drift: 0.0,
+            volatility,
+            jump_intensity: 0.0,
+            jump_mean: 0.0,
+            jump_volatility: 0.0,
+            confidence: 0.95,
+            seed,
+        }
+    }
+
+    #[test]
+    fn test_simulation_is_reproducible_with_seed() {
+        let positions = vec![Position::Spot(SpotPosition::new(1.0, 100.0, None))];
+        let context = ValuationContext::default();
+        let mut jumpy = config(500, 0.8, 42);
+        jumpy.jump_intensity = 5.0;
+        jumpy.jump_mean = -0.1;
+        jumpy.jump_volatility = 0.2;
+
+        let first = SimulationEngine::simulate_portfolio(&positions, 100.0, &jumpy, &context);
+        let second = SimulationEngine::simulate_portfolio(&positions, 100.0, &jumpy, &context);
+        assert_eq!(first, second);
+
+        jumpy.seed = 7;
+        let other = SimulationEngine::simulate_portfolio(&positions, 100.0, &jumpy, &context);
+        assert_ne!(first, other);
+    }
+
+    #[test]
+    fn test_zero_volatility_collapses_to_payoff() {
+        let call = OptionPosition::new(OptionType::Call, 1.0, 90.0, 4.0, None);
+        let positions = vec![Position::Option(call)];
+        let context = ValuationContext::new(30.0, 0.0, 0.5, 0.0);
+
+        let result =
+            SimulationEngine::simulate_portfolio(&positions, 100.0, &config(100, 0.0, 1), &context)
+                .unwrap();
+
+        // Every path ends at 100 on expiry (intrinsic 10 - premium 4), measured
+        // against today's mark, which still carries time value
+        let today = ValuationContext { days_elapsed: 0.0, ..context };
+        let current_value = PayoffEngine::calculate_portfolio_value(&positions, 100.0, &today);
+        let expected_pnl = 6.0 - current_value;
+        assert!(expected_pnl < 0.0);
+        assert!(result
+            .pnl
+            .iter()
+            .all(|pnl| (pnl - expected_pnl).abs() < 1e-9));
+        assert!(result.std_dev < 1e-9);
+        assert!((result.value_at_risk + expected_pnl).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_var_and_cvar_for_long_spot() {
+        let positions = vec![Position::Spot(SpotPosition::new(1.0, 100.0, None))];
+        let context = ValuationContext::default();
+        let result = SimulationEngine::simulate_portfolio(
+            &positions,
+            100.0,
+            &config(20_000, 0.5, 2024),
+            &context,
+        )
+        .unwrap();
+
+        // Analytic 95% VaR of a lognormal with zero drift, sigma * sqrt(30/365)
+        let sigma = 0.5 * (30.0_f64 / 365.0).sqrt();
+        let analytic_var = 100.0 * (1.0 - (-0.5 * sigma * sigma - 1.644854 * sigma).exp());
+
+        assert!((result.value_at_risk - analytic_var).abs() < 1.0);
+        assert!(result.conditional_value_at_risk > result.value_at_risk);
+        assert!(result.mean.abs() < 0.5);
+        assert_eq!(result.percentiles.len(), REPORTED_PERCENTILES.len());
+        assert!((result.percentile(0.05) + result.value_at_risk).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_confidence_follows_var_settings() {
+        let mut settings = CalculationSettings::default();
+        settings.var_settings.confidence_levels = vec![0.9, 0.99];
+        assert_eq!(SimulationConfig::from_settings(&settings).confidence, 0.99);
+
+        settings.var_settings.confidence_levels.clear();
+        assert_eq!(
+            SimulationConfig::from_settings(&settings).confidence,
+            DEFAULT_CONFIDENCE
+        );
+    }
+}
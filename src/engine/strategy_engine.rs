@@ -0,0 +1,795 @@
+use crate::models::{ContractType, OptionType, Position};
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+
+/// Relative tolerance when matching leg sizes and strikes
+const MATCH_TOLERANCE: f64 = 1e-9;
+
+/// Named multi-leg structure
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StrategyKind {
+    ShortIronCondor,
+    LongIronCondor,
+    ShortIronButterfly,
+    LongIronButterfly,
+    LongButterfly,
+    ShortButterfly,
+    Collar,
+    CoveredCall,
+    ProtectivePut,
+    SyntheticLong,
+    SyntheticShort,
+    LongStraddle,
+    ShortStraddle,
+    LongStrangle,
+    ShortStrangle,
+    BullCallSpread,
+    BearCallSpread,
+    BullPutSpread,
+    BearPutSpread,
+    CalendarSpread,
+    CallRatioSpread,
+    PutRatioSpread,
+}
+
+impl StrategyKind {
+    /// Display name
+    pub fn label(self) -> &'static str {
+        match self {
+            StrategyKind::ShortIronCondor => "Short Iron Condor",
+            StrategyKind::LongIronCondor => "Long Iron Condor",
+            StrategyKind::ShortIronButterfly => "Short Iron Butterfly",
+            StrategyKind::LongIronButterfly => "Long Iron Butterfly",
+            StrategyKind::LongButterfly => "Long Butterfly",
+            StrategyKind::ShortButterfly => "Short Butterfly",
+            StrategyKind::Collar => "Collar",
+            StrategyKind::CoveredCall => "Covered Call",
+            StrategyKind::ProtectivePut => "Protective Put",
+            StrategyKind::SyntheticLong => "Synthetic Long",
+            StrategyKind::SyntheticShort => "Synthetic Short",
+            StrategyKind::LongStraddle => "Long Straddle",
+            StrategyKind::ShortStraddle => "Short Straddle",
+            StrategyKind::LongStrangle => "Long Strangle",
+            StrategyKind::ShortStrangle => "Short Strangle",
+            StrategyKind::BullCallSpread => "Bull Call Spread",
+            StrategyKind::BearCallSpread => "Bear Call Spread",
+            StrategyKind::BullPutSpread => "Bull Put Spread",
+            StrategyKind::BearPutSpread => "Bear Put Spread",
+            StrategyKind::CalendarSpread => "Calendar Spread",
+            StrategyKind::CallRatioSpread => "Call Ratio Spread",
+            StrategyKind::PutRatioSpread => "Put Ratio Spread",
+        }
+    }
+}
+
+/// A position's part in a recognized strategy
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StrategyLeg {
+    pub index: usize,       // Index into the classified positions
+    pub role: &'static str, // e.g. "short put", "lower wing"
+}
+
+/// A structure found among the positions
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecognizedStrategy {
+    pub kind: StrategyKind,
+    pub underlying: Option<String>, // Underlying of every leg (None = primary)
+    pub legs: Vec<StrategyLeg>,     // In ascending strike order
+    pub widths: Vec<f64>,           // Strike distances between adjacent strikes
+}
+
+/// Strategies found among the active positions, plus the legs left over
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StrategyReport {
+    pub strategies: Vec<RecognizedStrategy>, // Ordered by first leg
+    pub residuals: Vec<usize>,               // Active legs that fit no strategy
+}
+
+impl StrategyReport {
+    /// Strategy a position belongs to, with its role in it
+    pub fn strategy_of(&self, index: usize) -> Option<(&RecognizedStrategy, &'static str)> {
+        self.strategies.iter().find_map(|strategy| {
+            strategy
+                .legs
+                .iter()
+                .find(|leg| leg.index == index)
+                .map(|leg| (strategy, leg.role))
+        })
+    }
+}
+
+/// Strategy recognition engine (WASM-compatible)
+pub struct StrategyEngine;
+
+impl StrategyEngine {
+    /// Name the structures held by the active positions
+    ///
+    /// Larger structures are matched first (condors before spreads); legs are matched
+    /// within one underlying, on equal sizes in underlying units and equal expiries.
+    /// Each pattern only searches legs that can share it (one underlying, expiry and
+    /// option type where the pattern needs them), so mixed accounts stay cheap.
+    pub fn classify(positions: &[Position]) -> StrategyReport {
+        let mut residuals = Vec::new();
+        let mut legs: Vec<Leg> = positions
+            .iter()
+            .enumerate()
+            .filter(|(_, pos)| pos.is_active())
+            .filter_map(|(index, pos)| {
+                let leg = Leg::from_position(index, pos);
+                if leg.is_none() {
+                    residuals.push(index);
+                }
+                leg
+            })
+            .collect();
+        legs.sort_by(|a, b| {
+            a.underlying
+                .cmp(&b.underlying)
+                .then(a.strike.partial_cmp(&b.strike).unwrap())
+                .then(a.class.cmp(&b.class))
+                .then(a.index.cmp(&b.index))
+        });
+
+        let patterns: [(usize, Grouping, Pattern); 12] = [
+            (4, Grouping::Series, match_iron_condor),
+            (4, Grouping::SeriesClass, match_split_butterfly),
+            (3, Grouping::SeriesClass, match_butterfly),
+            (3, Grouping::Covered, match_collar),
+            (2, Grouping::Covered, match_covered_call),
+            (2, Grouping::Covered, match_protective_put),
+            (2, Grouping::Series, match_synthetic),
+            (2, Grouping::Series, match_straddle),
+            (2, Grouping::Series, match_strangle),
+            (2, Grouping::SeriesClass, match_vertical),
+            (2, Grouping::Class, match_calendar),
+            (2, Grouping::SeriesClass, match_ratio),
+        ];
+
+        let mut used = vec![false; legs.len()];
+        let mut strategies = Vec::new();
+        for (size, grouping, pattern) in patterns {
+            for bucket in Self::buckets(&legs, grouping) {
+                // Combinations before the last match's first leg already failed
+                let mut start = 0;
+                while let Some((chosen, found)) =
+                    Self::find_match(&legs, &bucket, &used, size, pattern, &mut Vec::new(), start)
+                {
+                    start = chosen[0];
+                    let chosen: Vec<usize> = chosen.iter().map(|&slot| bucket[slot]).collect();
+                    for &leg in &chosen {
+                        used[leg] = true;
+                    }
+                    strategies.push(RecognizedStrategy {
+                        kind: found.kind,
+                        underlying: legs[chosen[0]].underlying.map(str::to_string),
+                        legs: chosen
+                            .iter()
+                            .zip(found.roles)
+                            .map(|(&leg, role)| StrategyLeg { index: legs[leg].index, role })
+                            .collect(),
+                        widths: found.widths,
+                    });
+                }
+            }
+        }
+
+        residuals.extend(
+            legs.iter()
+                .zip(&used)
+                .filter(|(_, used)| !**used)
+                .map(|(leg, _)| leg.index),
+        );
+        residuals.sort_unstable();
+        strategies.sort_by_key(|strategy| strategy.legs.iter().map(|leg| leg.index).min());
+
+        StrategyReport { strategies, residuals }
+    }
+
+    // === Private helper functions ===
+
+    /// Legs a pattern may combine, grouped so that only legs sharing a group are tried
+    /// together; each group keeps the sorted leg order
+    fn buckets(legs: &[Leg], grouping: Grouping) -> Vec<Vec<usize>> {
+        type Key<'a> = (
+            Option<&'a str>,
+            Option<Option<DateTime<Utc>>>,
+            Option<LegClass>,
+        );
+        let mut buckets: BTreeMap<Key, Vec<usize>> = BTreeMap::new();
+        for (i, leg) in legs.iter().enumerate() {
+            if leg.class == LegClass::Underlying {
+                continue;
+            }
+            let key = match grouping {
+                Grouping::Series | Grouping::Covered => (leg.underlying, Some(leg.expiry), None),
+                Grouping::SeriesClass => (leg.underlying, Some(leg.expiry), Some(leg.class)),
+                Grouping::Class => (leg.underlying, None, Some(leg.class)),
+            };
+            buckets.entry(key).or_default().push(i);
+        }
+
+        // The underlying pairs with the options of any one expiry
+        if grouping == Grouping::Covered {
+            for (key, bucket) in buckets.iter_mut() {
+                bucket.extend((0..legs.len()).filter(|&i| {
+                    legs[i].class == LegClass::Underlying && legs[i].underlying == key.0
+                }));
+                bucket.sort_unstable();
+            }
+        }
+        buckets.into_values().collect()
+    }
+
+    /// First combination of unused legs in the bucket (in sorted order) the pattern
+    /// accepts, as positions within the bucket
+    fn find_match(
+        legs: &[Leg],
+        bucket: &[usize],
+        used: &[bool],
+        size: usize,
+        pattern: Pattern,
+        chosen: &mut Vec<usize>,
+        start: usize,
+    ) -> Option<(Vec<usize>, PatternMatch)> {
+        if chosen.len() == size {
+            let combination: Vec<&Leg> = chosen.iter().map(|&slot| &legs[bucket[slot]]).collect();
+            return pattern(&combination).map(|found| (chosen.clone(), found));
+        }
+
+        // Not enough legs left in the bucket to complete the combination
+        let last = (bucket.len() + chosen.len() + 1).checked_sub(size)?;
+        for slot in start..last {
+            if used[bucket[slot]] {
+                continue;
+            }
+            chosen.push(slot);
+            let found = Self::find_match(legs, bucket, used, size, pattern, chosen, slot + 1);
+            chosen.pop();
+            if found.is_some() {
+                return found;
+            }
+        }
+        None
+    }
+}
+
+/// Which legs a pattern is searched across
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Grouping {
+    Series,      // Options on one underlying and expiry
+    SeriesClass, // Calls or puts on one underlying and expiry
+    Covered,     // Options on one underlying and expiry with that underlying's spot / futures
+    Class,       // Calls or puts on one underlying, any expiry
+}
+
+/// Leg kinds a strategy is built from, in the order legs at one strike are sorted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum LegClass {
+    Underlying, // Spot or linear futures
+    Put,
+    Call,
+}
+
+/// Active position reduced to what strategy matching looks at
+#[derive(Debug, Clone, Copy)]
+struct Leg<'a> {
+    index: usize,
+    class: LegClass,
+    units: f64,  // Signed size in underlying units
+    strike: f64, // 0 for the underlying
+    expiry: Option<DateTime<Utc>>,
+    underlying: Option<&'a str>,
+}
+
+impl<'a> Leg<'a> {
+    /// None for legs no strategy is built from (digitals, barriers, inverse futures)
+    fn from_position(index: usize, position: &'a Position) -> Option<Self> {
+        let (class, units, strike, expiry) = match position {
+            Position::Spot(spot) => (LegClass::Underlying, spot.quantity, 0.0, None),
+            Position::Futures(futures) if futures.contract_type == ContractType::Linear => (
+                LegClass::Underlying,
+                futures.quantity * futures.contract_size,
+                0.0,
+                None,
+            ),
+            Position::Option(option) => (
+                match option.option_type {
+                    OptionType::Call => LegClass::Call,
+                    OptionType::Put => LegClass::Put,
+                },
+                option.units(),
+                option.strike_price,
+                option.expiry,
+            ),
+            _ => return None,
+        };
+        (units != 0.0).then_some(Self {
+            index,
+            class,
+            units,
+            strike,
+            expiry,
+            underlying: position.underlying(),
+        })
+    }
+
+    fn is_long(&self) -> bool {
+        self.units > 0.0
+    }
+}
+
+/// Strategy a combination of legs forms, with the role of each leg
+struct PatternMatch {
+    kind: StrategyKind,
+    roles: Vec<&'static str>,
+    widths: Vec<f64>,
+}
+
+/// Recognizes one structure from legs in ascending strike order
+type Pattern = fn(&[&Leg]) -> Option<PatternMatch>;
+
+fn approx_eq(a: f64, b: f64) -> bool {
+    (a - b).abs() <= MATCH_TOLERANCE * a.abs().max(b.abs()).max(1.0)
+}
+
+/// Legs on one underlying and expiry
+fn same_series(legs: &[&Leg]) -> bool {
+    legs.windows(2)
+        .all(|pair| pair[0].underlying == pair[1].underlying && pair[0].expiry == pair[1].expiry)
+}
+
+fn classes(legs: &[&Leg]) -> Vec<LegClass> {
+    legs.iter().map(|leg| leg.class).collect()
+}
+
+fn found(kind: StrategyKind, roles: &[&'static str], widths: Vec<f64>) -> Option<PatternMatch> {
+    Some(PatternMatch { kind, roles: roles.to_vec(), widths })
+}
+
+/// Put spread below a call spread, wings one way and the body the other
+fn match_iron_condor(legs: &[&Leg]) -> Option<PatternMatch> {
+    let [lower, put, call, upper] = legs else {
+        return None;
+    };
+    use LegClass::*;
+    if classes(legs) != [Put, Put, Call, Call] || !same_series(legs) {
+        return None;
+    }
+    let wings = lower.units;
+    let sized = approx_eq(upper.units, wings)
+        && approx_eq(put.units, -wings)
+        && approx_eq(call.units, -wings);
+    if !sized
+        || lower.strike >= put.strike
+        || put.strike > call.strike
+        || call.strike >= upper.strike
+    {
+        return None;
+    }
+
+    let butterfly = approx_eq(put.strike, call.strike);
+    let kind = match (lower.is_long(), butterfly) {
+        (true, false) => StrategyKind::ShortIronCondor,
+        (false, false) => StrategyKind::LongIronCondor,
+        (true, true) => StrategyKind::ShortIronButterfly,
+        (false, true) => StrategyKind::LongIronButterfly,
+    };
+    let roles = if lower.is_long() {
+        ["long put wing", "short put", "short call", "long call wing"]
+    } else {
+        ["short put wing", "long put", "long call", "short call wing"]
+    };
+    let mut widths = vec![put.strike - lower.strike];
+    if !butterfly {
+        widths.push(call.strike - put.strike);
+    }
+    widths.push(upper.strike - call.strike);
+    found(kind, &roles, widths)
+}
+
+/// Butterfly with the two body contracts held as separate legs
+fn match_split_butterfly(legs: &[&Leg]) -> Option<PatternMatch> {
+    let [lower, body, other_body, upper] = legs else {
+        return None;
+    };
+    if !same_series(legs)
+        || body.class == LegClass::Underlying
+        || legs.iter().any(|leg| leg.class != body.class)
+    {
+        return None;
+    }
+    let sized = approx_eq(upper.units, lower.units)
+        && approx_eq(body.units, -lower.units)
+        && approx_eq(other_body.units, -lower.units);
+    if !sized
+        || lower.strike >= body.strike
+        || !approx_eq(body.strike, other_body.strike)
+        || body.strike >= upper.strike
+    {
+        return None;
+    }
+
+    let kind = if lower.is_long() {
+        StrategyKind::LongButterfly
+    } else {
+        StrategyKind::ShortButterfly
+    };
+    let widths = vec![body.strike - lower.strike, upper.strike - body.strike];
+    found(kind, &["lower wing", "body", "body", "upper wing"], widths)
+}
+
+/// Wings of one size around a body of twice the size, all calls or all puts
+fn match_butterfly(legs: &[&Leg]) -> Option<PatternMatch> {
+    let [lower, body, upper] = legs else {
+        return None;
+    };
+    if !same_series(legs)
+        || body.class == LegClass::Underlying
+        || legs.iter().any(|leg| leg.class != body.class)
+    {
+        return None;
+    }
+    let sized = approx_eq(upper.units, lower.units) && approx_eq(body.units, -2.0 * lower.units);
+    if !sized || lower.strike >= body.strike || body.strike >= upper.strike {
+        return None;
+    }
+
+    let kind = if lower.is_long() {
+        StrategyKind::LongButterfly
+    } else {
+        StrategyKind::ShortButterfly
+    };
+    let widths = vec![body.strike - lower.strike, upper.strike - body.strike];
+    found(kind, &["lower wing", "body", "upper wing"], widths)
+}
+
+/// Long underlying with a protective put below a covered call
+fn match_collar(legs: &[&Leg]) -> Option<PatternMatch> {
+    let [underlying, put, call] = legs else {
+        return None;
+    };
+    use LegClass::*;
+    if classes(legs) != [Underlying, Put, Call]
+        || underlying.underlying != put.underlying
+        || !same_series(&[*put, *call])
+    {
+        return None;
+    }
+    let sized = underlying.is_long()
+        && approx_eq(put.units, underlying.units)
+        && approx_eq(call.units, -underlying.units);
+    if !sized || put.strike > call.strike {
+        return None;
+    }
+
+    let roles = ["underlying", "protective put", "covered call"];
+    found(StrategyKind::Collar, &roles, vec![call.strike - put.strike])
+}
+
+/// Long underlying with a short call of the same size
+fn match_covered_call(legs: &[&Leg]) -> Option<PatternMatch> {
+    let [underlying, call] = legs else {
+        return None;
+    };
+    let matched = underlying.class == LegClass::Underlying
+        && call.class == LegClass::Call
+        && underlying.underlying == call.underlying
+        && underlying.is_long()
+        && approx_eq(call.units, -underlying.units);
+    if !matched {
+        return None;
+    }
+    found(
+        StrategyKind::CoveredCall,
+        &["underlying", "covered call"],
+        Vec::new(),
+    )
+}
+
+/// Long underlying with a long put of the same size
+fn match_protective_put(legs: &[&Leg]) -> Option<PatternMatch> {
+    let [underlying, put] = legs else {
+        return None;
+    };
+    let matched = underlying.class == LegClass::Underlying
+        && put.class == LegClass::Put
+        && underlying.underlying == put.underlying
+        && underlying.is_long()
+        && approx_eq(put.units, underlying.units);
+    if !matched {
+        return None;
+    }
+    found(
+        StrategyKind::ProtectivePut,
+        &["underlying", "protective put"],
+        Vec::new(),
+    )
+}
+
+/// Call and put at one strike on opposite sides: replicates the underlying
+fn match_synthetic(legs: &[&Leg]) -> Option<PatternMatch> {
+    let [put, call] = legs else {
+        return None;
+    };
+    use LegClass::*;
+    let matched = classes(legs) == [Put, Call]
+        && same_series(legs)
+        && approx_eq(put.strike, call.strike)
+        && approx_eq(put.units, -call.units);
+    if !matched {
+        return None;
+    }
+    if call.is_long() {
+        found(
+            StrategyKind::SyntheticLong,
+            &["short put", "long call"],
+            Vec::new(),
+        )
+    } else {
+        found(
+            StrategyKind::SyntheticShort,
+            &["long put", "short call"],
+            Vec::new(),
+        )
+    }
+}
+
+/// Call and put at one strike on the same side
+fn match_straddle(legs: &[&Leg]) -> Option<PatternMatch> {
+    let [put, call] = legs else {
+        return None;
+    };
+    use LegClass::*;
+    let matched = classes(legs) == [Put, Call]
+        && same_series(legs)
+        && approx_eq(put.strike, call.strike)
+        && approx_eq(put.units, call.units);
+    if !matched {
+        return None;
+    }
+    if call.is_long() {
+        found(
+            StrategyKind::LongStraddle,
+            &["long put", "long call"],
+            Vec::new(),
+        )
+    } else {
+        found(
+            StrategyKind::ShortStraddle,
+            &["short put", "short call"],
+            Vec::new(),
+        )
+    }
+}
+
+/// Put below a call on the same side
+fn match_strangle(legs: &[&Leg]) -> Option<PatternMatch> {
+    let [put, call] = legs else {
+        return None;
+    };
+    use LegClass::*;
+    let matched = classes(legs) == [Put, Call]
+        && same_series(legs)
+        && put.strike < call.strike
+        && approx_eq(put.units, call.units);
+    if !matched {
+        return None;
+    }
+    let widths = vec![call.strike - put.strike];
+    if call.is_long() {
+        found(
+            StrategyKind::LongStrangle,
+            &["long put", "long call"],
+            widths,
+        )
+    } else {
+        found(
+            StrategyKind::ShortStrangle,
+            &["short put", "short call"],
+            widths,
+        )
+    }
+}
+
+/// Same-size calls or puts bought at one strike and sold at another
+fn match_vertical(legs: &[&Leg]) -> Option<PatternMatch> {
+    let [lower, upper] = legs else {
+        return None;
+    };
+    let matched = lower.class == upper.class
+        && lower.class != LegClass::Underlying
+        && same_series(legs)
+        && lower.strike < upper.strike
+        && approx_eq(lower.units, -upper.units);
+    if !matched {
+        return None;
+    }
+
+    let kind = match (lower.class, lower.is_long()) {
+        (LegClass::Call, true) => StrategyKind::BullCallSpread,
+        (LegClass::Call, false) => StrategyKind::BearCallSpread,
+        (_, true) => StrategyKind::BullPutSpread,
+        (_, false) => StrategyKind::BearPutSpread,
+    };
+    let roles = if lower.is_long() {
+        ["long lower strike", "short upper strike"]
+    } else {
+        ["short lower strike", "long upper strike"]
+    };
+    found(kind, &roles, vec![upper.strike - lower.strike])
+}
+
+/// Same-size calls or puts at one strike, bought and sold in different expiries
+fn match_calendar(legs: &[&Leg]) -> Option<PatternMatch> {
+    let [first, second] = legs else {
+        return None;
+    };
+    let (Some(first_expiry), Some(second_expiry)) = (first.expiry, second.expiry) else {
+        return None;
+    };
+    let matched = first.class == second.class
+        && first.class != LegClass::Underlying
+        && first.underlying == second.underlying
+        && first_expiry != second_expiry
+        && approx_eq(first.strike, second.strike)
+        && approx_eq(first.units, -second.units);
+    if !matched {
+        return None;
+    }
+
+    let near_is_long = (first_expiry < second_expiry) == first.is_long();
+    let (near, far) = if near_is_long {
+        ("long near expiry", "short far expiry")
+    } else {
+        ("short near expiry", "long far expiry")
+    };
+    let roles = if first_expiry < second_expiry {
+        [near, far]
+    } else {
+        [far, near]
+    };
+    found(StrategyKind::CalendarSpread, &roles, Vec::new())
+}
+
+/// Calls or puts bought at one strike and sold at another in different sizes
+fn match_ratio(legs: &[&Leg]) -> Option<PatternMatch> {
+    let [lower, upper] = legs else {
+        return None;
+    };
+    let matched = lower.class == upper.class
+        && lower.class != LegClass::Underlying
+        && same_series(legs)
+        && lower.strike < upper.strike
+        && lower.is_long() != upper.is_long();
+    if !matched {
+        return None;
+    }
+
+    let kind = if lower.class == LegClass::Call {
+        StrategyKind::CallRatioSpread
+    } else {
+        StrategyKind::PutRatioSpread
+    };
+    let roles = if lower.is_long() {
+        ["long lower strike", "short upper strike"]
+    } else {
+        ["short lower strike", "long upper strike"]
+    };
+    found(kind, &roles, vec![upper.strike - lower.strike])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::*;
+
+    fn option(option_type: OptionType, quantity: f64, strike: f64) -> Position {
+        Position::Option(OptionPosition::new(
+            option_type,
+            quantity,
+            strike,
+            1.0,
+            None,
+        ))
+    }
+
+    #[test]
+    fn test_iron_condor_with_residual() {
+        let positions = vec![
+            option(OptionType::Call, -1.0, 110.0),
+            option(OptionType::Put, 1.0, 85.0),
+            option(OptionType::Call, 1.0, 120.0),
+            option(OptionType::Put, -1.0, 90.0),
+            Position::Digital(DigitalPosition::new(
+                OptionType::Call,
+                1.0,
+                100.0,
+                10.0,
+                4.0,
+                None,
+            )),
+        ];
+
+        let report = StrategyEngine::classify(&positions);
+        assert_eq!(report.strategies.len(), 1);
+        let condor = &report.strategies[0];
+        assert_eq!(condor.kind, StrategyKind::ShortIronCondor);
+        assert_eq!(condor.widths, vec![5.0, 20.0, 10.0]);
+        let indices: Vec<usize> = condor.legs.iter().map(|leg| leg.index).collect();
+        assert_eq!(indices, vec![1, 3, 0, 2]);
+        assert_eq!(report.strategy_of(3).unwrap().1, "short put");
+        assert_eq!(report.residuals, vec![4]);
+    }
+
+    #[test]
+    fn test_mixed_account_structures() {
+        let mut inactive = option(OptionType::Put, 1.0, 80.0);
+        inactive.set_active(false);
+        let positions = vec![
+            Position::Spot(SpotPosition::new(2.0, 100.0, None)),
+            option(OptionType::Call, -2.0, 110.0),
+            option(OptionType::Call, 1.0, 95.0),
+            option(OptionType::Call, -2.0, 100.0),
+            option(OptionType::Call, 1.0, 105.0),
+            option(OptionType::Put, -1.0, 90.0),
+            option(OptionType::Put, 3.0, 95.0),
+            inactive,
+        ];
+
+        let report = StrategyEngine::classify(&positions);
+        let kinds: Vec<StrategyKind> = report
+            .strategies
+            .iter()
+            .map(|strategy| strategy.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                StrategyKind::CoveredCall,
+                StrategyKind::LongButterfly,
+                StrategyKind::PutRatioSpread,
+            ]
+        );
+        assert_eq!(report.strategies[2].widths, vec![5.0]);
+        assert!(report.residuals.is_empty());
+    }
+
+    #[test]
+    fn test_structures_across_expiries() {
+        let near = Utc::now() + chrono::Duration::days(7);
+        let far = Utc::now() + chrono::Duration::days(35);
+        let dated =
+            |option_type, quantity, strike, expiry| match option(option_type, quantity, strike) {
+                Position::Option(option) => {
+                    Position::Option(OptionPosition { expiry: Some(expiry), ..option })
+                }
+                other => other,
+            };
+        let positions = vec![
+            dated(OptionType::Call, 1.0, 100.0, near),
+            dated(OptionType::Call, -1.0, 110.0, near),
+            dated(OptionType::Call, -1.0, 105.0, far),
+            dated(OptionType::Call, 1.0, 105.0, near),
+            Position::Spot(SpotPosition::new(-1.0, 100.0, None)),
+        ];
+
+        // The far call only pairs across expiries; a short spot covers no call
+        let report = StrategyEngine::classify(&positions);
+        let found: Vec<(StrategyKind, Vec<usize>)> = report
+            .strategies
+            .iter()
+            .map(|strategy| {
+                let indices = strategy.legs.iter().map(|leg| leg.index).collect();
+                (strategy.kind, indices)
+            })
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (StrategyKind::BullCallSpread, vec![0, 1]),
+                (StrategyKind::CalendarSpread, vec![2, 3]),
+            ]
+        );
+        assert_eq!(report.residuals, vec![4]);
+    }
+}
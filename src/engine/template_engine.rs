@@ -0,0 +1,368 @@
+use super::pricing_engine::{PricingEngine, ValuationContext};
+use crate::models::{
+    ContractType, FuturesPosition, OptionPosition, OptionType, Position, PositionTemplate,
+    SpotPosition, TemplateCategory, TemplateLegParameters, TemplatePosition,
+};
+use chrono::{DateTime, Utc};
+
+/// Where a template is placed when it is turned into positions
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateInputs {
+    pub underlying_price: f64,      // Price the strike offsets are relative to
+    pub size: f64,                  // Multiplies every leg's quantity
+    pub width: f64,                 // Strike width as a fraction of the price (0.05 = 5%)
+    pub underlying: Option<String>, // Underlying of the created legs (None = primary)
+}
+
+/// Strategy template engine (WASM-compatible)
+pub struct TemplateEngine;
+
+impl TemplateEngine {
+    /// Built-in template library
+    pub fn builtin_templates() -> Vec<PositionTemplate> {
+        use OptionType::{Call, Put};
+        vec![
+            Self::builtin(
+                "bull-call-spread",
+                "Bull Call Spread",
+                "Long ATM call, short call one width higher",
+                TemplateCategory::Options,
+                vec![
+                    Self::option_leg(Call, 1.0, 0.0, 0.0, "Long call"),
+                    Self::option_leg(Call, -1.0, 0.0, 1.0, "Short call"),
+                ],
+            ),
+            Self::builtin(
+                "bear-put-spread",
+                "Bear Put Spread",
+                "Long ATM put, short put one width lower",
+                TemplateCategory::Options,
+                vec![
+                    Self::option_leg(Put, 1.0, 0.0, 0.0, "Long put"),
+                    Self::option_leg(Put, -1.0, 0.0, -1.0, "Short put"),
+                ],
+            ),
+            Self::builtin(
+                "long-straddle",
+                "Long Straddle",
+                "Long ATM call and put",
+                TemplateCategory::Options,
+                vec![
+                    Self::option_leg(Put, 1.0, 0.0, 0.0, "Long put"),
+                    Self::option_leg(Call, 1.0, 0.0, 0.0, "Long call"),
+                ],
+            ),
+            Self::builtin(
+                "short-strangle",
+                "Short Strangle",
+                "Short put one width below and short call one width above ATM",
+                TemplateCategory::Options,
+                vec![
+                    Self::option_leg(Put, -1.0, 0.0, -1.0, "Short put"),
+                    Self::option_leg(Call, -1.0, 0.0, 1.0, "Short call"),
+                ],
+            ),
+            Self::builtin(
+                "short-iron-condor",
+                "Short Iron Condor",
+                "Short ATM±5% strangle with long wings one width further out",
+                TemplateCategory::Complex,
+                vec![
+                    Self::option_leg(Put, 1.0, -0.05, -1.0, "Long put wing"),
+                    Self::option_leg(Put, -1.0, -0.05, 0.0, "Short put"),
+                    Self::option_leg(Call, -1.0, 0.05, 0.0, "Short call"),
+                    Self::option_leg(Call, 1.0, 0.05, 1.0, "Long call wing"),
+                ],
+            ),
+            Self::builtin(
+                "long-call-butterfly",
+                "Long Call Butterfly",
+                "Two short ATM calls between long calls one width either side",
+                TemplateCategory::Complex,
+                vec![
+                    Self::option_leg(Call, 1.0, 0.0, -1.0, "Lower wing"),
+                    Self::option_leg(Call, -2.0, 0.0, 0.0, "Body"),
+                    Self::option_leg(Call, 1.0, 0.0, 1.0, "Upper wing"),
+                ],
+            ),
+            Self::builtin(
+                "collar",
+                "Collar",
+                "Long underlying with a put one width below and a short call one width above",
+                TemplateCategory::BasicStrategies,
+                vec![
+                    Self::underlying_leg("Spot", 1.0, "Underlying"),
+                    Self::option_leg(Put, 1.0, 0.0, -1.0, "Protective put"),
+                    Self::option_leg(Call, -1.0, 0.0, 1.0, "Covered call"),
+                ],
+            ),
+            Self::builtin(
+                "covered-call",
+                "Covered Call",
+                "Long underlying with a short call one width above",
+                TemplateCategory::BasicStrategies,
+                vec![
+                    Self::underlying_leg("Spot", 1.0, "Underlying"),
+                    Self::option_leg(Call, -1.0, 0.0, 1.0, "Covered call"),
+                ],
+            ),
+        ]
+    }
+
+    /// Turn a template into positions around the given price
+    ///
+    /// Option premiums are today's model value under the valuation context.
+    pub fn instantiate(
+        template: &PositionTemplate,
+        inputs: &TemplateInputs,
+        context: &ValuationContext,
+    ) -> Result<Vec<Position>, String> {
+        if inputs.underlying_price <= 0.0 {
+            return Err("Underlying price must be positive".to_string());
+        }
+        if inputs.size <= 0.0 {
+            return Err("Template size must be positive".to_string());
+        }
+        if inputs.width < 0.0 {
+            return Err("Strike width cannot be negative".to_string());
+        }
+
+        let price = inputs.underlying_price;
+        template
+            .positions
+            .iter()
+            .enumerate()
+            .map(|(leg_num, leg)| {
+                let parameters = leg.leg_parameters()?;
+                let quantity = parameters.quantity * inputs.size;
+                let description = format!("{} - {}", template.name, leg.description);
+
+                let mut position = match leg.position_type.as_str() {
+                    "Spot" => Position::Spot(SpotPosition::new(quantity, price, Some(description))),
+                    "Futures" => Position::Futures(FuturesPosition::new(
+                        quantity,
+                        price,
+                        1.0,
+                        Some(description),
+                    )),
+                    "Option" => {
+                        let option_type = parameters
+                            .option_type
+                            .ok_or(format!("Leg {}: missing option type", leg_num + 1))?;
+                        let strike = price
+                            * (1.0
+                                + parameters.strike_offset
+                                + parameters.width_steps * inputs.width);
+                        if strike <= 0.0 {
+                            return Err(format!("Leg {}: strike falls below zero", leg_num + 1));
+                        }
+
+                        let option = OptionPosition::new(
+                            option_type,
+                            quantity,
+                            strike,
+                            0.0,
+                            Some(description),
+                        );
+                        let premium = PricingEngine::value_option(
+                            &option,
+                            price,
+                            context.leg_time_from_today(None),
+                            context,
+                        );
+                        Position::Option(OptionPosition { premium, ..option })
+                    }
+                    other => {
+                        return Err(format!(
+                            "Leg {}: unsupported position type '{}'",
+                            leg_num + 1,
+                            other
+                        ))
+                    }
+                };
+                position.set_underlying(inputs.underlying.clone());
+                Ok(position)
+            })
+            .collect()
+    }
+
+    /// User template from the active spot, linear futures and option legs, with
+    /// strikes stored relative to the given price
+    pub fn template_from_positions(
+        id: &str,
+        name: &str,
+        positions: &[Position],
+        underlying_price: f64,
+    ) -> Result<PositionTemplate, String> {
+        if underlying_price <= 0.0 {
+            return Err("Underlying price must be positive".to_string());
+        }
+
+        let legs: Vec<TemplatePosition> = positions
+            .iter()
+            .filter(|pos| pos.is_active())
+            .filter_map(|pos| {
+                let description = match pos.description() {
+                    "" => format!("Leg {:?}", pos.position_type()),
+                    description => description.to_string(),
+                };
+                match pos {
+                    Position::Spot(spot) => {
+                        Some(Self::underlying_leg("Spot", spot.quantity, &description))
+                    }
+                    Position::Futures(futures) if futures.contract_type == ContractType::Linear => {
+                        Some(Self::underlying_leg(
+                            "Futures",
+                            futures.quantity * futures.contract_size,
+                            &description,
+                        ))
+                    }
+                    Position::Option(option) => Some(Self::option_leg(
+                        option.option_type,
+                        option.units(),
+                        option.strike_price / underlying_price - 1.0,
+                        0.0,
+                        &description,
+                    )),
+                    _ => None,
+                }
+            })
+            .collect();
+        if legs.is_empty() {
+            return Err("No spot, futures or option legs to save".to_string());
+        }
+
+        Ok(PositionTemplate {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: None,
+            category: TemplateCategory::Custom,
+            positions: legs,
+            created_at: Utc::now(),
+            tags: Vec::new(),
+        })
+    }
+
+    // === Private helper functions ===
+
+    fn builtin(
+        id: &str,
+        name: &str,
+        description: &str,
+        category: TemplateCategory,
+        positions: Vec<TemplatePosition>,
+    ) -> PositionTemplate {
+        PositionTemplate {
+            id: format!("builtin-{}", id),
+            name: name.to_string(),
+            description: Some(description.to_string()),
+            category,
+            positions,
+            created_at: DateTime::<Utc>::UNIX_EPOCH,
+            tags: vec!["builtin".to_string()],
+        }
+    }
+
+    fn option_leg(
+        option_type: OptionType,
+        quantity: f64,
+        strike_offset: f64,
+        width_steps: f64,
+        description: &str,
+    ) -> TemplatePosition {
+        TemplatePosition::new(
+            "Option",
+            TemplateLegParameters {
+                quantity,
+                option_type: Some(option_type),
+                strike_offset,
+                width_steps,
+            },
+            description,
+        )
+    }
+
+    fn underlying_leg(position_type: &str, quantity: f64, description: &str) -> TemplatePosition {
+        TemplatePosition::new(
+            position_type,
+            TemplateLegParameters { quantity, ..TemplateLegParameters::default() },
+            description,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{StrategyEngine, StrategyKind};
+
+    fn inputs(price: f64) -> TemplateInputs {
+        TemplateInputs {
+            underlying_price: price,
+            size: 2.0,
+            width: 0.05,
+            underlying: None,
+        }
+    }
+
+    fn strikes(positions: &[Position]) -> Vec<f64> {
+        positions
+            .iter()
+            .filter_map(|pos| match pos {
+                Position::Option(option) => Some((option.strike_price * 1e6).round() / 1e6),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_instantiate_iron_condor() {
+        let template = TemplateEngine::builtin_templates()
+            .into_iter()
+            .find(|template| template.id == "builtin-short-iron-condor")
+            .unwrap();
+        let context = ValuationContext::new(30.0, 0.0, 0.5, 0.05);
+
+        let positions = TemplateEngine::instantiate(&template, &inputs(100.0), &context).unwrap();
+        assert_eq!(strikes(&positions), vec![90.0, 95.0, 105.0, 110.0]);
+        let quantities: Vec<f64> = positions.iter().map(|pos| pos.quantity()).collect();
+        assert_eq!(quantities, vec![2.0, -2.0, -2.0, 2.0]);
+        assert!(positions.iter().all(|pos| match pos {
+            Position::Option(option) => option.premium > 0.0,
+            _ => false,
+        }));
+
+        let report = StrategyEngine::classify(&positions);
+        assert_eq!(report.strategies[0].kind, StrategyKind::ShortIronCondor);
+        assert!(report.residuals.is_empty());
+    }
+
+    #[test]
+    fn test_user_template_round_trip() {
+        let positions = vec![
+            Position::Spot(SpotPosition::new(1.0, 100.0, None)),
+            Position::Option(OptionPosition::new(
+                OptionType::Call,
+                -1.0,
+                110.0,
+                2.0,
+                None,
+            )),
+        ];
+        let template = TemplateEngine::template_from_positions(
+            "custom-1",
+            "My Covered Call",
+            &positions,
+            100.0,
+        )
+        .unwrap();
+        assert_eq!(template.positions.len(), 2);
+
+        // Strikes follow the price the template is placed at
+        let context = ValuationContext::new(30.0, 0.0, 0.5, 0.05);
+        let moved = TemplateEngine::instantiate(&template, &inputs(200.0), &context).unwrap();
+        assert_eq!(strikes(&moved), vec![220.0]);
+        assert_eq!(moved[0].quantity(), 2.0);
+        assert_eq!(moved[1].quantity(), -2.0);
+    }
+}
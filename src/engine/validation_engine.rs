@@ -1,4 +1,7 @@
-use crate::models::{FuturesPosition, OptionPosition, Position, SpotPosition};
+use crate::models::{
+    BarrierPosition, DigitalPosition, FuturesPosition, OptionPosition, OptionType, Position,
+    SpotPosition, TradingFees,
+};
 
 /// Validation result for position inputs
 #[derive(Debug, Clone, PartialEq)]
@@ -39,6 +42,11 @@ impl ValidationEngine {
             Position::Spot(spot) => Self::validate_spot_position(spot, &mut result),
             Position::Option(option) => Self::validate_option_position(option, &mut result),
             Position::Futures(futures) => Self::validate_futures_position(futures, &mut result),
+            Position::Digital(digital) => Self::validate_digital_position(digital, &mut result),
+            Position::Barrier(barrier) => Self::validate_barrier_position(barrier, &mut result),
+        }
+        if let Some(fees) = position.fees() {
+            Self::validate_trading_fees(&fees, &mut result);
         }
 
         result
@@ -111,6 +119,25 @@ impl ValidationEngine {
 
     // === Private validation functions ===
 
+    /// Validate a position's own fee rates
+    fn validate_trading_fees(fees: &TradingFees, result: &mut ValidationResult) {
+        let rates = [
+            fees.entry_rate,
+            fees.exit_rate,
+            fees.exercise_rate,
+            fees.slippage,
+        ];
+        if rates.iter().any(|rate| !rate.is_finite() || *rate < 0.0) {
+            result.add_error("Fee rates and slippage cannot be negative".to_string());
+        }
+        if fees.premium_cap.is_some_and(|cap| cap < 0.0) {
+            result.add_error("Fee cap cannot be negative".to_string());
+        }
+        if rates.iter().any(|rate| *rate > 0.01) {
+            result.add_warning("Fee rate above 1% per trade".to_string());
+        }
+    }
+
     fn validate_spot_position(spot: &SpotPosition, result: &mut ValidationResult) {
         if spot.quantity == 0.0 {
             result.add_error("Quantity cannot be zero".to_string());
@@ -139,6 +166,13 @@ impl ValidationEngine {
             result.add_error("Premium cannot be negative".to_string());
         }
 
+        if option
+            .multiplier
+            .is_some_and(|multiplier| multiplier <= 0.0)
+        {
+            result.add_error("Contract multiplier must be positive".to_string());
+        }
+
         // Warnings
         if option.premium == 0.0 {
             result.add_warning("Zero premium option - verify this is correct".to_string());
@@ -151,6 +185,10 @@ impl ValidationEngine {
         if option.premium > option.strike_price * 0.5 {
             result.add_warning("Premium seems unusually high relative to strike price".to_string());
         }
+
+        if option.contract_multiplier() > 100000.0 {
+            result.add_warning("Very large contract multiplier detected".to_string());
+        }
     }
 
     fn validate_futures_position(futures: &FuturesPosition, result: &mut ValidationResult) {
@@ -172,16 +210,90 @@ impl ValidationEngine {
         }
     }
 
+    fn validate_digital_position(digital: &DigitalPosition, result: &mut ValidationResult) {
+        if digital.quantity == 0.0 {
+            result.add_error("Quantity cannot be zero".to_string());
+        }
+
+        if digital.strike_price <= 0.0 {
+            result.add_error("Strike price must be positive".to_string());
+        }
+
+        if digital.payout <= 0.0 {
+            result.add_error("Payout must be positive".to_string());
+        }
+
+        if digital.premium < 0.0 {
+            result.add_error("Premium cannot be negative".to_string());
+        }
+
+        // Warnings
+        if digital.premium >= digital.payout && digital.payout > 0.0 {
+            result.add_warning(
+                "Premium is not below the payout - the long side cannot profit".to_string(),
+            );
+        }
+
+        if digital.quantity.abs() > 1000.0 {
+            result.add_warning("Large digital position detected".to_string());
+        }
+    }
+
+    fn validate_barrier_position(barrier: &BarrierPosition, result: &mut ValidationResult) {
+        if barrier.quantity == 0.0 {
+            result.add_error("Quantity cannot be zero".to_string());
+        }
+
+        if barrier.strike_price <= 0.0 {
+            result.add_error("Strike price must be positive".to_string());
+        }
+
+        if barrier.barrier_level <= 0.0 {
+            result.add_error("Barrier level must be positive".to_string());
+        }
+
+        if barrier.rebate < 0.0 {
+            result.add_error("Rebate cannot be negative".to_string());
+        }
+
+        if barrier.premium < 0.0 {
+            result.add_error("Premium cannot be negative".to_string());
+        }
+
+        // Warnings
+        let knocked_out_before_paying = !barrier.barrier_type.is_knock_in()
+            && match barrier.option_type {
+                OptionType::Call => {
+                    barrier.barrier_type.is_up() && barrier.barrier_level <= barrier.strike_price
+                }
+                OptionType::Put => {
+                    !barrier.barrier_type.is_up() && barrier.barrier_level >= barrier.strike_price
+                }
+            };
+        if knocked_out_before_paying {
+            result.add_warning(
+                "Barrier is not beyond the strike - the option knocks out before it can pay"
+                    .to_string(),
+            );
+        }
+
+        if barrier.quantity.abs() > 1000.0 {
+            result.add_warning("Large barrier option position detected".to_string());
+        }
+    }
+
     fn validate_portfolio_risk(positions: &[Position], result: &mut ValidationResult) {
         // Check for excessive leverage
         let total_notional = positions
             .iter()
             .map(|pos| match pos {
                 Position::Spot(spot) => spot.quantity.abs() * spot.entry_price,
-                Position::Option(option) => option.quantity.abs() * option.strike_price,
+                Position::Option(option) => option.units().abs() * option.strike_price,
                 Position::Futures(futures) => {
                     futures.quantity.abs() * futures.entry_price * futures.contract_size
                 }
+                Position::Digital(digital) => digital.quantity.abs() * digital.payout,
+                Position::Barrier(barrier) => barrier.quantity.abs() * barrier.strike_price,
             })
             .sum::<f64>();
 
@@ -189,6 +301,23 @@ impl ValidationEngine {
             result.add_warning("Portfolio has very large notional exposure".to_string());
         }
 
+        // Payoffs are summed as if every option settled in the same currency
+        let mut settlement_currencies: Vec<&str> = positions
+            .iter()
+            .filter_map(|pos| match pos {
+                Position::Option(option) => option.settlement_currency.as_deref(),
+                _ => None,
+            })
+            .collect();
+        settlement_currencies.sort_unstable();
+        settlement_currencies.dedup();
+        if settlement_currencies.len() > 1 {
+            result.add_warning(format!(
+                "Options settle in different currencies ({}) - payoffs are added without conversion",
+                settlement_currencies.join(", ")
+            ));
+        }
+
         // Check for portfolio complexity
         if positions.len() > 10 {
             result.add_warning(
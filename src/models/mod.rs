@@ -7,12 +7,16 @@ pub mod import_data;
 
 // Re-export main types
 pub use position::{
-    FuturesPosition, OptionPosition, OptionType, Position, PositionType, SpotPosition,
+    BarrierPath, BarrierPosition, BarrierType, BarrierValuation, ContractType, DigitalPosition,
+    ExerciseStyle, FuturesPosition, MaintenanceTier, MarginMode, OptionPosition, OptionType,
+    Position, PositionType, PricingModel, SpotPosition, TradingFees,
 };
 pub use web3_data::{
     BackupLocation, CalculationSettings, ChartSettings, ChartTheme, ChartType, ConflictType,
     DataExchangeFormat, DisplaySettings, EnhancedPosition, ExportFormat, ExportSettings,
-    ExternalDataSource, LegendPosition, Portfolio, PortfolioSettings, PositionMetadata,
-    PositionOrder, PositionSource, PositionTemplate, StorageMetadata, StorageProvider,
-    SyncConflict, SyncMetadata,
+    ExternalDataSource, FeeSchedule, LegendPosition, Liquidity, MarginMethod, Portfolio,
+    PortfolioSettings, PositionMetadata, PositionOrder, PositionSource, PositionTemplate,
+    PriceDistribution, ScenarioGrid, StorageMetadata, StorageProvider, SyncConflict,
+    SyncMetadata, TemplateCategory, TemplateLegParameters, TemplatePosition, UnderlyingQuote,
+    VarSettings,
 };
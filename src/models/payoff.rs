@@ -1,4 +1,5 @@
-use super::position::{Position, SpotPosition, OptionPosition, FuturesPosition, OptionType};
+use super::position::{Position, SpotPosition, OptionPosition, FuturesPosition, DigitalPosition, BarrierPosition, OptionType};
+use crate::engine::PricingEngine;
 use serde::{Deserialize, Serialize};
 
 /// A single point on the payoff diagram
@@ -18,6 +19,8 @@ impl PayoffCalculator {
             Position::Spot(spot) => Self::calculate_spot_payoff(spot, underlying_price),
             Position::Option(option) => Self::calculate_option_payoff(option, underlying_price),
             Position::Futures(futures) => Self::calculate_futures_payoff(futures, underlying_price),
+            Position::Digital(digital) => Self::calculate_digital_payoff(digital, underlying_price),
+            Position::Barrier(barrier) => Self::calculate_barrier_payoff(barrier, underlying_price),
         }
     }
 
@@ -64,8 +67,8 @@ impl PayoffCalculator {
             OptionType::Put => (option.strike_price - underlying_price).max(0.0),
         };
 
-        // For long positions: P&L = Quantity * (Intrinsic Value - Premium)
-        // For short positions: P&L = Quantity * (Premium - Intrinsic Value) 
+        // For long positions: P&L = Quantity * Multiplier * (Intrinsic Value - Premium)
+        // For short positions: P&L = Quantity * Multiplier * (Premium - Intrinsic Value)
         // Note: quantity can be negative for short positions
         let net_payoff = if option.quantity >= 0.0 {
             // Long position: pay premium, receive intrinsic value
@@ -75,7 +78,7 @@ impl PayoffCalculator {
             option.premium - intrinsic_value
         };
 
-        option.quantity.abs() * net_payoff * if option.quantity >= 0.0 { 1.0 } else { -1.0 }
+        option.units().abs() * net_payoff * if option.quantity >= 0.0 { 1.0 } else { -1.0 }
     }
 
     /// Calculate futures position payoff
@@ -84,6 +87,37 @@ impl PayoffCalculator {
         futures.quantity * futures.contract_size * (underlying_price - futures.entry_price)
     }
 
+    /// Calculate digital position payoff
+    fn calculate_digital_payoff(digital: &DigitalPosition, underlying_price: f64) -> f64 {
+        let in_the_money = match digital.option_type {
+            OptionType::Call => underlying_price > digital.strike_price,
+            OptionType::Put => underlying_price < digital.strike_price,
+        };
+        let payout = if in_the_money { digital.payout } else { 0.0 };
+
+        // P&L = Quantity * (Payout if in the money - Premium)
+        digital.quantity * (payout - digital.premium)
+    }
+
+    /// Calculate barrier position payoff
+    fn calculate_barrier_payoff(barrier: &BarrierPosition, underlying_price: f64) -> f64 {
+        let intrinsic_value = match barrier.option_type {
+            OptionType::Call => (underlying_price - barrier.strike_price).max(0.0),
+            OptionType::Put => (barrier.strike_price - underlying_price).max(0.0),
+        };
+
+        // Without the path, assume the barrier was touched only if expiry is beyond it
+        let touched = PricingEngine::barrier_touched(barrier, underlying_price);
+        let value = if touched == barrier.barrier_type.is_knock_in() {
+            intrinsic_value
+        } else {
+            barrier.rebate
+        };
+
+        // P&L = Quantity * (Value at expiry - Premium)
+        barrier.quantity * (value - barrier.premium)
+    }
+
     /// Find break-even points for a portfolio
     pub fn find_breakeven_points(
         positions: &[Position],
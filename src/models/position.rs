@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 /// Types of financial positions supported
@@ -6,6 +7,8 @@ pub enum PositionType {
     Spot,
     Option,
     Futures,
+    Digital,
+    Barrier,
 }
 
 /// Option types (Call or Put)
@@ -15,12 +18,94 @@ pub enum OptionType {
     Put,
 }
 
+/// Option exercise style
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ExerciseStyle {
+    #[default]
+    European, // Exercise only at expiry
+    American, // Exercise any time up to expiry
+}
+
+/// Option pricing model used before expiry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PricingModel {
+    #[default]
+    BlackScholes, // Underlying is spot
+    Black76, // Underlying is the forward / futures price
+}
+
+/// Barrier direction and what touching it does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum BarrierType {
+    #[default]
+    UpAndOut, // Dies once the price rises to the barrier
+    UpAndIn,    // Comes alive once the price rises to the barrier
+    DownAndOut, // Dies once the price falls to the barrier
+    DownAndIn,  // Comes alive once the price falls to the barrier
+}
+
+/// Path assumed between today and expiry when drawing the expiry payoff
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum BarrierPath {
+    #[default]
+    Monotone, // Barrier counts as touched only if the expiry price is beyond it
+    Touched, // Barrier has already been touched
+}
+
+/// Pre-expiry valuation method for barrier options
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum BarrierValuation {
+    #[default]
+    ClosedForm, // Continuously monitored barrier (Reiner-Rubinstein)
+    MonteCarlo, // Daily monitored barrier, simulated
+}
+
+/// Futures contract margining
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ContractType {
+    #[default]
+    Linear, // USD-margined: P&L = size * (price - entry)
+    Inverse, // Coin-margined: P&L = size * (1/entry - 1/price) in the base coin
+}
+
+/// Futures margin mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum MarginMode {
+    #[default]
+    Cross, // Shares the wallet with the other cross positions
+    Isolated, // Only the margin posted for this position is at risk
+}
+
+/// Maintenance margin bracket, amounts in the margin currency (quote for linear, coin for inverse)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MaintenanceTier {
+    pub max_notional: Option<f64>, // Upper notional bound of the bracket (None = no cap)
+    pub rate: f64,                 // Maintenance margin rate (0.004 = 0.4%)
+    pub deduction: f64,            // Maintenance amount deducted (Binance "cum")
+}
+
+/// Trading costs of a position: fees and slippage on entry and on the projected exit
+///
+/// Spot and futures rates apply to the traded notional. Option-like rates apply
+/// to the underlying (the strike stands in for it at entry) and are capped at a
+/// fraction of the option price, as Binance does.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct TradingFees {
+    pub entry_rate: f64,          // Fee rate on the entry trade (0.0005 = 0.05%)
+    pub exit_rate: f64,           // Fee rate on a closing trade
+    pub exercise_rate: f64,       // Options held to expiry: exercise fee when in the money
+    pub premium_cap: Option<f64>, // Option-like legs: fee cap as a fraction of the option price
+    pub slippage: f64,            // Price slippage per traded side (0.001 = 0.1%)
+}
+
 /// Main position enum that contains all position types
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Position {
     Spot(SpotPosition),
     Option(OptionPosition),
     Futures(FuturesPosition),
+    Digital(DigitalPosition),
+    Barrier(BarrierPosition),
 }
 
 /// Spot position (direct ownership of underlying asset)
@@ -30,6 +115,10 @@ pub struct SpotPosition {
     pub entry_price: f64,    // Price at which position was entered
     pub description: String, // Optional description
     pub active: bool,        // Whether position is active (included in calculations)
+    #[serde(default)]
+    pub underlying: Option<String>, // Underlying symbol (None = the portfolio's primary underlying)
+    #[serde(default)]
+    pub fees: Option<TradingFees>, // None = portfolio fee schedule
 }
 
 /// Option position (Call or Put)
@@ -42,6 +131,22 @@ pub struct OptionPosition {
     pub expiry_price: f64,       // Current price at expiry (for calculation)
     pub description: String,     // Optional description
     pub active: bool,            // Whether position is active (included in calculations)
+    #[serde(default)]
+    pub implied_volatility: Option<f64>, // Annualized IV for pre-expiry valuation (0.6 = 60%)
+    #[serde(default)]
+    pub expiry: Option<DateTime<Utc>>, // Expiry timestamp (None = portfolio days to expiry)
+    #[serde(default)]
+    pub exercise_style: ExerciseStyle, // European or American (early exercise)
+    #[serde(default)]
+    pub pricing_model: Option<PricingModel>, // None = portfolio default model
+    #[serde(default)]
+    pub multiplier: Option<f64>, // Underlying units per contract (None = 1)
+    #[serde(default)]
+    pub settlement_currency: Option<String>, // Currency premium and payoff are paid in (None = quote)
+    #[serde(default)]
+    pub underlying: Option<String>, // Underlying symbol (None = the portfolio's primary underlying)
+    #[serde(default)]
+    pub fees: Option<TradingFees>, // None = portfolio fee schedule
 }
 
 /// Futures position
@@ -52,6 +157,94 @@ pub struct FuturesPosition {
     pub contract_size: f64,  // Size of each contract (multiplier)
     pub description: String, // Optional description
     pub active: bool,        // Whether position is active (included in calculations)
+    #[serde(default)]
+    pub expiry: Option<DateTime<Utc>>, // Expiry timestamp (None = perpetual / undated)
+    #[serde(default)]
+    pub contract_type: ContractType, // Linear (USD-M) or inverse (COIN-M)
+    #[serde(default)]
+    pub pnl_in_base: bool, // Inverse only: report P&L in the base coin instead of quote
+    #[serde(default)]
+    pub funding_rate: Option<f64>, // Perpetuals: funding rate per 8h interval (0.0001 = 0.01%)
+    #[serde(default)]
+    pub funding_horizon_days: Option<f64>, // Perpetuals: holding period funding accrues over
+    #[serde(default)]
+    pub leverage: Option<f64>, // None = fully collateralized, never liquidated
+    #[serde(default)]
+    pub margin_mode: MarginMode, // Cross or isolated margin
+    #[serde(default)]
+    pub maintenance_tiers: Vec<MaintenanceTier>, // Empty = exchange default brackets
+    #[serde(default)]
+    pub underlying: Option<String>, // Underlying symbol (None = the portfolio's primary underlying)
+    #[serde(default)]
+    pub fees: Option<TradingFees>, // None = portfolio fee schedule
+}
+
+/// Digital (cash-or-nothing) option position
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DigitalPosition {
+    pub option_type: OptionType, // Call pays above the strike, put pays below
+    pub quantity: f64,           // Number of contracts (positive = long, negative = short)
+    pub strike_price: f64,       // Strike price of the digital
+    pub payout: f64,             // Cash paid per contract when it finishes in the money
+    pub premium: f64,            // Premium paid/received per contract
+    pub description: String,     // Optional description
+    pub active: bool,            // Whether position is active (included in calculations)
+    #[serde(default)]
+    pub implied_volatility: Option<f64>, // Annualized IV for pre-expiry valuation
+    #[serde(default)]
+    pub expiry: Option<DateTime<Utc>>, // Expiry timestamp (None = portfolio days to expiry)
+    #[serde(default)]
+    pub underlying: Option<String>, // Underlying symbol (None = the portfolio's primary underlying)
+    #[serde(default)]
+    pub fees: Option<TradingFees>, // None = portfolio fee schedule
+}
+
+/// Barrier (knock-in / knock-out) option position
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BarrierPosition {
+    pub option_type: OptionType,   // Call or Put
+    pub barrier_type: BarrierType, // Up/down, in/out
+    pub quantity: f64,             // Number of contracts (positive = long, negative = short)
+    pub strike_price: f64,         // Strike price of the option
+    pub barrier_level: f64,        // Price that knocks the option in or out
+    pub rebate: f64,               // Paid at expiry per contract if knocked out / never knocked in
+    pub premium: f64,              // Premium paid/received per contract
+    pub description: String,       // Optional description
+    pub active: bool,              // Whether position is active (included in calculations)
+    #[serde(default)]
+    pub implied_volatility: Option<f64>, // Annualized IV for pre-expiry valuation
+    #[serde(default)]
+    pub expiry: Option<DateTime<Utc>>, // Expiry timestamp (None = portfolio days to expiry)
+    #[serde(default)]
+    pub path: BarrierPath, // Path assumption for the expiry payoff
+    #[serde(default)]
+    pub valuation: BarrierValuation, // Closed form or Monte Carlo before expiry
+    #[serde(default)]
+    pub underlying: Option<String>, // Underlying symbol (None = the portfolio's primary underlying)
+    #[serde(default)]
+    pub fees: Option<TradingFees>, // None = portfolio fee schedule
+}
+
+impl BarrierType {
+    /// Whether the barrier sits above the current price
+    pub fn is_up(self) -> bool {
+        matches!(self, BarrierType::UpAndOut | BarrierType::UpAndIn)
+    }
+
+    /// Whether touching the barrier activates the option
+    pub fn is_knock_in(self) -> bool {
+        matches!(self, BarrierType::UpAndIn | BarrierType::DownAndIn)
+    }
+
+    /// Display label ("Up-and-Out", ...)
+    pub fn label(self) -> &'static str {
+        match self {
+            BarrierType::UpAndOut => "Up-and-Out",
+            BarrierType::UpAndIn => "Up-and-In",
+            BarrierType::DownAndOut => "Down-and-Out",
+            BarrierType::DownAndIn => "Down-and-In",
+        }
+    }
 }
 
 impl Position {
@@ -61,6 +254,8 @@ impl Position {
             Position::Spot(_) => PositionType::Spot,
             Position::Option(_) => PositionType::Option,
             Position::Futures(_) => PositionType::Futures,
+            Position::Digital(_) => PositionType::Digital,
+            Position::Barrier(_) => PositionType::Barrier,
         }
     }
 
@@ -70,6 +265,55 @@ impl Position {
             Position::Spot(pos) => &pos.description,
             Position::Option(pos) => &pos.description,
             Position::Futures(pos) => &pos.description,
+            Position::Digital(pos) => &pos.description,
+            Position::Barrier(pos) => &pos.description,
+        }
+    }
+
+    /// Get the underlying symbol (None = the portfolio's primary underlying)
+    pub fn underlying(&self) -> Option<&str> {
+        match self {
+            Position::Spot(pos) => pos.underlying.as_deref(),
+            Position::Option(pos) => pos.underlying.as_deref(),
+            Position::Futures(pos) => pos.underlying.as_deref(),
+            Position::Digital(pos) => pos.underlying.as_deref(),
+            Position::Barrier(pos) => pos.underlying.as_deref(),
+        }
+    }
+
+    /// Set the underlying symbol, treating a blank symbol as the primary underlying
+    pub fn set_underlying(&mut self, underlying: Option<String>) {
+        let underlying = underlying
+            .map(|symbol| symbol.trim().to_uppercase())
+            .filter(|symbol| !symbol.is_empty());
+        match self {
+            Position::Spot(ref mut pos) => pos.underlying = underlying,
+            Position::Option(ref mut pos) => pos.underlying = underlying,
+            Position::Futures(ref mut pos) => pos.underlying = underlying,
+            Position::Digital(ref mut pos) => pos.underlying = underlying,
+            Position::Barrier(ref mut pos) => pos.underlying = underlying,
+        }
+    }
+
+    /// Get the position's own fees (None = portfolio fee schedule)
+    pub fn fees(&self) -> Option<TradingFees> {
+        match self {
+            Position::Spot(pos) => pos.fees,
+            Position::Option(pos) => pos.fees,
+            Position::Futures(pos) => pos.fees,
+            Position::Digital(pos) => pos.fees,
+            Position::Barrier(pos) => pos.fees,
+        }
+    }
+
+    /// Set the position's own fees
+    pub fn set_fees(&mut self, fees: Option<TradingFees>) {
+        match self {
+            Position::Spot(ref mut pos) => pos.fees = fees,
+            Position::Option(ref mut pos) => pos.fees = fees,
+            Position::Futures(ref mut pos) => pos.fees = fees,
+            Position::Digital(ref mut pos) => pos.fees = fees,
+            Position::Barrier(ref mut pos) => pos.fees = fees,
         }
     }
 
@@ -79,6 +323,8 @@ impl Position {
             Position::Spot(pos) => pos.quantity,
             Position::Option(pos) => pos.quantity,
             Position::Futures(pos) => pos.quantity,
+            Position::Digital(pos) => pos.quantity,
+            Position::Barrier(pos) => pos.quantity,
         }
     }
 
@@ -88,6 +334,8 @@ impl Position {
             Position::Spot(pos) => pos.active,
             Position::Option(pos) => pos.active,
             Position::Futures(pos) => pos.active,
+            Position::Digital(pos) => pos.active,
+            Position::Barrier(pos) => pos.active,
         }
     }
 
@@ -97,6 +345,8 @@ impl Position {
             Position::Spot(ref mut pos) => pos.active = !pos.active,
             Position::Option(ref mut pos) => pos.active = !pos.active,
             Position::Futures(ref mut pos) => pos.active = !pos.active,
+            Position::Digital(ref mut pos) => pos.active = !pos.active,
+            Position::Barrier(ref mut pos) => pos.active = !pos.active,
         }
     }
 
@@ -106,6 +356,8 @@ impl Position {
             Position::Spot(ref mut pos) => pos.active = active,
             Position::Option(ref mut pos) => pos.active = active,
             Position::Futures(ref mut pos) => pos.active = active,
+            Position::Digital(ref mut pos) => pos.active = active,
+            Position::Barrier(ref mut pos) => pos.active = active,
         }
     }
 }
@@ -120,6 +372,8 @@ impl SpotPosition {
                 format!("{} {} units @ {}", direction, quantity.abs(), entry_price)
             }),
             active: true, // Default to active
+            underlying: None,
+            fees: None,
         }
     }
 }
@@ -154,8 +408,26 @@ impl OptionPosition {
                 )
             }),
             active: true, // Default to active
+            implied_volatility: None,
+            expiry: None,
+            exercise_style: ExerciseStyle::European,
+            pricing_model: None,
+            multiplier: None,
+            settlement_currency: None,
+            underlying: None,
+            fees: None,
         }
     }
+
+    /// Underlying units per contract
+    pub fn contract_multiplier(&self) -> f64 {
+        self.multiplier.unwrap_or(1.0)
+    }
+
+    /// Signed exposure in underlying units (contracts times the multiplier)
+    pub fn units(&self) -> f64 {
+        self.quantity * self.contract_multiplier()
+    }
 }
 
 impl FuturesPosition {
@@ -180,6 +452,102 @@ impl FuturesPosition {
                 )
             }),
             active: true, // Default to active
+            expiry: None,
+            contract_type: ContractType::Linear,
+            pnl_in_base: false,
+            funding_rate: None,
+            funding_horizon_days: None,
+            leverage: None,
+            margin_mode: MarginMode::Cross,
+            maintenance_tiers: Vec::new(),
+            underlying: None,
+            fees: None,
+        }
+    }
+}
+
+impl DigitalPosition {
+    pub fn new(
+        option_type: OptionType,
+        quantity: f64,
+        strike_price: f64,
+        payout: f64,
+        premium: f64,
+        description: Option<String>,
+    ) -> Self {
+        Self {
+            option_type,
+            quantity,
+            strike_price,
+            payout,
+            premium,
+            description: description.unwrap_or_else(|| {
+                let direction = if quantity >= 0.0 { "Long" } else { "Short" };
+                let opt_type = match option_type {
+                    OptionType::Call => "Digital Call",
+                    OptionType::Put => "Digital Put",
+                };
+                format!(
+                    "{} {} {} @ Strike {} Payout {} Premium {}",
+                    direction,
+                    quantity.abs(),
+                    opt_type,
+                    strike_price,
+                    payout,
+                    premium
+                )
+            }),
+            active: true, // Default to active
+            implied_volatility: None,
+            expiry: None,
+            underlying: None,
+            fees: None,
+        }
+    }
+}
+
+impl BarrierPosition {
+    pub fn new(
+        option_type: OptionType,
+        barrier_type: BarrierType,
+        quantity: f64,
+        strike_price: f64,
+        barrier_level: f64,
+        premium: f64,
+        description: Option<String>,
+    ) -> Self {
+        Self {
+            option_type,
+            barrier_type,
+            quantity,
+            strike_price,
+            barrier_level,
+            rebate: 0.0,
+            premium,
+            description: description.unwrap_or_else(|| {
+                let direction = if quantity >= 0.0 { "Long" } else { "Short" };
+                let opt_type = match option_type {
+                    OptionType::Call => "Call",
+                    OptionType::Put => "Put",
+                };
+                format!(
+                    "{} {} {} {} @ Strike {} Barrier {} Premium {}",
+                    direction,
+                    quantity.abs(),
+                    barrier_type.label(),
+                    opt_type,
+                    strike_price,
+                    barrier_level,
+                    premium
+                )
+            }),
+            active: true, // Default to active
+            implied_volatility: None,
+            expiry: None,
+            path: BarrierPath::Monotone,
+            valuation: BarrierValuation::ClosedForm,
+            underlying: None,
+            fees: None,
         }
     }
 }
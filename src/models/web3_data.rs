@@ -2,7 +2,7 @@
 // This file defines the comprehensive data model for Web3 data sovereignty
 // Supporting multiple storage providers, sync, and full user control
 
-use crate::models::Position;
+use crate::models::{OptionType, Position, PricingModel, TradingFees};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -46,13 +46,17 @@ impl Portfolio {
     }
 
     pub fn add_position(&mut self, position: crate::models::Position) {
+        self.add_position_from(position, PositionSource::Manual);
+    }
+
+    pub fn add_position_from(&mut self, position: crate::models::Position, source: PositionSource) {
         let enhanced_position = EnhancedPosition {
             id: uuid::Uuid::new_v4().to_string(),
             position,
             metadata: PositionMetadata {
                 created_at: chrono::Utc::now(),
                 updated_at: chrono::Utc::now(),
-                source: PositionSource::Manual,
+                source,
                 tags: Vec::new(),
                 notes: None,
                 external_id: None,
@@ -62,6 +66,43 @@ impl Portfolio {
         self.update_timestamp();
     }
 
+    /// Indices of suggested hedge trades still awaiting the user's decision
+    pub fn proposed_hedge_indices(&self) -> Vec<usize> {
+        self.positions
+            .iter()
+            .enumerate()
+            .filter(|(_, ep)| ep.metadata.source.is_proposed_hedge())
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Switch the proposed hedge trades on and keep them as regular hedges
+    pub fn accept_proposed_hedges(&mut self) {
+        for index in self.proposed_hedge_indices() {
+            self.positions[index].position.set_active(true);
+            self.positions[index].metadata.source = PositionSource::Hedge { proposed: false };
+        }
+        self.update_timestamp();
+    }
+
+    /// Remove the proposed hedge trades; accepted hedges stay
+    pub fn discard_proposed_hedges(&mut self) {
+        self.positions
+            .retain(|ep| !ep.metadata.source.is_proposed_hedge());
+        self.update_timestamp();
+    }
+
+    /// Toggle a position on or off; switching a proposed hedge on accepts it
+    pub fn toggle_position(&mut self, index: usize) {
+        if let Some(ep) = self.positions.get_mut(index) {
+            ep.position.toggle_active();
+            if ep.position.is_active() && ep.metadata.source.is_proposed_hedge() {
+                ep.metadata.source = PositionSource::Hedge { proposed: false };
+            }
+            self.update_timestamp();
+        }
+    }
+
     pub fn update_timestamp(&mut self) {
         self.updated_at = chrono::Utc::now();
     }
@@ -96,6 +137,14 @@ pub enum PositionSource {
     BinanceAPI { account_id: String }, // Imported from Binance
     CSVImport { filename: String },    // Imported from CSV
     Template { template_id: String },  // From position template
+    Hedge { proposed: bool },          // From the hedge engine (proposed until accepted)
+}
+
+impl PositionSource {
+    /// Suggested hedge trade the user has neither accepted nor discarded
+    pub fn is_proposed_hedge(&self) -> bool {
+        matches!(self, PositionSource::Hedge { proposed: true })
+    }
 }
 
 // === Portfolio Settings ===
@@ -124,11 +173,69 @@ pub struct ChartSettings {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct CalculationSettings {
     pub auto_range: bool,       // Auto-calculate price range
     pub include_inactive: bool, // Include disabled positions
     pub precision: u8,          // Decimal precision
     pub currency: String,       // USD, EUR, etc.
+
+    // Pre-expiry valuation assumptions
+    pub underlying_price: Option<f64>, // Current underlying price (None = chart midpoint)
+    pub volatility: f64,               // Default annualized implied volatility (0.6 = 60%)
+    pub risk_free_rate: f64,           // Annualized continuously-compounded rate (0.05 = 5%)
+    pub days_to_expiry: f64,           // Calendar days from today until expiry
+    pub days_elapsed: f64,             // Valuation point for the T+n curve (days from today)
+    pub default_pricing_model: PricingModel, // Model for options without their own
+    pub futures_basis: f64,            // Annualized futures basis over spot (0.05 = 5% contango)
+
+    // Probability assumptions
+    pub price_distribution: PriceDistribution, // Price model for probability of profit / EV
+
+    // Margin
+    pub margin_method: MarginMethod, // Per-leg rules or scenario-based portfolio margin
+    pub account_equity: Option<f64>, // Account equity to check margin against
+
+    // Multi-underlying portfolios
+    pub underlying_quotes: Vec<UnderlyingQuote>, // Price and beta of each named underlying
+
+    // Trading costs
+    pub fee_schedule: FeeSchedule, // Fees for positions without their own
+
+    // Scenario analysis
+    pub scenario_grid: ScenarioGrid, // Price / volatility / time grid of the scenario report
+
+    // Value at Risk
+    pub var_settings: VarSettings, // Confidence levels, horizons and price history for VaR / ES
+
+    // Monte Carlo simulation
+    pub simulation_paths: usize, // Simulated price paths (0 = simulation off)
+    pub simulation_seed: u64,    // RNG seed for reproducible runs
+    pub simulation_horizon_days: Option<f64>, // Days from today (None = expiry)
+    pub jump_intensity: f64,     // Expected price jumps per year (0 = pure GBM)
+    pub jump_mean: f64,          // Mean log jump size (-0.1 = ~10% drop)
+    pub jump_volatility: f64,    // Standard deviation of the log jump size
+}
+
+impl CalculationSettings {
+    /// Quote of a named underlying
+    pub fn underlying_quote(&self, symbol: &str) -> Option<&UnderlyingQuote> {
+        self.underlying_quotes
+            .iter()
+            .find(|quote| quote.symbol == symbol)
+    }
+
+    /// Insert or replace the quote of a named underlying
+    pub fn set_underlying_quote(&mut self, quote: UnderlyingQuote) {
+        match self
+            .underlying_quotes
+            .iter_mut()
+            .find(|existing| existing.symbol == quote.symbol)
+        {
+            Some(existing) => *existing = quote,
+            None => self.underlying_quotes.push(quote),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -248,6 +355,125 @@ pub enum ExportFormat {
     PDF,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PriceDistribution {
+    Uniform,   // Every price in the chart range equally likely
+    Lognormal, // Lognormal around the current price (volatility, days to expiry)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum MarginMethod {
+    Standard,        // Sum of per-leg requirements
+    PortfolioMargin, // Worst loss across a price / volatility shock grid
+}
+
+/// Exchange fee rates applied to positions without their own fees (Binance defaults)
+///
+/// Off by default so portfolios saved before fees existed keep their gross P&L.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FeeSchedule {
+    pub enabled: bool,              // Off = gross P&L
+    pub spot_rate: f64,             // Spot trading fee (0.001 = 0.1%)
+    pub futures_maker_rate: f64,    // Futures maker fee (0.0002 = 0.02%)
+    pub futures_taker_rate: f64,    // Futures taker fee (0.0005 = 0.05%)
+    pub option_rate: f64,           // Option trading fee as a fraction of the underlying
+    pub option_exercise_rate: f64,  // Option exercise fee as a fraction of the underlying
+    pub option_fee_cap: f64,        // Option fees capped at this fraction of the option price
+    pub entry_liquidity: Liquidity, // Futures entry as maker or taker
+    pub exit_liquidity: Liquidity,  // Futures projected exit as maker or taker
+    pub slippage: f64,              // Price slippage per traded side (0.001 = 0.1%)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Liquidity {
+    Maker, // Resting limit order
+    #[default]
+    Taker, // Crosses the spread
+}
+
+impl FeeSchedule {
+    /// Fees of a position: its own, or this schedule's rates for its type
+    pub fn fees_for(&self, position: &Position) -> TradingFees {
+        if let Some(fees) = position.fees() {
+            return fees;
+        }
+        if !self.enabled {
+            return TradingFees::default();
+        }
+
+        let futures_rate = |liquidity| match liquidity {
+            Liquidity::Maker => self.futures_maker_rate,
+            Liquidity::Taker => self.futures_taker_rate,
+        };
+        let fees = TradingFees { slippage: self.slippage, ..TradingFees::default() };
+        match position {
+            Position::Spot(_) => TradingFees {
+                entry_rate: self.spot_rate,
+                exit_rate: self.spot_rate,
+                ..fees
+            },
+            Position::Futures(_) => TradingFees {
+                entry_rate: futures_rate(self.entry_liquidity),
+                exit_rate: futures_rate(self.exit_liquidity),
+                ..fees
+            },
+            Position::Option(_) => TradingFees {
+                entry_rate: self.option_rate,
+                exit_rate: self.option_rate,
+                exercise_rate: self.option_exercise_rate,
+                premium_cap: Some(self.option_fee_cap),
+                ..fees
+            },
+            // Cash-settled at expiry without an exercise fee
+            Position::Digital(_) | Position::Barrier(_) => TradingFees {
+                entry_rate: self.option_rate,
+                exit_rate: self.option_rate,
+                premium_cap: Some(self.option_fee_cap),
+                ..fees
+            },
+        }
+    }
+
+    /// Positions with their fees resolved against this schedule
+    pub fn apply(&self, positions: &[Position]) -> Vec<Position> {
+        positions
+            .iter()
+            .map(|position| {
+                let mut position = position.clone();
+                position.set_fees(Some(self.fees_for(&position)));
+                position
+            })
+            .collect()
+    }
+}
+
+/// Current price and beta of a named underlying (positions tagged with its symbol)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnderlyingQuote {
+    pub symbol: String,     // Matches Position::underlying
+    pub price: Option<f64>, // Current price (None = not quoted yet)
+    pub beta: f64,          // Return sensitivity to the primary underlying (1.0 = moves with it)
+}
+
+/// Axes of the scenario report: every combination is revalued
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScenarioGrid {
+    pub price_moves: Vec<f64>, // Relative underlying moves (-0.1 = 10% drop)
+    pub volatility_shifts: Vec<f64>, // Absolute IV shifts (0.05 = +5 vol points)
+    pub days_forward: Vec<f64>, // Days from today the portfolio is revalued at
+}
+
+/// Value-at-Risk report settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VarSettings {
+    pub confidence_levels: Vec<f64>, // VaR confidence levels (0.99 = 99%)
+    pub horizons_days: Vec<f64>,     // Holding periods in days (1 = 1-day VaR)
+    pub price_history: Vec<f64>, // Daily closes, oldest first (empty = volatility assumption only)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ConflictType {
     PositionModified,
@@ -258,7 +484,7 @@ pub enum ConflictType {
 }
 
 // === Position Templates ===
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PositionTemplate {
     pub id: String,
     pub name: String,
@@ -269,7 +495,7 @@ pub struct PositionTemplate {
     pub tags: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum TemplateCategory {
     BasicStrategies,
     Options,
@@ -278,13 +504,40 @@ pub enum TemplateCategory {
     Custom,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TemplatePosition {
     pub position_type: String,         // "Spot", "Option", "Futures"
-    pub parameters: serde_json::Value, // Flexible parameters
+    pub parameters: serde_json::Value, // Flexible parameters (see TemplateLegParameters)
     pub description: String,
 }
 
+/// Parameters of a template leg, relative to the price it is instantiated at
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TemplateLegParameters {
+    pub quantity: f64, // Signed, per unit of template size (positive = long)
+    pub option_type: Option<OptionType>, // Options only
+    pub strike_offset: f64, // Strike relative to the current price (0.05 = ATM+5%)
+    pub width_steps: f64, // Widths added to the strike offset (-1 = one width lower)
+}
+
+impl TemplatePosition {
+    /// Template leg from typed parameters
+    pub fn new(position_type: &str, parameters: TemplateLegParameters, description: &str) -> Self {
+        Self {
+            position_type: position_type.to_string(),
+            parameters: serde_json::to_value(parameters).unwrap_or_default(),
+            description: description.to_string(),
+        }
+    }
+
+    /// Typed view of the parameters
+    pub fn leg_parameters(&self) -> Result<TemplateLegParameters, String> {
+        serde_json::from_value(self.parameters.clone())
+            .map_err(|e| format!("Invalid template leg parameters: {}", e))
+    }
+}
+
 // === External Data Integration ===
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExternalDataSource {
@@ -382,6 +635,74 @@ impl Default for CalculationSettings {
             include_inactive: false,
             precision: 2,
             currency: "USD".to_string(),
+            underlying_price: None,
+            volatility: 0.6,
+            risk_free_rate: 0.05,
+            days_to_expiry: 30.0,
+            days_elapsed: 0.0,
+            default_pricing_model: PricingModel::BlackScholes,
+            futures_basis: 0.0,
+            price_distribution: PriceDistribution::Lognormal,
+            margin_method: MarginMethod::Standard,
+            account_equity: None,
+            underlying_quotes: Vec::new(),
+            fee_schedule: FeeSchedule::default(),
+            scenario_grid: ScenarioGrid::default(),
+            var_settings: VarSettings::default(),
+            simulation_paths: 5000,
+            simulation_seed: 42,
+            simulation_horizon_days: None,
+            jump_intensity: 0.0,
+            jump_mean: 0.0,
+            jump_volatility: 0.0,
+        }
+    }
+}
+
+impl Default for FeeSchedule {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            spot_rate: 0.001,
+            futures_maker_rate: 0.0002,
+            futures_taker_rate: 0.0005,
+            option_rate: 0.0003,
+            option_exercise_rate: 0.00015,
+            option_fee_cap: 0.1,
+            entry_liquidity: Liquidity::Taker,
+            exit_liquidity: Liquidity::Taker,
+            slippage: 0.0,
+        }
+    }
+}
+
+impl Default for ScenarioGrid {
+    fn default() -> Self {
+        Self {
+            price_moves: vec![-0.2, -0.1, -0.05, 0.0, 0.05, 0.1, 0.2],
+            volatility_shifts: vec![-0.1, 0.0, 0.1],
+            days_forward: vec![0.0, 1.0, 7.0],
+        }
+    }
+}
+
+impl Default for VarSettings {
+    fn default() -> Self {
+        Self {
+            confidence_levels: vec![0.95, 0.99],
+            horizons_days: vec![1.0, 10.0],
+            price_history: Vec::new(),
+        }
+    }
+}
+
+impl Default for TemplateLegParameters {
+    fn default() -> Self {
+        Self {
+            quantity: 1.0,
+            option_type: None,
+            strike_offset: 0.0,
+            width_steps: 0.0,
         }
     }
 }
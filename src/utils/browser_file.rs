@@ -1,6 +1,8 @@
 // File import/export utilities for browser
+use crate::engine::ScenarioReport;
 use crate::models::{web3_data, Portfolio};
 use crate::utils::web3_export_import::{export, file_utils, import};
+use crate::utils::LocalStorageManager;
 use js_sys::{Array, Uint8Array};
 use wasm_bindgen::prelude::*;
 use web_sys::{window, Blob, BlobPropertyBag, Document, File, FileReader, HtmlAnchorElement, Url};
@@ -104,24 +106,50 @@ impl BrowserFileManager {
             .ok_or_else(|| "File content is not a string".to_string())
     }
 
-    /// Export portfolio to file and trigger download
+    /// Export portfolio (with the saved user templates) to file and trigger download
     pub fn export_portfolio_to_file(
         portfolio: &Portfolio,
         format: crate::models::ExportFormat,
     ) -> Result<(), String> {
-        let (content, filename, mime_type) = export::generate_download_content(portfolio, format)?;
+        let templates = LocalStorageManager::load_templates().unwrap_or_default();
+        let (content, filename, mime_type) =
+            export::generate_download_content(portfolio, &templates, format)?;
         Self::download_file(&content, &filename, &mime_type)
     }
 
-    /// Import portfolio from file content
+    /// Export a scenario report to file and trigger download
+    pub fn export_scenario_report_to_file(
+        report: &ScenarioReport,
+        portfolio_name: &str,
+        format: crate::models::ExportFormat,
+    ) -> Result<(), String> {
+        let (content, filename, mime_type) =
+            export::generate_scenario_download_content(report, portfolio_name, format)?;
+        Self::download_file(&content, &filename, &mime_type)
+    }
+
+    /// Import portfolio from file content; templates it carries join the saved ones
     pub fn import_portfolio_from_content(content: &str) -> Result<Portfolio, String> {
-        match import::import_smart(content)? {
-            crate::utils::ImportResult::Portfolio(portfolio) => Ok(portfolio),
+        let portfolio = match import::import_smart(content)? {
+            crate::utils::ImportResult::Portfolio(portfolio) => portfolio,
             crate::utils::ImportResult::Positions(positions) => {
                 // Convert legacy positions to new portfolio format
-                Self::convert_positions_to_portfolio(positions)
+                Self::convert_positions_to_portfolio(positions)?
+            }
+        };
+
+        // Imported templates replace saved ones with the same id
+        let imported_templates = import::import_templates(content);
+        if !imported_templates.is_empty() {
+            let mut templates = LocalStorageManager::load_templates().unwrap_or_default();
+            for template in imported_templates {
+                templates.retain(|existing| existing.id != template.id);
+                templates.push(template);
             }
+            LocalStorageManager::save_templates(&templates)?;
         }
+
+        Ok(portfolio)
     }
 
     /// Convert legacy positions to new portfolio format
@@ -1,5 +1,5 @@
 // Local storage management for Web3 data sovereignty
-use crate::models::{Portfolio, Position};
+use crate::models::{Portfolio, Position, PositionTemplate};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
@@ -10,6 +10,7 @@ const PORTFOLIO_LIST_KEY: &str = "payoff_portfolios_v1";
 const CURRENT_PORTFOLIO_KEY: &str = "payoff_current_portfolio_v1";
 const APP_SETTINGS_KEY: &str = "payoff_app_settings_v1";
 const AUTO_SAVE_KEY: &str = "payoff_auto_save_v1";
+const TEMPLATES_KEY: &str = "payoff_templates_v1";
 
 /// Portfolio metadata for list management
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -342,6 +343,34 @@ impl LocalStorageManager {
         Ok(api_keys)
     }
 
+    /// Save user-defined position templates
+    pub fn save_templates(templates: &[PositionTemplate]) -> Result<(), String> {
+        let storage = Self::get_storage()?;
+
+        let templates_json = serde_json::to_string(templates)
+            .map_err(|e| format!("Failed to serialize templates: {}", e))?;
+
+        storage
+            .set_item(TEMPLATES_KEY, &templates_json)
+            .map_err(|_| "Failed to save templates to storage")?;
+
+        Ok(())
+    }
+
+    /// Load user-defined position templates (empty when none are saved)
+    pub fn load_templates() -> Result<Vec<PositionTemplate>, String> {
+        let storage = Self::get_storage()?;
+
+        match storage
+            .get_item(TEMPLATES_KEY)
+            .map_err(|_| "Failed to access storage")?
+        {
+            Some(templates_json) => serde_json::from_str(&templates_json)
+                .map_err(|e| format!("Failed to parse templates: {}", e)),
+            None => Ok(Vec::new()),
+        }
+    }
+
     /// Clear all data from local storage
     pub fn clear_all_data() -> Result<(), String> {
         let storage = Self::get_storage()?;
@@ -2,25 +2,34 @@
 // This module provides comprehensive data export/import functionality
 // with support for multiple formats, encryption, and metadata preservation
 
+use crate::engine::{ListedOption, ScenarioCell, ScenarioReport, StrategyEngine, StrategyReport};
 use crate::models::{
-    DataExchangeFormat, ExportFormat, ExternalDataSource, Portfolio, Position, PositionTemplate,
+    BarrierType, DataExchangeFormat, ExportFormat, ExternalDataSource, OptionType, Portfolio,
+    Position, PositionTemplate,
 };
 use chrono::Utc;
 use serde_json;
 
+/// Trailing CSV columns for option-like legs: Option_Type, Payout, Barrier_Type, Barrier, Rebate,
+/// Settlement_Currency (exports before Settlement_Currency carry the first five)
+const EXTENSION_COLUMNS: usize = 6;
+
 // === Enhanced Export Functions ===
 pub mod export {
     use super::*;
 
-    /// Export complete portfolio with all metadata and settings
-    pub fn export_portfolio_complete(portfolio: &Portfolio) -> Result<String, String> {
+    /// Export complete portfolio with all metadata and settings, plus the user's templates
+    pub fn export_portfolio_complete(
+        portfolio: &Portfolio,
+        templates: &[PositionTemplate],
+    ) -> Result<String, String> {
         let exchange_format = DataExchangeFormat {
             format_version: "1.0.0".to_string(),
             exported_at: Utc::now(),
             exported_by: format!("payoff-diagram-web-{}", env!("CARGO_PKG_VERSION")),
             checksum: None, // TODO: Implement checksums
             portfolios: vec![portfolio.clone()],
-            templates: Vec::new(),        // TODO: Load user templates
+            templates: templates.to_vec(),
             external_sources: Vec::new(), // TODO: Load external sources
             includes_settings: true,
             includes_metadata: true,
@@ -39,10 +48,11 @@ pub mod export {
     /// Export portfolio to specific format
     pub fn export_portfolio_format(
         portfolio: &Portfolio,
+        templates: &[PositionTemplate],
         format: ExportFormat,
     ) -> Result<String, String> {
         match format {
-            ExportFormat::JSON => export_portfolio_complete(portfolio),
+            ExportFormat::JSON => export_portfolio_complete(portfolio, templates),
             ExportFormat::CSV => export_to_csv(portfolio),
             ExportFormat::Excel => Err("Excel export not yet implemented".to_string()),
             ExportFormat::PDF => Err("PDF export not yet implemented".to_string()),
@@ -60,38 +70,51 @@ pub mod export {
         let mut csv_content = String::new();
 
         // CSV Header
-        csv_content.push_str("ID,Type,Quantity,Entry_Price,Strike_Price,Premium,Contract_Size,Description,Active,Created_At,Tags\n");
+        csv_content.push_str("ID,Type,Quantity,Entry_Price,Strike_Price,Premium,Contract_Size,Description,Active,Created_At,Tags,Strategy,Option_Type,Payout,Barrier_Type,Barrier,Rebate,Settlement_Currency\n");
+
+        // Strategy column: structure and role of each active leg
+        let positions: Vec<Position> = portfolio
+            .positions
+            .iter()
+            .map(|enhanced_pos| enhanced_pos.position.clone())
+            .collect();
+        let strategies = StrategyEngine::classify(&positions);
 
         // CSV Data
-        for enhanced_pos in &portfolio.positions {
+        for (index, enhanced_pos) in portfolio.positions.iter().enumerate() {
             let pos = &enhanced_pos.position;
             let meta = &enhanced_pos.metadata;
 
             let row = match pos {
                 Position::Spot(spot) => format!(
-                    "{},Spot,{},{},,,,,\"{}\",{},{},\"{}\"\n",
+                    "{},Spot,{},{},,,,,\"{}\",{},{},\"{}\",\"{}\",,,,,,\n",
                     enhanced_pos.id,
                     spot.quantity,
                     spot.entry_price,
                     spot.description.replace("\"", "\"\""),
                     spot.active,
                     meta.created_at.format("%Y-%m-%d %H:%M:%S"),
-                    meta.tags.join(";")
+                    meta.tags.join(";"),
+                    strategy_label(&strategies, index)
                 ),
                 Position::Option(opt) => format!(
-                    "{},Option,{},{},{},{},,,\"{}\",{},{},\"{}\"\n",
+                    "{},Option,{},{},{},{},{},,\"{}\",{},{},\"{}\",\"{}\",{},,,,,{}\n",
                     enhanced_pos.id,
                     opt.quantity,
                     opt.expiry_price,
                     opt.strike_price,
                     opt.premium,
+                    opt.contract_multiplier(),
                     opt.description.replace("\"", "\"\""),
                     opt.active,
                     meta.created_at.format("%Y-%m-%d %H:%M:%S"),
-                    meta.tags.join(";")
+                    meta.tags.join(";"),
+                    strategy_label(&strategies, index),
+                    format_option_type(opt.option_type),
+                    opt.settlement_currency.as_deref().unwrap_or("")
                 ),
                 Position::Futures(fut) => format!(
-                    "{},Futures,{},{},,,{},\"{}\",{},{},\"{}\"\n",
+                    "{},Futures,{},{},,,{},\"{}\",{},{},\"{}\",\"{}\",,,,,,\n",
                     enhanced_pos.id,
                     fut.quantity,
                     fut.entry_price,
@@ -99,7 +122,38 @@ pub mod export {
                     fut.description.replace("\"", "\"\""),
                     fut.active,
                     meta.created_at.format("%Y-%m-%d %H:%M:%S"),
-                    meta.tags.join(";")
+                    meta.tags.join(";"),
+                    strategy_label(&strategies, index)
+                ),
+                Position::Digital(digital) => format!(
+                    "{},Digital,{},,{},{},,,\"{}\",{},{},\"{}\",\"{}\",{},{},,,,\n",
+                    enhanced_pos.id,
+                    digital.quantity,
+                    digital.strike_price,
+                    digital.premium,
+                    digital.description.replace("\"", "\"\""),
+                    digital.active,
+                    meta.created_at.format("%Y-%m-%d %H:%M:%S"),
+                    meta.tags.join(";"),
+                    strategy_label(&strategies, index),
+                    format_option_type(digital.option_type),
+                    digital.payout
+                ),
+                Position::Barrier(barrier) => format!(
+                    "{},Barrier,{},,{},{},,,\"{}\",{},{},\"{}\",\"{}\",{},,{:?},{},{},\n",
+                    enhanced_pos.id,
+                    barrier.quantity,
+                    barrier.strike_price,
+                    barrier.premium,
+                    barrier.description.replace("\"", "\"\""),
+                    barrier.active,
+                    meta.created_at.format("%Y-%m-%d %H:%M:%S"),
+                    meta.tags.join(";"),
+                    strategy_label(&strategies, index),
+                    format_option_type(barrier.option_type),
+                    barrier.barrier_type,
+                    barrier.barrier_level,
+                    barrier.rebate
                 ),
             };
             csv_content.push_str(&row);
@@ -108,12 +162,97 @@ pub mod export {
         Ok(csv_content)
     }
 
+    /// Export a scenario report, one row per grid cell
+    pub fn export_scenario_report(
+        report: &ScenarioReport,
+        format: ExportFormat,
+    ) -> Result<String, String> {
+        match format {
+            ExportFormat::JSON => export_scenarios_to_json(report),
+            ExportFormat::CSV => Ok(export_scenarios_to_csv(report)),
+            ExportFormat::Excel => Err("Excel export not yet implemented".to_string()),
+            ExportFormat::PDF => Err("PDF export not yet implemented".to_string()),
+        }
+    }
+
+    /// Generate scenario report download content with proper MIME type
+    pub fn generate_scenario_download_content(
+        report: &ScenarioReport,
+        portfolio_name: &str,
+        format: ExportFormat,
+    ) -> Result<(String, String, String), String> {
+        let content = export_scenario_report(report, format.clone())?;
+        let filename = generate_filename(&format!("{}_scenarios", portfolio_name), &format);
+        let mime_type = get_mime_type(&format);
+
+        Ok((content, filename, mime_type))
+    }
+
+    fn export_scenarios_to_csv(report: &ScenarioReport) -> String {
+        let mut csv_content = String::new();
+
+        csv_content
+            .push_str("Days_Forward,Volatility_Shift,Price_Move,Underlying_Price,PnL,Worst\n");
+        for cell in &report.cells {
+            csv_content.push_str(&format!(
+                "{},{},{},{},{},{}\n",
+                cell.days_forward,
+                cell.volatility_shift,
+                cell.price_move,
+                cell.underlying_price,
+                cell.pnl,
+                report.worst == Some(*cell)
+            ));
+        }
+
+        csv_content
+    }
+
+    fn export_scenarios_to_json(report: &ScenarioReport) -> Result<String, String> {
+        let cell_json = |cell: &ScenarioCell| {
+            serde_json::json!({
+                "days_forward": cell.days_forward,
+                "volatility_shift": cell.volatility_shift,
+                "price_move": cell.price_move,
+                "underlying_price": cell.underlying_price,
+                "pnl": cell.pnl,
+            })
+        };
+        let report_json = serde_json::json!({
+            "exported_at": Utc::now(),
+            "base_price": report.base_price,
+            "grid": report.grid,
+            "cells": report.cells.iter().map(cell_json).collect::<Vec<_>>(),
+            "worst": report.worst.as_ref().map(cell_json),
+        });
+
+        serde_json::to_string_pretty(&report_json)
+            .map_err(|e| format!("Scenario export error: {}", e))
+    }
+
+    /// "Strategy: role" for legs of a recognized strategy, "Residual" for other active legs
+    fn strategy_label(report: &StrategyReport, index: usize) -> String {
+        match report.strategy_of(index) {
+            Some((strategy, role)) => format!("{}: {}", strategy.kind.label(), role),
+            None if report.residuals.contains(&index) => "Residual".to_string(),
+            None => String::new(),
+        }
+    }
+
+    fn format_option_type(option_type: OptionType) -> &'static str {
+        match option_type {
+            OptionType::Call => "Call",
+            OptionType::Put => "Put",
+        }
+    }
+
     /// Generate file download content with proper MIME type
     pub fn generate_download_content(
         portfolio: &Portfolio,
+        templates: &[PositionTemplate],
         format: ExportFormat,
     ) -> Result<(String, String, String), String> {
-        let content = export_portfolio_format(portfolio, format.clone())?;
+        let content = export_portfolio_format(portfolio, templates, format.clone())?;
         let filename = generate_filename(&portfolio.name, &format);
         let mime_type = get_mime_type(&format);
 
@@ -172,6 +311,13 @@ pub mod import {
             .ok_or_else(|| "No portfolios found in import data".to_string())
     }
 
+    /// Templates carried by an exchange-format export (empty for other formats)
+    pub fn import_templates(json_data: &str) -> Vec<PositionTemplate> {
+        serde_json::from_str::<DataExchangeFormat>(json_data)
+            .map(|exchange_format| exchange_format.templates)
+            .unwrap_or_default()
+    }
+
     /// Import positions only (legacy format)
     pub fn import_positions_only(json_data: &str) -> Result<Vec<Position>, String> {
         serde_json::from_str(json_data).map_err(|e| format!("Position import error: {}", e))
@@ -206,13 +352,20 @@ pub mod import {
             return Err("Empty CSV data".to_string());
         }
 
+        // Exports before Settlement_Currency end their rows one column earlier
+        let extension_columns = if lines[0].trim_end().ends_with("Settlement_Currency") {
+            EXTENSION_COLUMNS
+        } else {
+            EXTENSION_COLUMNS - 1
+        };
+
         // Skip header line
         for (line_num, line) in lines.iter().skip(1).enumerate() {
             if line.trim().is_empty() {
                 continue;
             }
 
-            let position = parse_csv_line(line)
+            let position = parse_csv_line(line, extension_columns)
                 .map_err(|e| format!("Error on line {}: {}", line_num + 2, e))?;
 
             positions.push(position);
@@ -221,7 +374,99 @@ pub mod import {
         Ok(positions)
     }
 
-    fn parse_csv_line(line: &str) -> Result<Position, String> {
+    /// Import daily closes (oldest first) from CSV; the close is the last column
+    /// and a header line is skipped
+    pub fn import_price_history_csv(csv_data: &str) -> Result<Vec<f64>, String> {
+        let mut closes = Vec::new();
+
+        for (line_num, line) in csv_data.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let field = line
+                .rsplit(',')
+                .next()
+                .unwrap_or("")
+                .trim()
+                .trim_matches('"');
+            let close = match field.parse::<f64>() {
+                Ok(close) => close,
+                Err(_) if line_num == 0 => continue, // Header
+                Err(_) => return Err(format!("Error on line {}: Invalid close", line_num + 1)),
+            };
+            if !close.is_finite() || close <= 0.0 {
+                return Err(format!(
+                    "Error on line {}: Close must be positive",
+                    line_num + 1
+                ));
+            }
+            closes.push(close);
+        }
+
+        if closes.len() < 2 {
+            return Err("At least two closes are needed for returns".to_string());
+        }
+        Ok(closes)
+    }
+
+    /// Import listed options from `Type,Strike,Premium` CSV rows (Type is Call or
+    /// Put); a header line is skipped
+    pub fn import_option_chain_csv(csv_data: &str) -> Result<Vec<ListedOption>, String> {
+        let mut chain = Vec::new();
+
+        for (line_num, line) in csv_data.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let fields: Vec<&str> = line
+                .split(',')
+                .map(|field| field.trim().trim_matches('"'))
+                .collect();
+            let Some(option_type) = fields.first().and_then(|field| parse_option_type(field))
+            else {
+                if line_num == 0 {
+                    continue; // Header
+                }
+                return Err(format!(
+                    "Error on line {}: Type must be Call or Put",
+                    line_num + 1
+                ));
+            };
+            if fields.len() < 3 {
+                return Err(format!(
+                    "Error on line {}: Expected Type,Strike,Premium",
+                    line_num + 1
+                ));
+            }
+
+            let strike = fields[1]
+                .parse::<f64>()
+                .ok()
+                .filter(|strike| strike.is_finite() && *strike > 0.0);
+            let premium = fields[2]
+                .parse::<f64>()
+                .ok()
+                .filter(|premium| premium.is_finite() && *premium >= 0.0);
+            match (strike, premium) {
+                (Some(strike), Some(premium)) => {
+                    chain.push(ListedOption { option_type, strike, premium })
+                }
+                (None, _) => return Err(format!("Error on line {}: Invalid strike", line_num + 1)),
+                (_, None) => {
+                    return Err(format!("Error on line {}: Invalid premium", line_num + 1))
+                }
+            }
+        }
+
+        if chain.is_empty() {
+            return Err("No listed options found".to_string());
+        }
+        Ok(chain)
+    }
+
+    fn parse_csv_line(line: &str, extension_columns: usize) -> Result<Position, String> {
         let fields: Vec<&str> = line.split(',').collect();
 
         if fields.len() < 9 {
@@ -230,39 +475,51 @@ pub mod import {
 
         let position_type = fields[1].trim();
         let quantity: f64 = fields[2].trim().parse().map_err(|_| "Invalid quantity")?;
-        let entry_price: f64 = fields[3]
-            .trim()
-            .parse()
-            .map_err(|_| "Invalid entry price")?;
         let description = fields[8].trim().trim_matches('"').to_string();
 
+        // Option_Type, Payout, Barrier_Type, Barrier, Rebate and Settlement_Currency
+        // trail every row (absent in older exports)
+        let extension = |column: usize| {
+            (fields.len() >= 9 + extension_columns && column < extension_columns)
+                .then(|| fields[fields.len() - extension_columns + column].trim())
+                .filter(|field| !field.is_empty())
+        };
+        let number = |index: usize, error: &str| -> Result<f64, String> {
+            fields[index].trim().parse().map_err(|_| error.to_string())
+        };
+        let option_type = extension(0).and_then(parse_option_type);
+
         match position_type {
             "Spot" => Ok(Position::Spot(crate::models::SpotPosition::new(
                 quantity,
-                entry_price,
+                number(3, "Invalid entry price")?,
                 Some(description),
             ))),
             "Option" => {
-                let strike_price: f64 = fields[4]
-                    .trim()
-                    .parse()
-                    .map_err(|_| "Invalid strike price")?;
-                let premium: f64 = fields[5].trim().parse().map_err(|_| "Invalid premium")?;
-
-                // Default to Call for now - CSV format could be enhanced
-                Ok(Position::Option(crate::models::OptionPosition::new(
-                    crate::models::OptionType::Call,
-                    quantity,
-                    strike_price,
-                    premium,
-                    Some(description),
-                )))
+                let strike_price = number(4, "Invalid strike price")?;
+                let premium = number(5, "Invalid premium")?;
+                // Older exports leave Contract_Size empty on option rows
+                let multiplier = match fields[6].trim() {
+                    "" => None,
+                    _ => Some(number(6, "Invalid contract size")?),
+                };
+
+                // Older exports have no option type column: default to Call
+                Ok(Position::Option(crate::models::OptionPosition {
+                    multiplier,
+                    settlement_currency: extension(5).map(str::to_uppercase),
+                    ..crate::models::OptionPosition::new(
+                        option_type.unwrap_or(OptionType::Call),
+                        quantity,
+                        strike_price,
+                        premium,
+                        Some(description),
+                    )
+                }))
             }
             "Futures" => {
-                let contract_size: f64 = fields[6]
-                    .trim()
-                    .parse()
-                    .map_err(|_| "Invalid contract size")?;
+                let entry_price = number(3, "Invalid entry price")?;
+                let contract_size = number(6, "Invalid contract size")?;
 
                 Ok(Position::Futures(crate::models::FuturesPosition::new(
                     quantity,
@@ -271,10 +528,72 @@ pub mod import {
                     Some(description),
                 )))
             }
+            "Digital" => {
+                let strike_price = number(4, "Invalid strike price")?;
+                let premium = number(5, "Invalid premium")?;
+                let option_type = option_type.ok_or("Missing digital option type")?;
+                let payout = extension(1)
+                    .and_then(|field| field.parse::<f64>().ok())
+                    .ok_or("Missing digital payout")?;
+
+                Ok(Position::Digital(crate::models::DigitalPosition::new(
+                    option_type,
+                    quantity,
+                    strike_price,
+                    payout,
+                    premium,
+                    Some(description),
+                )))
+            }
+            "Barrier" => {
+                let strike_price = number(4, "Invalid strike price")?;
+                let premium = number(5, "Invalid premium")?;
+                let option_type = option_type.ok_or("Missing barrier option type")?;
+                let barrier_type = extension(2)
+                    .and_then(parse_barrier_type)
+                    .ok_or("Missing barrier type")?;
+                let barrier_level = extension(3)
+                    .and_then(|field| field.parse::<f64>().ok())
+                    .ok_or("Missing barrier level")?;
+                let rebate = extension(4)
+                    .and_then(|field| field.parse::<f64>().ok())
+                    .unwrap_or(0.0);
+
+                Ok(Position::Barrier(crate::models::BarrierPosition {
+                    rebate,
+                    ..crate::models::BarrierPosition::new(
+                        option_type,
+                        barrier_type,
+                        quantity,
+                        strike_price,
+                        barrier_level,
+                        premium,
+                        Some(description),
+                    )
+                }))
+            }
             _ => Err(format!("Unknown position type: {}", position_type)),
         }
     }
 
+    fn parse_option_type(field: &str) -> Option<OptionType> {
+        match field {
+            "Call" => Some(OptionType::Call),
+            "Put" => Some(OptionType::Put),
+            _ => None,
+        }
+    }
+
+    fn parse_barrier_type(field: &str) -> Option<BarrierType> {
+        match field {
+            "UpAndOut" => Some(BarrierType::UpAndOut),
+            "UpAndIn" => Some(BarrierType::UpAndIn),
+            "DownAndOut" => Some(BarrierType::DownAndOut),
+            "DownAndIn" => Some(BarrierType::DownAndIn),
+            _ => None,
+        }
+    }
+
     fn is_compatible_version(version: &str) -> bool {
         // For now, accept any 1.x.x version
         version.starts_with("1.")
@@ -309,6 +628,9 @@ pub mod import {
                     if opt.premium < 0.0 {
                         errors.push(format!("Position {}: Invalid premium", index + 1));
                     }
+                    if opt.contract_multiplier() <= 0.0 {
+                        errors.push(format!("Position {}: Invalid contract size", index + 1));
+                    }
                 }
                 Position::Futures(fut) => {
                     if fut.entry_price <= 0.0 {
@@ -318,6 +640,28 @@ pub mod import {
                         errors.push(format!("Position {}: Invalid contract size", index + 1));
                     }
                 }
+                Position::Barrier(barrier) => {
+                    if barrier.strike_price <= 0.0 {
+                        errors.push(format!("Position {}: Invalid strike price", index + 1));
+                    }
+                    if barrier.barrier_level <= 0.0 {
+                        errors.push(format!("Position {}: Invalid barrier level", index + 1));
+                    }
+                    if barrier.premium < 0.0 || barrier.rebate < 0.0 {
+                        errors.push(format!("Position {}: Invalid premium", index + 1));
+                    }
+                }
+                Position::Digital(digital) => {
+                    if digital.strike_price <= 0.0 {
+                        errors.push(format!("Position {}: Invalid strike price", index + 1));
+                    }
+                    if digital.payout <= 0.0 {
+                        errors.push(format!("Position {}: Invalid payout", index + 1));
+                    }
+                    if digital.premium < 0.0 {
+                        errors.push(format!("Position {}: Invalid premium", index + 1));
+                    }
+                }
             }
         }
 
@@ -370,3 +714,42 @@ pub mod file_utils {
         base_size + position_size + metadata_size
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::*;
+
+    #[test]
+    fn test_csv_round_trip_keeps_settlement_currency() {
+        let inverse = OptionPosition {
+            settlement_currency: Some("BTC".to_string()),
+            multiplier: Some(0.1),
+            ..OptionPosition::new(OptionType::Put, -2.0, 60000.0, 0.015, None)
+        };
+        let mut portfolio = Portfolio::new("Coin".to_string(), String::new());
+        portfolio.add_position(Position::Option(inverse.clone()));
+        portfolio.add_position(Position::Spot(SpotPosition::new(1.0, 60000.0, None)));
+
+        let csv = export::export_portfolio_format(&portfolio, &[], ExportFormat::CSV).unwrap();
+        let positions = import::import_from_csv(&csv).unwrap();
+        let Position::Option(imported) = &positions[0] else {
+            panic!("expected an option, got {:?}", positions[0]);
+        };
+        assert_eq!(imported.settlement_currency.as_deref(), Some("BTC"));
+        assert_eq!(imported.option_type, OptionType::Put);
+        assert_eq!(imported.multiplier, Some(0.1));
+        assert_eq!((imported.quantity, imported.strike_price), (-2.0, 60000.0));
+        assert!(matches!(positions[1], Position::Spot(_)));
+
+        // Exports without the column still read their option type
+        let older = "ID,Type,Quantity,Entry_Price,Strike_Price,Premium,Contract_Size,Description,Active,Created_At,Tags,Strategy,Option_Type,Payout,Barrier_Type,Barrier,Rebate\n\
+            1,Option,1,0,100,5,,\"\",true,2024-01-01 00:00:00,\"\",\"\",Put,,,,\n";
+        let positions = import::import_from_csv(older).unwrap();
+        assert!(matches!(
+            &positions[0],
+            Position::Option(option)
+                if option.option_type == OptionType::Put && option.settlement_currency.is_none()
+        ));
+    }
+}